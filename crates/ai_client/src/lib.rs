@@ -1,9 +1,11 @@
 use anyhow::{anyhow, Context, Result};
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, Response};
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use reqwest::StatusCode;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
+use std::time::Duration;
 
 /// Configuration for talking to a local Ollama server.
 ///
@@ -13,17 +15,71 @@ use serde::{Deserialize, Serialize};
 pub struct OllamaClientConfig {
     pub base_url: String,
     pub model: String,
+    /// Extra attempts after a connection error or 5xx response (4xx is never retried).
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled on each further attempt.
+    pub retry_base_delay_ms: u64,
 }
 
 impl OllamaClientConfig {
     /// Loads config from env vars:
-    /// - `OLLAMA_BASE_URL` (default: `http://localhost:11434`)
-    /// - `OLLAMA_MODEL`    (default: `qwen2.5:7b`)
+    /// - `OLLAMA_BASE_URL`    (default: `http://localhost:11434`)
+    /// - `OLLAMA_MODEL`       (default: `qwen2.5:7b`)
+    /// - `OLLAMA_MAX_RETRIES` (default: `2`)
     pub fn from_env() -> Self {
-        let base_url = std::env::var("OLLAMA_BASE_URL")
-            .unwrap_or_else(|_| "http://localhost:11434".to_string());
-        let model = std::env::var("OLLAMA_MODEL").unwrap_or_else(|_| "qwen2.5:7b".to_string());
-        Self { base_url, model }
+        let base_url =
+            std::env::var("OLLAMA_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let model = std::env::var("OLLAMA_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+        let max_retries = std::env::var("OLLAMA_MAX_RETRIES")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_MAX_RETRIES);
+        Self {
+            base_url,
+            model,
+            max_retries,
+            ..Self::default()
+        }
+    }
+}
+
+/// Same defaults as `from_env` with no env vars set.
+impl Default for OllamaClientConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: DEFAULT_MODEL.to_string(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay_ms: DEFAULT_RETRY_BASE_DELAY_MS,
+        }
+    }
+}
+
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+const DEFAULT_MODEL: &str = "qwen2.5:7b";
+const DEFAULT_MAX_RETRIES: u32 = 2;
+const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// Per-call sampling options forwarded to Ollama.
+///
+/// Only `Some` fields are sent. The default matches `chat`: temperature 0.0
+/// and everything else left to the model's defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatOptions {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub num_ctx: Option<u32>,
+    pub seed: Option<i64>,
+}
+
+impl Default for ChatOptions {
+    fn default() -> Self {
+        Self {
+            temperature: Some(0.0),
+            top_p: None,
+            num_ctx: None,
+            seed: None,
+        }
     }
 }
 
@@ -37,6 +93,8 @@ pub struct OllamaClient {
     http: Client,
     base_url: Url,
     model: String,
+    max_retries: u32,
+    retry_base_delay: Duration,
 }
 
 impl OllamaClient {
@@ -55,19 +113,65 @@ impl OllamaClient {
             http,
             base_url,
             model: config.model,
+            max_retries: config.max_retries,
+            retry_base_delay: Duration::from_millis(config.retry_base_delay_ms),
         })
     }
 
     /// Generic helper for a single-turn chat call.
     pub fn chat(&self, system_prompt: &str, user_prompt: &str) -> Result<String> {
-        let endpoint = self
-            .base_url
-            .join("api/chat")
-            .context("Failed to build Ollama /api/chat URL")?;
+        self.chat_with_options(system_prompt, user_prompt, &ChatOptions::default())
+    }
+
+    /// Single-turn chat call with explicit sampling options.
+    pub fn chat_with_options(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        options: &ChatOptions,
+    ) -> Result<String> {
+        let request = self.chat_request(system_prompt, user_prompt, false, options);
+        let (endpoint, resp) = self.post_chat(&request)?;
+
+        let response: OllamaChatResponse = resp
+            .json()
+            .with_context(|| format!("Failed to parse JSON response from {endpoint}"))?;
+
+        let content = response
+            .message
+            .map(|m| m.content)
+            .ok_or_else(|| anyhow!("Ollama response had no message content"))?;
 
-        let request = OllamaChatRequest {
+        Ok(content.trim().to_string())
+    }
+
+    /// Like [`OllamaClient::chat`], but asks Ollama to stream the reply.
+    ///
+    /// `on_chunk` is called with each content fragment as it arrives; the
+    /// concatenated (trimmed) text is returned once the stream ends.
+    pub fn chat_streaming(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        on_chunk: impl FnMut(&str),
+    ) -> Result<String> {
+        let request = self.chat_request(system_prompt, user_prompt, true, &ChatOptions::default());
+        let (endpoint, resp) = self.post_chat(&request)?;
+
+        read_chat_stream(BufReader::new(resp), on_chunk)
+            .with_context(|| format!("Failed to read streamed response from {endpoint}"))
+    }
+
+    fn chat_request(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        stream: bool,
+        options: &ChatOptions,
+    ) -> OllamaChatRequest {
+        OllamaChatRequest {
             model: self.model.clone(),
-            stream: false,
+            stream,
             messages: vec![
                 Message {
                     role: "system".to_string(),
@@ -79,43 +183,90 @@ impl OllamaClient {
                 },
             ],
             options: Some(OllamaOptions {
-                temperature: Some(0.0),
+                temperature: options.temperature,
+                top_p: options.top_p,
+                num_ctx: options.num_ctx,
+                seed: options.seed,
             }),
-        };
+        }
+    }
+
+    fn post_chat(&self, request: &OllamaChatRequest) -> Result<(Url, Response)> {
+        let endpoint = self
+            .base_url
+            .join("api/chat")
+            .context("Failed to build Ollama /api/chat URL")?;
+
+        let mut attempt = 0;
+        loop {
+            let retries_left = attempt < self.max_retries;
+
+            match self.http.post(endpoint.clone()).json(request).send() {
+                Ok(resp) if resp.status().is_success() => return Ok((endpoint, resp)),
+                Ok(resp) if resp.status().is_server_error() && retries_left => {}
+                Ok(resp) => return Err(self.status_error(&endpoint, resp)),
+                Err(err) if (err.is_connect() || err.is_timeout()) && retries_left => {}
+                Err(err) => return Err(err).with_context(|| format!("POST {endpoint} failed")),
+            }
+
+            std::thread::sleep(self.retry_base_delay * 2u32.saturating_pow(attempt));
+            attempt += 1;
+        }
+    }
+
+    fn status_error(&self, endpoint: &Url, resp: Response) -> anyhow::Error {
+        let status = resp.status();
+        let body = resp.text().unwrap_or_default();
+
+        if status == StatusCode::NOT_FOUND {
+            return anyhow!(
+                "Ollama returned 404 for {endpoint}. This often means the model '{}' is not installed. Set OLLAMA_MODEL to an installed model (see GET /api/tags or `ollama list`) or run `ollama pull <model>`. Response body: {body}",
+                self.model
+            );
+        }
+
+        anyhow!("Ollama returned {status} for {endpoint}. Response body: {body}")
+    }
+
+    /// Returns `true` if the Ollama server answers `GET /api/tags`.
+    ///
+    /// A refused connection or a non-success status is reported as `false`
+    /// rather than an error, so callers can fail fast with their own message.
+    pub fn is_available(&self) -> Result<bool> {
+        let endpoint = self.tags_endpoint()?;
+
+        match self.http.get(endpoint).send() {
+            Ok(resp) => Ok(resp.status().is_success()),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Returns `true` if the configured model is installed on the server.
+    pub fn has_model(&self) -> Result<bool> {
+        let endpoint = self.tags_endpoint()?;
 
         let resp = self
             .http
-            .post(endpoint.clone())
-            .json(&request)
+            .get(endpoint.clone())
             .send()
-            .with_context(|| format!("POST {endpoint} failed"))?;
+            .with_context(|| format!("GET {endpoint} failed"))?;
 
         if !resp.status().is_success() {
             let status = resp.status();
-            let body = resp.text().unwrap_or_default();
-
-            if status == StatusCode::NOT_FOUND {
-                return Err(anyhow!(
-                    "Ollama returned 404 for {endpoint}. This often means the model '{}' is not installed. Set OLLAMA_MODEL to an installed model (see GET /api/tags or `ollama list`) or run `ollama pull <model>`. Response body: {body}",
-                    self.model
-                ));
-            }
-
-            return Err(anyhow!(
-                "Ollama returned {status} for {endpoint}. Response body: {body}"
-            ));
+            return Err(anyhow!("Ollama returned {status} for {endpoint}"));
         }
 
-        let response: OllamaChatResponse = resp
+        let tags: OllamaTagsResponse = resp
             .json()
             .with_context(|| format!("Failed to parse JSON response from {endpoint}"))?;
 
-        let content = response
-            .message
-            .map(|m| m.content)
-            .ok_or_else(|| anyhow!("Ollama response had no message content"))?;
+        Ok(tags_contain_model(&tags, &self.model))
+    }
 
-        Ok(content.trim().to_string())
+    fn tags_endpoint(&self) -> Result<Url> {
+        self.base_url
+            .join("api/tags")
+            .context("Failed to build Ollama /api/tags URL")
     }
 
     /// Translates a piece of text into `target_language`.
@@ -128,12 +279,121 @@ impl OllamaClient {
 
         self.chat(&system_prompt, text)
     }
+
+    /// Translates several texts in a single round trip.
+    ///
+    /// The texts are sent as a numbered list and the reply is parsed back by
+    /// number. If the model returns a different number of items, each text
+    /// is translated on its own with [`OllamaClient::translate_text`].
+    pub fn translate_batch(&self, texts: &[String], target_language: &str) -> Result<Vec<String>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let system_prompt = format!(
+            "You are a translation engine. The user sends a numbered list of texts, one per line. Translate each text to {target_language}. Reply with the same numbered list, one line per item, in the form `N. translation`, and nothing else."
+        );
+
+        let user_prompt = texts
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                format!(
+                    "{}. {}",
+                    i + 1,
+                    t.split_whitespace().collect::<Vec<_>>().join(" ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let reply = self.chat(&system_prompt, &user_prompt)?;
+        if let Some(translations) = parse_numbered_lines(&reply, texts.len()) {
+            return Ok(translations);
+        }
+
+        texts
+            .iter()
+            .map(|t| self.translate_text(t, target_language))
+            .collect()
+    }
 }
 
 /// Backwards compatible name.
 #[deprecated(note = "Use OllamaClient (this crate is Ollama-only)")]
 pub type OpenAiCompatibleClient = OllamaClient;
 
+/// Reads Ollama's newline-delimited JSON stream, forwarding each content
+/// fragment to `on_chunk` until a line reports `done: true`.
+fn read_chat_stream<R: BufRead>(reader: R, mut on_chunk: impl FnMut(&str)) -> Result<String> {
+    let mut full = String::new();
+
+    for line in reader.lines() {
+        let line = line.context("Failed to read stream line")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response: OllamaChatResponse = serde_json::from_str(&line)
+            .with_context(|| format!("Failed to parse stream line: {line}"))?;
+
+        if let Some(message) = response.message {
+            if !message.content.is_empty() {
+                on_chunk(&message.content);
+                full.push_str(&message.content);
+            }
+        }
+
+        if response.done {
+            break;
+        }
+    }
+
+    Ok(full.trim().to_string())
+}
+
+/// Parses a `N. text` list back into `expected` items ordered by `N`.
+///
+/// Returns `None` unless every number from 1 to `expected` appears exactly once.
+fn parse_numbered_lines(reply: &str, expected: usize) -> Option<Vec<String>> {
+    let mut items: Vec<Option<String>> = vec![None; expected];
+
+    for line in reply.lines() {
+        let line = line.trim();
+        let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            continue;
+        }
+
+        let rest = &line[digits..];
+        let Some(text) = rest.strip_prefix('.').or_else(|| rest.strip_prefix(')')) else {
+            continue;
+        };
+
+        let n: usize = line[..digits].parse().ok()?;
+        if n == 0 || n > expected || items[n - 1].is_some() {
+            return None;
+        }
+        items[n - 1] = Some(text.trim().to_string());
+    }
+
+    items.into_iter().collect()
+}
+
+/// Ollama lists untagged pulls as `<name>:latest`, so a configured model
+/// without a tag matches its `:latest` entry.
+fn tags_contain_model(tags: &OllamaTagsResponse, model: &str) -> bool {
+    let wanted = if model.contains(':') {
+        model.to_string()
+    } else {
+        format!("{model}:latest")
+    };
+
+    tags.models
+        .iter()
+        .any(|m| m.name == model || m.name == wanted)
+}
+
 fn validate_local_base_url(base_url: &str) -> Result<Url> {
     let url =
         Url::parse(base_url).with_context(|| format!("Invalid OLLAMA_BASE_URL: {base_url}"))?;
@@ -181,9 +441,243 @@ struct OllamaChatRequest {
 struct OllamaOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ctx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
 struct OllamaChatResponse {
     message: Option<Message>,
+    #[serde(default)]
+    done: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaTagsResponse {
+    #[serde(default)]
+    models: Vec<OllamaModelTag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaModelTag {
+    name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read, Write};
+    use std::net::TcpListener;
+
+    fn client_for(base_url: &str) -> OllamaClient {
+        OllamaClient::new(OllamaClientConfig {
+            base_url: base_url.to_string(),
+            model: "test-model".to_string(),
+            retry_base_delay_ms: 0,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn local_client() -> OllamaClient {
+        client_for("http://localhost:11434")
+    }
+
+    /// Serves one canned HTTP response per incoming connection, in order.
+    fn mock_server(responses: Vec<(u16, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        std::thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 8192];
+                let _ = stream.read(&mut buf);
+                let reply = format!(
+                    "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(reply.as_bytes()).unwrap();
+            }
+        });
+
+        format!("http://{addr}")
+    }
+
+    const CHAT_OK: &str = r#"{"message":{"role":"assistant","content":"ok"},"done":true}"#;
+
+    #[test]
+    fn chat_retries_server_errors_until_success() {
+        let base_url = mock_server(vec![(500, "loading"), (503, "loading"), (200, CHAT_OK)]);
+
+        assert_eq!(client_for(&base_url).chat("sys", "user").unwrap(), "ok");
+    }
+
+    #[test]
+    fn translate_batch_splits_numbered_reply() {
+        let base_url = mock_server(vec![(
+            200,
+            r#"{"message":{"role":"assistant","content":"1. Supermarket\n2. Taxi fare\n3. Refund"},"done":true}"#,
+        )]);
+
+        let texts = vec!["超市".to_string(), "打车".to_string(), "退款".to_string()];
+        let out = client_for(&base_url)
+            .translate_batch(&texts, "English")
+            .unwrap();
+
+        assert_eq!(out, vec!["Supermarket", "Taxi fare", "Refund"]);
+    }
+
+    #[test]
+    fn translate_batch_falls_back_to_single_calls_on_count_mismatch() {
+        let base_url = mock_server(vec![
+            (
+                200,
+                r#"{"message":{"role":"assistant","content":"1. Supermarket and taxi fare"},"done":true}"#,
+            ),
+            (
+                200,
+                r#"{"message":{"role":"assistant","content":"Supermarket"},"done":true}"#,
+            ),
+            (
+                200,
+                r#"{"message":{"role":"assistant","content":"Taxi fare"},"done":true}"#,
+            ),
+        ]);
+
+        let texts = vec!["超市".to_string(), "打车".to_string()];
+        let out = client_for(&base_url)
+            .translate_batch(&texts, "English")
+            .unwrap();
+
+        assert_eq!(out, vec!["Supermarket", "Taxi fare"]);
+    }
+
+    #[test]
+    fn parse_numbered_lines_rejects_gaps_and_duplicates() {
+        assert_eq!(
+            parse_numbered_lines("2) b\n1) a", 2),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert_eq!(parse_numbered_lines("1. a\n3. c", 2), None);
+        assert_eq!(parse_numbered_lines("1. a\n1. b", 2), None);
+    }
+
+    #[test]
+    fn chat_does_not_retry_client_errors() {
+        let base_url = mock_server(vec![(400, "bad request"), (200, CHAT_OK)]);
+
+        let err = client_for(&base_url).chat("sys", "user").unwrap_err();
+        assert!(err.to_string().contains("400"));
+    }
+
+    #[test]
+    fn chat_surfaces_last_error_when_retries_are_exhausted() {
+        let base_url = mock_server(vec![(500, "a"), (500, "b"), (500, "c")]);
+
+        let err = client_for(&base_url).chat("sys", "user").unwrap_err();
+        assert!(err.to_string().contains("500"));
+    }
+
+    #[test]
+    fn chat_request_uses_zero_temperature_by_default() {
+        let request = local_client().chat_request("sys", "user", false, &ChatOptions::default());
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(body["options"], serde_json::json!({ "temperature": 0.0 }));
+    }
+
+    #[test]
+    fn chat_request_includes_overridden_options() {
+        let options = ChatOptions {
+            temperature: Some(0.7),
+            seed: Some(42),
+            ..ChatOptions::default()
+        };
+        let request = local_client().chat_request("sys", "user", false, &options);
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(body["options"]["temperature"], 0.7);
+        assert_eq!(body["options"]["seed"], 42);
+        assert!(body["options"].get("top_p").is_none());
+        assert!(body["options"].get("num_ctx").is_none());
+    }
+
+    fn tags(body: &str) -> OllamaTagsResponse {
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn tags_contain_model_finds_installed_model() {
+        let listed = tags(
+            r#"{"models":[{"name":"qwen2.5:7b","size":4683087332},{"name":"llama3:latest"}]}"#,
+        );
+
+        assert!(tags_contain_model(&listed, "qwen2.5:7b"));
+        assert!(tags_contain_model(&listed, "llama3"));
+        assert!(tags_contain_model(&listed, "llama3:latest"));
+    }
+
+    #[test]
+    fn tags_contain_model_reports_missing_model() {
+        assert!(!tags_contain_model(
+            &tags(r#"{"models":[{"name":"llama3:latest"}]}"#),
+            "qwen2.5:7b"
+        ));
+        assert!(!tags_contain_model(&tags(r#"{"models":[]}"#), "llama3"));
+        assert!(!tags_contain_model(&tags("{}"), "llama3"));
+    }
+
+    #[test]
+    fn has_model_reads_installed_models_from_tags_endpoint() {
+        let base_url = mock_server(vec![
+            (200, r#"{"models":[{"name":"test-model:latest"}]}"#),
+            (200, r#"{"models":[{"name":"llama3:latest"}]}"#),
+        ]);
+        let client = client_for(&base_url);
+
+        assert!(client.has_model().unwrap());
+        assert!(!client.has_model().unwrap());
+    }
+
+    #[test]
+    fn is_available_reports_running_and_unreachable_servers() {
+        let base_url = mock_server(vec![(200, r#"{"models":[]}"#)]);
+        assert!(client_for(&base_url).is_available().unwrap());
+
+        // Bind then drop a listener so the port is known to refuse connections.
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = closed.local_addr().unwrap();
+        drop(closed);
+
+        assert!(!client_for(&format!("http://{addr}"))
+            .is_available()
+            .unwrap());
+    }
+
+    #[test]
+    fn read_chat_stream_forwards_each_fragment() {
+        let body = concat!(
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"Grocery\"},\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\" store\"},\"done\":false}\n",
+            "\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true}\n",
+        );
+
+        let mut chunks = Vec::new();
+        let full = read_chat_stream(Cursor::new(body), |c| chunks.push(c.to_string())).unwrap();
+
+        assert_eq!(chunks, vec!["Grocery", " store"]);
+        assert_eq!(full, "Grocery store");
+    }
+
+    #[test]
+    fn read_chat_stream_rejects_malformed_lines() {
+        let body = "{\"message\":{\"role\":\"assistant\",\"content\":\"ok\"}}\nnot json\n";
+        assert!(read_chat_stream(Cursor::new(body), |_| {}).is_err());
+    }
 }
@@ -1,6 +1,8 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use serde_json::{json, Value};
 use std::io::Read;
+use utils::StatusFilter;
 
 mod accounts;
 mod instruments;
@@ -12,7 +14,11 @@ pub const PARSER_NAME: &str = "alipay";
 pub struct AlipayCsvParser {
     pub account_id: String,
     pub currency: String,
-    pub only_successful: bool,
+    /// Which `交易状态` values become transactions. Pending rows are tagged
+    /// `"pending": true`.
+    pub status_filter: StatusFilter,
+    /// Rows dated before this are skipped.
+    pub since: Option<NaiveDate>,
 }
 
 impl AlipayCsvParser {
@@ -20,17 +26,29 @@ impl AlipayCsvParser {
         Self {
             account_id: account_id.into(),
             currency: "CNY".to_string(),
-            only_successful: true,
+            status_filter: StatusFilter::CompletedOnly,
+            since: None,
         }
     }
 
+    /// Skips rows dated before `since`, before their txn_id is computed.
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = Some(since);
+        self
+    }
+
     pub fn with_currency(mut self, currency: impl Into<String>) -> Self {
         self.currency = currency.into();
         self
     }
 
     pub fn with_only_successful(mut self, only_successful: bool) -> Self {
-        self.only_successful = only_successful;
+        self.status_filter = StatusFilter::from_only_completed(only_successful);
+        self
+    }
+
+    pub fn with_status_filter(mut self, status_filter: StatusFilter) -> Self {
+        self.status_filter = status_filter;
         self
     }
 
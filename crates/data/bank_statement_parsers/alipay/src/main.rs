@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use std::{env, fs::File, io::Read};
 
 use alipay::AlipayCsvParser;
@@ -24,18 +25,16 @@ impl utils::ParserContract for AlipayImportContract {
         &[utils::InputFormat::Csv]
     }
 
-    fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
-        let mut csv_file = File::open(input_file_path)
-            .with_context(|| format!("Cannot open {}", input_file_path))?;
-        let mut csv_buf = Vec::new();
-        csv_file.read_to_end(&mut csv_buf)?;
+    fn set_since(&mut self, since: NaiveDate) {
+        self.parser.since = Some(since);
+    }
 
-        let txns = self.parser.parse_reader(csv_buf.as_slice())?;
+    fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        utils::ConcurrentParserContract::parse_file_shared(self, input_file_path)
+    }
 
-        Ok(utils::ParsedEntities {
-            transactions: txns,
-            ..Default::default()
-        })
+    fn as_concurrent(&self) -> Option<&dyn utils::ConcurrentParserContract> {
+        Some(self)
     }
 
     fn finalize_entities(
@@ -51,7 +50,24 @@ impl utils::ParserContract for AlipayImportContract {
     }
 }
 
+impl utils::ConcurrentParserContract for AlipayImportContract {
+    fn parse_file_shared(&self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        let mut csv_file = File::open(input_file_path)
+            .with_context(|| format!("Cannot open {}", input_file_path))?;
+        let mut csv_buf = Vec::new();
+        csv_file.read_to_end(&mut csv_buf)?;
+
+        let txns = self.parser.parse_reader(csv_buf.as_slice())?;
+
+        Ok(utils::ParsedEntities {
+            transactions: txns,
+            ..Default::default()
+        })
+    }
+}
+
 fn main() -> Result<()> {
+    utils::init_console_logging();
     let args: Vec<String> = env::args().collect();
     let mut contract = AlipayImportContract::new();
     utils::run_parser_contract_cli(&mut contract, &args, "../../../../database")
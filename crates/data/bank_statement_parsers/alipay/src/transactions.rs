@@ -1,10 +1,10 @@
 use anyhow::{anyhow, Context, Result};
 use chrono::{NaiveDate, NaiveDateTime};
-use encoding_rs::GB18030;
+use encoding_rs::{Encoding, GB18030, UTF_16BE, UTF_16LE};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::io::Read;
-use utils::{build_transaction, TransactionInput};
+use utils::{build_transaction, mark_pending, RowStatus, TransactionInput};
 
 use crate::AlipayCsvParser;
 
@@ -18,6 +18,7 @@ struct AlipayColumns {
     idx_status: usize,
     idx_order: usize,
     idx_note: Option<usize>,
+    idx_channel: Option<usize>,
 }
 
 pub fn parse_transactions<R: Read>(parser: &AlipayCsvParser, mut reader: R) -> Result<Vec<Value>> {
@@ -45,6 +46,7 @@ pub fn parse_transactions<R: Read>(parser: &AlipayCsvParser, mut reader: R) -> R
         idx_status: find_col(&headers, "交易状态")?,
         idx_order: find_col(&headers, "交易订单号")?,
         idx_note: find_optional_col(&headers, "备注"),
+        idx_channel: find_optional_col(&headers, "收/付款方式"),
     };
 
     let mut out = Vec::new();
@@ -53,11 +55,15 @@ pub fn parse_transactions<R: Read>(parser: &AlipayCsvParser, mut reader: R) -> R
         let rec = rec.with_context(|| format!("CSV read error at row {}", row_idx + 2))?;
 
         let status = status_from_row(&rec, &cols);
-        if should_skip_row(parser, status) {
+        let row_status = classify_status(status);
+        if !parser.status_filter.keeps(row_status) {
             continue;
         }
 
         let date = date_from_row(&rec, &cols, row_idx + 2)?;
+        if parser.since.is_some_and(|since| date < since) {
+            continue;
+        }
         let amount = amount_from_row(&rec, &cols, row_idx + 2)?;
         let inout = inout_from_row(&rec, &cols);
         let item_raw = item_from_row(&rec, &cols);
@@ -65,6 +71,7 @@ pub fn parse_transactions<R: Read>(parser: &AlipayCsvParser, mut reader: R) -> R
         let category_raw = category_from_row(&rec, &cols);
         let semantic_blob = semantic_blob(item_raw, counterparty_raw, category_raw);
 
+        let refund = is_refund(status, category_raw);
         let (base_type, semantic_tag) =
             infer_base_type_and_semantic_tag(inout, amount, status, &semantic_blob);
         let description = field_description(
@@ -78,25 +85,53 @@ pub fn parse_transactions<R: Read>(parser: &AlipayCsvParser, mut reader: R) -> R
         let order_no = order_no_from_row(&rec, &cols);
         let txn_id = field_txn_id(parser, date, amount, &description, order_no, row_idx + 1);
 
-        out.push(build_transaction(&TransactionInput {
+        let mut txn = build_transaction(&TransactionInput {
             date: field_date(date),
             from_account_id: field_from_account_id(parser, &base_type, amount),
             to_account_id: field_to_account_id(parser, &base_type, amount),
             transaction_type: field_type(parser, &base_type, amount),
-            category: field_category(),
+            category: field_category(refund),
             amount: field_amount(amount),
             currency: field_currency(parser),
             description,
             description_en: field_description_en(),
             txn_id,
-        }));
+        });
+
+        if let Some(obj) = txn.as_object_mut() {
+            if refund && !order_no.is_empty() {
+                obj.insert(
+                    "related_order".to_string(),
+                    Value::String(order_no.to_string()),
+                );
+            }
+            if let Some(source) = field_funding_source(channel_from_row(&rec, &cols)) {
+                obj.insert(
+                    "funding_source".to_string(),
+                    Value::String(source.to_string()),
+                );
+            }
+        }
+        if row_status == RowStatus::Pending {
+            mark_pending(&mut txn);
+        }
+
+        out.push(txn);
     }
 
     Ok(out)
 }
 
-fn should_skip_row(parser: &AlipayCsvParser, status: &str) -> bool {
-    parser.only_successful && !(status == "交易成功" || status == "退款成功")
+/// `交易成功`/`退款成功` are settled; `等待付款`, `等待确认收货`, `处理中`
+/// and similar are still pending. Anything else (e.g. `交易关闭`) is neither.
+fn classify_status(status: &str) -> RowStatus {
+    match status {
+        "交易成功" | "退款成功" => RowStatus::Completed,
+        s if s.contains("等待") || s.contains("处理中") || s.starts_with('待') => {
+            RowStatus::Pending
+        }
+        _ => RowStatus::Other,
+    }
 }
 
 fn status_from_row<'a>(rec: &'a csv::StringRecord, cols: &AlipayColumns) -> &'a str {
@@ -147,10 +182,48 @@ fn category_from_row<'a>(rec: &'a csv::StringRecord, cols: &AlipayColumns) -> &'
     rec.get(cols.idx_category).unwrap_or("").trim()
 }
 
+fn channel_from_row<'a>(rec: &'a csv::StringRecord, cols: &AlipayColumns) -> &'a str {
+    cols.idx_channel
+        .and_then(|i| rec.get(i))
+        .unwrap_or("")
+        .trim()
+}
+
+/// Classifies the 收/付款方式 channel. Bank-card payments are tagged
+/// `bank_card` so they can be reconciled against the card's own statement;
+/// they are still regular expenses/income, never internal transfers.
+fn field_funding_source(channel: &str) -> Option<&'static str> {
+    if channel.is_empty() || channel == "/" {
+        return None;
+    }
+
+    if channel.contains("余额宝") {
+        Some("yuebao")
+    } else if channel.contains("余额") {
+        Some("balance")
+    } else if channel.contains("银行")
+        || channel.contains("储蓄卡")
+        || channel.contains("信用卡")
+        || channel.contains("借记卡")
+    {
+        Some("bank_card")
+    } else if channel.contains("花呗") {
+        Some("huabei")
+    } else {
+        None
+    }
+}
+
 fn semantic_blob(item_raw: &str, counterparty_raw: &str, category_raw: &str) -> String {
     format!("{} {} {}", item_raw, counterparty_raw, category_raw)
 }
 
+/// Refunds reuse the original purchase's 交易订单号 and are flagged either in
+/// 交易状态 or in 交易分类, independently of the 收/支 cell.
+fn is_refund(status: &str, category_raw: &str) -> bool {
+    status.contains("退款") || category_raw.contains("退款")
+}
+
 fn infer_base_type_and_semantic_tag(
     inout: &str,
     amount: f64,
@@ -178,7 +251,7 @@ fn infer_base_type_and_semantic_tag(
 
     let mut semantic_tag: Option<&'static str> = None;
 
-    if status == "退款成功" || semantic_blob.contains("退款") {
+    if status.contains("退款") || semantic_blob.contains("退款") {
         txn_type = "income";
         semantic_tag = Some("退款");
     } else if semantic_blob.contains("充值") {
@@ -239,8 +312,12 @@ fn field_currency(parser: &AlipayCsvParser) -> String {
     parser.currency.clone()
 }
 
-fn field_category() -> String {
-    "uncategorized".to_string()
+fn field_category(refund: bool) -> String {
+    if refund {
+        "refund".to_string()
+    } else {
+        "uncategorized".to_string()
+    }
 }
 
 fn normalized_accounts_and_type(
@@ -329,10 +406,38 @@ fn decode_text_lossy(bytes: &[u8]) -> String {
         }
     }
 
+    if let Some(encoding) = sniff_utf16(bytes) {
+        let (decoded, _, _) = encoding.decode(bytes);
+        return decoded.into_owned();
+    }
+
     let (decoded, _, _) = GB18030.decode(bytes);
     decoded.into_owned()
 }
 
+/// Guesses BOM-less UTF-16 from where the null bytes fall: ASCII characters
+/// (digits, commas, newlines) leave a zero high byte at odd offsets in
+/// UTF-16LE and at even offsets in UTF-16BE. GB18030 and UTF-8 text never
+/// contain null bytes.
+fn sniff_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(512)];
+    let zeros_even = sample.iter().step_by(2).filter(|b| **b == 0).count();
+    let zeros_odd = sample
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|b| **b == 0)
+        .count();
+
+    if zeros_odd > zeros_even && zeros_odd * 8 >= sample.len() / 2 {
+        Some(UTF_16LE)
+    } else if zeros_even > zeros_odd && zeros_even * 8 >= sample.len() / 2 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
 fn slice_to_csv_table(decoded: &str) -> Result<String> {
     let needle = "交易时间,";
     let start = decoded
@@ -397,3 +502,132 @@ fn make_alipay_txn_id(
 
     format!("ALIPAY-{}", hex::encode(&hash[..12]))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "交易时间,交易分类,交易对方,对方账号,商品说明,收/支,金额,收/付款方式,交易状态,交易订单号,商家订单号,备注";
+
+    fn parse(rows: &[&str]) -> Vec<Value> {
+        let csv = format!("支付宝交易明细\n{}\n{}\n", HEADER, rows.join("\n"));
+        parse_transactions(&AlipayCsvParser::new("ALIPAY"), csv.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn refund_row_is_income_with_related_order() {
+        let txns = parse(&[
+            "2026-03-02 10:00:00,日用百货,某商家,/,保温杯,不计收支,25.00,余额宝,退款成功,2026030122001100\t,/,",
+        ]);
+
+        assert_eq!(txns.len(), 1);
+        let refund = &txns[0];
+        assert_eq!(refund["type"], "income");
+        assert_eq!(refund["category"], "refund");
+        assert_eq!(refund["to_account_id"], "ALIPAY");
+        assert_eq!(refund["related_order"], "2026030122001100");
+    }
+
+    #[test]
+    fn funding_source_distinguishes_yuebao_from_bank_card() {
+        let txns = parse(&[
+            "2026-03-01 09:00:00,餐饮美食,某餐厅,/,午餐,支出,38.00,余额宝,交易成功,2026030122001101\t,/,",
+            "2026-03-01 19:00:00,餐饮美食,某餐厅,/,晚餐,支出,88.00,招商银行储蓄卡(8866),交易成功,2026030122001102\t,/,",
+        ]);
+
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0]["funding_source"], "yuebao");
+
+        let card = &txns[1];
+        assert_eq!(card["funding_source"], "bank_card");
+        assert_eq!(card["type"], "expense");
+        assert_eq!(card["to_account_id"], "EXTERNAL_PAYEE");
+    }
+
+    fn utf16_bytes(text: &str, little_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|unit| {
+                if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                }
+            })
+            .collect()
+    }
+
+    fn tiny_export() -> String {
+        format!(
+            "支付宝交易明细\n{}\n2026-03-01 09:00:00,餐饮美食,某餐厅,/,午餐,支出,38.00,余额宝,交易成功,2026030122001101,/,\n",
+            HEADER
+        )
+    }
+
+    #[test]
+    fn decode_text_lossy_handles_bomless_utf16le() {
+        let decoded = decode_text_lossy(&utf16_bytes(&tiny_export(), true));
+        assert!(slice_to_csv_table(&decoded)
+            .unwrap()
+            .starts_with("交易时间,"));
+
+        let txns = parse_transactions(
+            &AlipayCsvParser::new("ALIPAY"),
+            utf16_bytes(&tiny_export(), true).as_slice(),
+        )
+        .unwrap();
+        assert_eq!(txns.len(), 1);
+    }
+
+    #[test]
+    fn decode_text_lossy_handles_bomless_utf16be() {
+        let decoded = decode_text_lossy(&utf16_bytes(&tiny_export(), false));
+        assert!(slice_to_csv_table(&decoded).is_ok());
+    }
+
+    #[test]
+    fn decode_text_lossy_keeps_gb18030_path() {
+        let export = tiny_export();
+        let (encoded, _, _) = GB18030.encode(&export);
+        let decoded = decode_text_lossy(&encoded);
+        assert!(slice_to_csv_table(&decoded).is_ok());
+    }
+
+    #[test]
+    fn regular_purchase_has_no_related_order() {
+        let txns = parse(&[
+            "2026-03-01 09:00:00,日用百货,某商家,/,保温杯,支出,59.00,余额宝,交易成功,2026030122001100\t,/,",
+        ]);
+
+        assert_eq!(txns.len(), 1);
+        assert_eq!(txns[0]["type"], "expense");
+        assert_eq!(txns[0]["category"], "uncategorized");
+        assert!(txns[0].get("related_order").is_none());
+    }
+
+    #[test]
+    fn status_filter_controls_pending_rows() {
+        use utils::StatusFilter;
+
+        let csv = format!(
+            "支付宝交易明细\n{}\n{}\n{}\n{}\n",
+            HEADER,
+            "2026-03-01 09:00:00,餐饮美食,某餐厅,/,午餐,支出,38.00,余额宝,交易成功,2026030122001101\t,/,",
+            "2026-03-02 09:00:00,日用百货,某商家,/,保温杯,支出,25.00,余额宝,等待确认收货,2026030222001102\t,/,",
+            "2026-03-03 09:00:00,日用百货,某商家,/,雨伞,支出,30.00,余额宝,交易关闭,2026030322001103\t,/,"
+        );
+        let parse = |filter| {
+            let parser = AlipayCsvParser::new("ALIPAY").with_status_filter(filter);
+            parse_transactions(&parser, csv.as_bytes()).unwrap()
+        };
+
+        let completed = parse(StatusFilter::CompletedOnly);
+        assert_eq!(completed.len(), 1);
+        assert!(completed[0].get("pending").is_none());
+
+        let with_pending = parse(StatusFilter::IncludePending);
+        assert_eq!(with_pending.len(), 2);
+        assert_eq!(with_pending[1]["pending"], true);
+
+        assert_eq!(parse(StatusFilter::All).len(), 3);
+    }
+}
@@ -0,0 +1,39 @@
+use serde_json::Value;
+use utils::{build_account, AccountInput};
+
+use crate::Camt053Parser;
+
+pub fn create_accounts(parser: &Camt053Parser) -> Vec<Value> {
+    vec![build_account(&AccountInput {
+        account_id: field_account_id(parser),
+        institution: field_institution(parser),
+        country: field_country(),
+        iban: field_iban(),
+        bic: field_bic(),
+        is_active: field_is_active(),
+    })]
+}
+
+fn field_account_id(parser: &Camt053Parser) -> String {
+    parser.account_id.clone()
+}
+
+fn field_institution(parser: &Camt053Parser) -> String {
+    parser.institution.clone()
+}
+
+fn field_country() -> Option<String> {
+    None
+}
+
+fn field_iban() -> Option<String> {
+    None
+}
+
+fn field_bic() -> Option<String> {
+    None
+}
+
+fn field_is_active() -> bool {
+    true
+}
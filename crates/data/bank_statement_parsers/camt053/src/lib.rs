@@ -0,0 +1,63 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_json::Value;
+
+mod accounts;
+mod transactions;
+
+pub const PARSER_NAME: &str = "camt053";
+
+/// Parser for ISO 20022 CAMT.053 bank-to-customer statements.
+pub struct Camt053Parser {
+    pub account_id: String,
+    pub institution: String,
+    /// Rows dated before this are skipped.
+    pub since: Option<NaiveDate>,
+}
+
+impl Camt053Parser {
+    pub fn new(account_id: impl Into<String>) -> Self {
+        Self {
+            account_id: account_id.into(),
+            institution: "CAMT.053 import".to_string(),
+            since: None,
+        }
+    }
+
+    /// Skips rows dated before `since`, before their txn_id is computed.
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn with_institution(mut self, institution: impl Into<String>) -> Self {
+        self.institution = institution.into();
+        self
+    }
+
+    pub fn create_accounts(&self) -> Vec<Value> {
+        accounts::create_accounts(self)
+    }
+
+    pub fn parse_file(&self, xml_path: &str) -> Result<Vec<Value>> {
+        transactions::parse_transactions(self, xml_path)
+    }
+
+    pub fn parse_str(&self, content: &str) -> Result<Vec<Value>> {
+        transactions::parse_content(self, content)
+    }
+}
+
+pub fn merge_transactions_into_template(
+    template: Value,
+    new_txns: Vec<Value>,
+) -> Result<(Value, utils::transactions::MergeStats)> {
+    utils::merge_transactions_with_deduplication(template, new_txns)
+}
+
+pub fn merge_accounts_into_template(
+    template: Value,
+    new_accounts: Vec<Value>,
+) -> Result<(Value, utils::accounts::MergeStats)> {
+    utils::merge_accounts_with_deduplication(template, new_accounts)
+}
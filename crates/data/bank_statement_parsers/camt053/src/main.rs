@@ -0,0 +1,77 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::env;
+
+use camt053_parser::Camt053Parser;
+
+struct Camt053ImportContract {
+    parser: Camt053Parser,
+}
+
+impl Camt053ImportContract {
+    fn new() -> Self {
+        Self {
+            // Change the account id and institution to the bank being imported.
+            parser: Camt053Parser::new("CAMT053_ACCOUNT").with_institution("CAMT.053 import"),
+        }
+    }
+}
+
+impl utils::ParserContract for Camt053ImportContract {
+    fn parser_name(&self) -> &'static str {
+        camt053_parser::PARSER_NAME
+    }
+
+    fn supported_input_formats(&self) -> &'static [utils::InputFormat] {
+        &[utils::InputFormat::Xml]
+    }
+
+    fn set_since(&mut self, since: NaiveDate) {
+        self.parser.since = Some(since);
+    }
+
+    fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        utils::ConcurrentParserContract::parse_file_shared(self, input_file_path)
+    }
+
+    fn as_concurrent(&self) -> Option<&dyn utils::ConcurrentParserContract> {
+        Some(self)
+    }
+
+    fn finalize_entities(
+        &mut self,
+        mut entities: utils::ParsedEntities,
+    ) -> Result<utils::ParsedEntities> {
+        entities.accounts = self.parser.create_accounts();
+        Ok(entities)
+    }
+
+    fn pipeline_profile(&self) -> utils::PipelineProfile {
+        utils::PipelineProfile::Default
+    }
+}
+
+impl utils::ConcurrentParserContract for Camt053ImportContract {
+    fn parse_file_shared(&self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        let txns = self
+            .parser
+            .parse_file(input_file_path)
+            .with_context(|| format!("Failed parsing {}", input_file_path))?;
+
+        Ok(utils::ParsedEntities {
+            transactions: txns,
+            ..Default::default()
+        })
+    }
+}
+
+fn main() -> Result<()> {
+    utils::init_console_logging();
+    // Usage:
+    //   camt053_parser [database_path] [output_path]
+    //
+    // Auto-discovers all .xml files in current directory.
+    let args: Vec<String> = env::args().collect();
+    let mut contract = Camt053ImportContract::new();
+    utils::run_parser_contract_cli(&mut contract, &args, "../../../../database")
+}
@@ -0,0 +1,295 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use serde_json::Value;
+use utils::{build_transaction, compute_txn_id, TransactionInput};
+
+use crate::Camt053Parser;
+
+pub fn parse_transactions(parser: &Camt053Parser, xml_path: &str) -> Result<Vec<Value>> {
+    let content =
+        std::fs::read_to_string(xml_path).with_context(|| format!("Cannot open {}", xml_path))?;
+    parse_content(parser, &content)
+}
+
+/// Walks every `<Ntry>` of the statement. Entry-level elements (`Amt`,
+/// `CdtDbtInd`, `BookgDt`) come before `NtryDtls`, so the first occurrence
+/// inside the entry is always the entry's own value.
+pub fn parse_content(parser: &Camt053Parser, content: &str) -> Result<Vec<Value>> {
+    let mut out = Vec::new();
+    for (idx, entry) in ntry_blocks(content).enumerate() {
+        if let Some(since) = parser.since {
+            if field_date(entry).with_context(|| format!("Ntry #{}", idx + 1))? < since {
+                continue;
+            }
+        }
+        out.push(build_entry(parser, entry, idx).with_context(|| format!("Ntry #{}", idx + 1))?);
+    }
+    Ok(out)
+}
+
+fn ntry_blocks(content: &str) -> impl Iterator<Item = &str> {
+    content.split("<Ntry>").skip(1).map(|rest| {
+        let end = rest.find("</Ntry>").unwrap_or(rest.len());
+        &rest[..end]
+    })
+}
+
+fn build_entry(parser: &Camt053Parser, entry: &str, idx: usize) -> Result<Value> {
+    let amount = field_amount(entry)?;
+    let currency = field_currency(entry)?;
+    let is_income = field_is_credit(entry)?;
+    let date = field_date(entry)?;
+    let description = field_description(entry);
+
+    let txn_id = match element_text(entry, "AcctSvcrRef") {
+        Some(reference) => compute_txn_id("CAMT", &[&parser.account_id, &reference]),
+        None => compute_txn_id(
+            "CAMT",
+            &[
+                &parser.account_id,
+                &date.format("%Y-%m-%d").to_string(),
+                &format!("{:.8}", amount),
+                &currency,
+                &description,
+                &idx.to_string(),
+            ],
+        ),
+    };
+
+    Ok(build_transaction(&TransactionInput {
+        date: date.format("%Y-%m-%d").to_string(),
+        from_account_id: field_from_account_id(parser, is_income),
+        to_account_id: field_to_account_id(parser, is_income),
+        transaction_type: field_type(is_income),
+        category: field_category(),
+        amount,
+        currency,
+        description,
+        description_en: None,
+        txn_id,
+    }))
+}
+
+/// Text content of the first `<tag>` or `<tag attr="..">`, entity-decoded.
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    let (_, rest) = open_tag(xml, tag)?;
+    let end = rest.find('<').unwrap_or(rest.len());
+    let value = decode_entities(rest[..end].trim());
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn all_element_texts(xml: &str, tag: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut rest = xml;
+    while let Some((_, after)) = open_tag(rest, tag) {
+        let end = after.find('<').unwrap_or(after.len());
+        let value = decode_entities(after[..end].trim());
+        if !value.is_empty() {
+            out.push(value);
+        }
+        rest = &after[end..];
+    }
+    out
+}
+
+fn attribute(xml: &str, tag: &str, attr: &str) -> Option<String> {
+    let (attrs, _) = open_tag(xml, tag)?;
+    let needle = format!("{}=\"", attr);
+    let start = attrs.find(&needle)? + needle.len();
+    let end = attrs[start..].find('"')?;
+    Some(attrs[start..start + end].trim().to_string())
+}
+
+/// Finds `<tag>` or `<tag ...>` and returns (attribute text, text after `>`).
+fn open_tag<'a>(xml: &'a str, tag: &str) -> Option<(&'a str, &'a str)> {
+    let open = format!("<{}", tag);
+    let mut from = 0;
+    while let Some(pos) = xml[from..].find(&open) {
+        let after_name = from + pos + open.len();
+        match xml[after_name..].chars().next() {
+            Some('>') | Some(' ') | Some('\t') | Some('\n') | Some('\r') => {
+                let close = after_name + xml[after_name..].find('>')?;
+                return Some((&xml[after_name..close], &xml[close + 1..]));
+            }
+            _ => from = after_name,
+        }
+    }
+    None
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn field_amount(entry: &str) -> Result<f64> {
+    let raw = element_text(entry, "Amt").ok_or_else(|| anyhow!("Missing Amt"))?;
+    let amount = raw
+        .parse::<f64>()
+        .with_context(|| format!("Invalid Amt: {}", raw))?;
+    Ok(amount.abs())
+}
+
+fn field_currency(entry: &str) -> Result<String> {
+    attribute(entry, "Amt", "Ccy").ok_or_else(|| anyhow!("Missing Amt Ccy attribute"))
+}
+
+/// CRDT means money into the account, DBIT money out of it.
+fn field_is_credit(entry: &str) -> Result<bool> {
+    match element_text(entry, "CdtDbtInd").as_deref() {
+        Some("CRDT") => Ok(true),
+        Some("DBIT") => Ok(false),
+        other => Err(anyhow!("Invalid CdtDbtInd: {:?}", other)),
+    }
+}
+
+/// `BookgDt` holds either `<Dt>` or `<DtTm>`; both start with `YYYY-MM-DD`.
+fn field_date(entry: &str) -> Result<NaiveDate> {
+    let (_, booking) = open_tag(entry, "BookgDt").ok_or_else(|| anyhow!("Missing BookgDt"))?;
+    let raw = element_text(booking, "Dt")
+        .or_else(|| element_text(booking, "DtTm"))
+        .ok_or_else(|| anyhow!("Missing BookgDt date"))?;
+    let ymd = raw.get(..10).unwrap_or(&raw);
+    NaiveDate::parse_from_str(ymd, "%Y-%m-%d").with_context(|| format!("Invalid BookgDt: {}", raw))
+}
+
+/// Joins every unstructured remittance line; falls back to the entry's
+/// additional info when the bank sends no `RmtInf`.
+fn field_description(entry: &str) -> String {
+    let lines = open_tag(entry, "RmtInf")
+        .map(|(_, rmt)| {
+            let end = rmt.find("</RmtInf>").unwrap_or(rmt.len());
+            all_element_texts(&rmt[..end], "Ustrd")
+        })
+        .unwrap_or_default();
+
+    if lines.is_empty() {
+        element_text(entry, "AddtlNtryInf").unwrap_or_default()
+    } else {
+        lines.join(" ")
+    }
+}
+
+fn field_from_account_id(parser: &Camt053Parser, is_income: bool) -> String {
+    if is_income {
+        "EXTERNAL_PAYER".to_string()
+    } else {
+        parser.account_id.clone()
+    }
+}
+
+fn field_to_account_id(parser: &Camt053Parser, is_income: bool) -> String {
+    if is_income {
+        parser.account_id.clone()
+    } else {
+        "EXTERNAL_PAYEE".to_string()
+    }
+}
+
+fn field_type(is_income: bool) -> String {
+    if is_income { "income" } else { "expense" }.to_string()
+}
+
+fn field_category() -> String {
+    "uncategorized".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAMT053: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.02">
+  <BkToCstmrStmt>
+    <Stmt>
+      <Id>STMT-2024-01</Id>
+      <Ntry>
+        <NtryRef>1</NtryRef>
+        <Amt Ccy="EUR">2500.00</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <Sts>BOOK</Sts>
+        <BookgDt><Dt>2024-01-25</Dt></BookgDt>
+        <ValDt><Dt>2024-01-26</Dt></ValDt>
+        <AcctSvcrRef>REF-0001</AcctSvcrRef>
+        <NtryDtls><TxDtls>
+          <AmtDtls><TxAmt><Amt Ccy="EUR">2500.00</Amt></TxAmt></AmtDtls>
+          <RmtInf><Ustrd>Salary January</Ustrd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+      <Ntry>
+        <Amt Ccy="EUR">64.30</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <Sts>BOOK</Sts>
+        <BookgDt><DtTm>2024-01-27T10:15:00</DtTm></BookgDt>
+        <NtryDtls><TxDtls>
+          <RmtInf><Ustrd>Electricity</Ustrd><Ustrd>Invoice 42 &amp; fees</Ustrd></RmtInf>
+        </TxDtls></NtryDtls>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>
+"#;
+
+    fn parser() -> Camt053Parser {
+        Camt053Parser::new("MYBANK_EUR")
+    }
+
+    #[test]
+    fn credit_entry_maps_to_income() {
+        let txns = parser().parse_str(CAMT053).unwrap();
+
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0]["date"], "2024-01-25");
+        assert_eq!(txns[0]["type"], "income");
+        assert_eq!(txns[0]["from_account_id"], "EXTERNAL_PAYER");
+        assert_eq!(txns[0]["to_account_id"], "MYBANK_EUR");
+        assert_eq!(txns[0]["amount"], 2500.0);
+        assert_eq!(txns[0]["currency"], "EUR");
+        assert_eq!(txns[0]["description"], "Salary January");
+    }
+
+    #[test]
+    fn debit_entry_maps_to_expense() {
+        let txns = parser().parse_str(CAMT053).unwrap();
+
+        assert_eq!(txns[1]["date"], "2024-01-27");
+        assert_eq!(txns[1]["type"], "expense");
+        assert_eq!(txns[1]["from_account_id"], "MYBANK_EUR");
+        assert_eq!(txns[1]["to_account_id"], "EXTERNAL_PAYEE");
+        assert_eq!(txns[1]["amount"], 64.3);
+        assert_eq!(txns[1]["description"], "Electricity Invoice 42 & fees");
+    }
+
+    #[test]
+    fn txn_id_prefers_account_servicer_reference() {
+        let first = parser().parse_str(CAMT053).unwrap();
+        let edited = CAMT053.replace("Salary January", "Salary Jan");
+        let second = parser().parse_str(&edited).unwrap();
+
+        assert_eq!(first[0]["txn_id"], second[0]["txn_id"]);
+        assert!(first[0]["txn_id"].as_str().unwrap().starts_with("CAMT-"));
+    }
+
+    #[test]
+    fn rejects_unknown_credit_debit_indicator() {
+        let content = CAMT053.replace("<CdtDbtInd>CRDT", "<CdtDbtInd>XXXX");
+        assert!(parser().parse_str(&content).is_err());
+    }
+
+    #[test]
+    fn since_skips_earlier_entries_and_keeps_cutoff_day() {
+        let since = NaiveDate::from_ymd_opt(2024, 1, 27).unwrap();
+        let txns = parser().with_since(since).parse_str(CAMT053).unwrap();
+
+        assert_eq!(txns.len(), 1);
+        assert_eq!(txns[0]["date"], "2024-01-27");
+        assert_eq!(txns[0]["description"], "Electricity Invoice 42 & fees");
+    }
+}
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use serde_json::Value;
 
 mod accounts;
@@ -11,6 +12,12 @@ pub const PARSER_NAME: &str = "carpay";
 pub struct CarPayXlsxParser {
     pub account_id: String,
     pub currency: String,
+    pub collapse_installments: bool,
+    /// Account the card bill is paid from; bill payments are booked as
+    /// internal transfers from it.
+    pub funding_account_id: String,
+    /// Rows dated before this are skipped.
+    pub since: Option<NaiveDate>,
 }
 
 impl CarPayXlsxParser {
@@ -18,14 +25,35 @@ impl CarPayXlsxParser {
         Self {
             account_id: account_id.into(),
             currency: "SEK".to_string(),
+            collapse_installments: false,
+            funding_account_id: "INTERNAL_SOURCE".to_string(),
+            since: None,
         }
     }
 
+    /// Skips rows dated before `since`, before their txn_id is computed.
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = Some(since);
+        self
+    }
+
     pub fn with_currency(mut self, currency: impl Into<String>) -> Self {
         self.currency = currency.into();
         self
     }
 
+    /// Emits one transaction per installment purchase (rows sharing the same
+    /// `Referens` within a sheet) instead of one per monthly charge.
+    pub fn with_collapse_installments(mut self, collapse_installments: bool) -> Self {
+        self.collapse_installments = collapse_installments;
+        self
+    }
+
+    pub fn with_funding_account_id(mut self, account_id: impl Into<String>) -> Self {
+        self.funding_account_id = account_id.into();
+        self
+    }
+
     pub fn create_accounts(&self) -> Vec<Value> {
         accounts::create_accounts(self)
     }
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use std::env;
 
 use carpay_parser::CarPayXlsxParser;
@@ -24,16 +25,16 @@ impl utils::ParserContract for CarPayImportContract {
         &[utils::InputFormat::Excel]
     }
 
+    fn set_since(&mut self, since: NaiveDate) {
+        self.parser.since = Some(since);
+    }
+
     fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
-        let txns = self
-            .parser
-            .parse_file(input_file_path)
-            .with_context(|| format!("Failed parsing {}", input_file_path))?;
+        utils::ConcurrentParserContract::parse_file_shared(self, input_file_path)
+    }
 
-        Ok(utils::ParsedEntities {
-            transactions: txns,
-            ..Default::default()
-        })
+    fn as_concurrent(&self) -> Option<&dyn utils::ConcurrentParserContract> {
+        Some(self)
     }
 
     fn finalize_entities(
@@ -49,7 +50,22 @@ impl utils::ParserContract for CarPayImportContract {
     }
 }
 
+impl utils::ConcurrentParserContract for CarPayImportContract {
+    fn parse_file_shared(&self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        let txns = self
+            .parser
+            .parse_file(input_file_path)
+            .with_context(|| format!("Failed parsing {}", input_file_path))?;
+
+        Ok(utils::ParsedEntities {
+            transactions: txns,
+            ..Default::default()
+        })
+    }
+}
+
 fn main() -> Result<()> {
+    utils::init_console_logging();
     let args: Vec<String> = env::args().collect();
     let mut contract = CarPayImportContract::new();
     utils::run_parser_contract_cli(&mut contract, &args, "../../../../database")
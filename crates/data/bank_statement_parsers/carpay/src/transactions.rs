@@ -1,10 +1,12 @@
 use anyhow::{anyhow, Context, Result};
 use calamine::{open_workbook, Data, Reader, Xlsx};
-use chrono::{Duration, NaiveDate, NaiveDateTime};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use utils::{build_transaction, TransactionInput};
+use utils::{
+    build_transaction, excel_serial_to_naive_datetime, txn_id_datetime_key, TransactionInput,
+};
 
 use crate::CarPayXlsxParser;
 
@@ -16,6 +18,27 @@ struct HeaderColumns {
     varuslag: Option<usize>,
     card: Option<usize>,
     cardtext: Option<usize>,
+    original_amount: Option<usize>,
+    original_currency: Option<usize>,
+}
+
+/// A parsed sheet row, before it is turned into a transaction.
+struct SheetRow {
+    /// Includes the time of day when the cell carries one; used for txn_id.
+    date: NaiveDateTime,
+    amount_raw: f64,
+    reference: String,
+    description: String,
+    row_number: usize,
+    original: Option<OriginalAmount>,
+    /// `Varuslag`/`Korttext` mark the row as a payment towards the card bill.
+    bill_payment: bool,
+}
+
+/// The pre-conversion amount of a foreign-currency charge.
+struct OriginalAmount {
+    amount: f64,
+    currency: String,
 }
 
 pub fn parse_transactions(parser: &CarPayXlsxParser, xlsx_path: &str) -> Result<Vec<Value>> {
@@ -31,108 +54,226 @@ pub fn parse_transactions(parser: &CarPayXlsxParser, xlsx_path: &str) -> Result<
             Err(_) => continue,
         };
 
-        let Some((header_row_idx, header_map)) = find_header_row(&range) else {
-            continue;
-        };
+        out.extend(parse_sheet(parser, &range, &sheet_name, xlsx_path)?);
+    }
 
-        let cols = HeaderColumns {
-            date: *header_map.get("Datum").context("Missing column: Datum")?,
-            amount: *header_map.get("Belopp").context("Missing column: Belopp")?,
-            reference: header_map.get("Referens").copied(),
-            merchant: header_map.get("Försäljningsställe").copied(),
-            varuslag: header_map.get("Varuslag").copied(),
-            card: header_map.get("Kort").copied(),
-            cardtext: header_map.get("Korttext").copied(),
-        };
+    Ok(out)
+}
 
-        for (row_idx, row) in range.rows().enumerate().skip(header_row_idx + 1) {
-            let row_number = row_idx + 1;
-            let date_cell = row.get(cols.date);
-            let amount_cell = row.get(cols.amount);
-
-            let date = match parse_excel_date(date_cell) {
-                Ok(d) => d,
-                Err(_) => {
-                    if is_effectively_empty_row(date_cell, amount_cell) {
-                        continue;
-                    }
-
-                    return Err(anyhow!(
-                        "Invalid date at sheet '{}' row {} in {}",
-                        sheet_name,
-                        row_number,
-                        xlsx_path
-                    ));
+fn parse_sheet(
+    parser: &CarPayXlsxParser,
+    range: &calamine::Range<Data>,
+    sheet_name: &str,
+    xlsx_path: &str,
+) -> Result<Vec<Value>> {
+    let Some((header_row_idx, header_map)) = find_header_row(range) else {
+        return Ok(Vec::new());
+    };
+
+    let cols = HeaderColumns {
+        date: *header_map.get("Datum").context("Missing column: Datum")?,
+        amount: *header_map.get("Belopp").context("Missing column: Belopp")?,
+        reference: header_map.get("Referens").copied(),
+        merchant: header_map.get("Försäljningsställe").copied(),
+        varuslag: header_map.get("Varuslag").copied(),
+        card: header_map.get("Kort").copied(),
+        cardtext: header_map.get("Korttext").copied(),
+        original_amount: header_map.get("Originalbelopp").copied(),
+        original_currency: header_map.get("Valuta").copied(),
+    };
+
+    let mut rows = Vec::new();
+
+    for (row_idx, row) in range.rows().enumerate().skip(header_row_idx + 1) {
+        let row_number = row_idx + 1;
+        let date_cell = row.get(cols.date);
+        let amount_cell = row.get(cols.amount);
+
+        let date = match parse_excel_date(date_cell) {
+            Ok(d) => d,
+            Err(_) => {
+                if is_effectively_empty_row(date_cell, amount_cell) {
+                    continue;
                 }
-            };
-
-            let amount_raw = match parse_amount(amount_cell) {
-                Ok(a) => a,
-                Err(_) => {
-                    if is_effectively_empty_row(date_cell, amount_cell) {
-                        continue;
-                    }
-
-                    return Err(anyhow!(
-                        "Invalid amount at sheet '{}' row {} in {}",
-                        sheet_name,
-                        row_number,
-                        xlsx_path
-                    ));
+
+                return Err(anyhow!(
+                    "Invalid date at sheet '{}' row {} in {}",
+                    sheet_name,
+                    row_number,
+                    xlsx_path
+                ));
+            }
+        };
+        if parser.since.is_some_and(|since| date.date() < since) {
+            continue;
+        }
+
+        let amount_raw = match parse_amount(amount_cell) {
+            Ok(a) => a,
+            Err(_) => {
+                if is_effectively_empty_row(date_cell, amount_cell) {
+                    continue;
                 }
-            };
 
-            if amount_raw.abs() < 1e-9 {
-                continue;
+                return Err(anyhow!(
+                    "Invalid amount at sheet '{}' row {} in {}",
+                    sheet_name,
+                    row_number,
+                    xlsx_path
+                ));
             }
+        };
 
-            let base_type = infer_base_type(amount_raw);
-            let normalized_amount = amount_raw.abs();
-            let description = field_description(row, &cols, &sheet_name);
-            let txn_id = field_txn_id(
-                parser,
-                date,
-                normalized_amount,
-                &description,
-                &sheet_name,
-                row_number,
-            );
-
-            out.push(build_transaction(&TransactionInput {
-                date: field_date(date),
-                from_account_id: field_from_account_id(parser, base_type),
-                to_account_id: field_to_account_id(parser, base_type),
-                transaction_type: field_type(base_type),
-                category: field_category(),
-                amount: field_amount(normalized_amount),
-                currency: field_currency(parser),
-                description,
-                description_en: field_description_en(),
-                txn_id,
-            }));
+        if amount_raw.abs() < 1e-9 {
+            continue;
         }
+
+        rows.push(SheetRow {
+            date,
+            amount_raw,
+            reference: field_reference(row, &cols),
+            description: field_description(row, &cols, sheet_name),
+            row_number,
+            original: field_original_amount(parser, row, &cols),
+            bill_payment: is_bill_payment(row, &cols),
+        });
     }
 
-    Ok(out)
+    if parser.collapse_installments {
+        rows = collapse_installment_rows(rows);
+    }
+
+    Ok(rows
+        .iter()
+        .map(|row| build_sheet_transaction(parser, row, sheet_name))
+        .collect())
+}
+
+fn build_sheet_transaction(parser: &CarPayXlsxParser, row: &SheetRow, sheet_name: &str) -> Value {
+    let base_type = infer_base_type(row.amount_raw, row.bill_payment);
+    let normalized_amount = row.amount_raw.abs();
+    let txn_id = field_txn_id(
+        parser,
+        row.date,
+        normalized_amount,
+        &row.description,
+        sheet_name,
+        row.row_number,
+    );
+
+    let mut txn = build_transaction(&TransactionInput {
+        date: field_date(row.date.date()),
+        from_account_id: field_from_account_id(parser, base_type),
+        to_account_id: field_to_account_id(parser, base_type),
+        transaction_type: field_type(base_type),
+        category: field_category(base_type),
+        amount: field_amount(normalized_amount),
+        currency: field_currency(parser),
+        description: row.description.clone(),
+        description_en: field_description_en(),
+        txn_id,
+    });
+
+    if let (Some(original), Some(obj)) = (&row.original, txn.as_object_mut()) {
+        obj.insert(
+            "original_amount".to_string(),
+            Value::from(original.amount.abs()),
+        );
+        obj.insert(
+            "original_currency".to_string(),
+            Value::String(original.currency.clone()),
+        );
+    }
+
+    txn
 }
 
-fn infer_base_type(amount_raw: f64) -> &'static str {
+/// Merges rows that share a non-empty `Referens` into a single row.
+///
+/// The merged row keeps the earliest date, the summed amount and the first
+/// row's description tagged with the number of installments. Rows without a
+/// reference, and references that appear only once, are left as they are.
+fn collapse_installment_rows(rows: Vec<SheetRow>) -> Vec<SheetRow> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for row in &rows {
+        if !row.reference.is_empty() {
+            *counts.entry(row.reference.clone()).or_insert(0) += 1;
+        }
+    }
+
+    let mut out: Vec<SheetRow> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+
+    for row in rows {
+        let installments = counts.get(&row.reference).copied().unwrap_or(0);
+        if installments < 2 {
+            out.push(row);
+            continue;
+        }
+
+        match group_index.get(&row.reference) {
+            Some(&idx) => {
+                let group = &mut out[idx];
+                group.amount_raw += row.amount_raw;
+                group.original = match (group.original.take(), row.original) {
+                    (Some(a), Some(b)) if a.currency == b.currency => Some(OriginalAmount {
+                        amount: a.amount + b.amount,
+                        currency: a.currency,
+                    }),
+                    _ => None,
+                };
+                if row.date < group.date {
+                    group.date = row.date;
+                }
+            }
+            None => {
+                group_index.insert(row.reference.clone(), out.len());
+                out.push(SheetRow {
+                    description: format!("{} installments={}", row.description, installments),
+                    ..row
+                });
+            }
+        }
+    }
+
+    out
+}
+
+/// Positive amounts are card purchases. Negative amounts reduce the card
+/// balance: a bill payment when the row says so, otherwise a merchant refund.
+fn infer_base_type(amount_raw: f64, bill_payment: bool) -> &'static str {
     if amount_raw > 0.0 {
         "expense"
+    } else if bill_payment {
+        "internal_transfer"
     } else {
-        "income"
+        "refund"
     }
 }
 
+/// A bill payment says "Betalning" or "Inbetalning" as a word of its own;
+/// card purchases ("Kortbetalning") merely contain it.
+fn is_bill_payment(row: &[Data], cols: &HeaderColumns) -> bool {
+    [cols.varuslag, cols.cardtext]
+        .into_iter()
+        .flatten()
+        .filter_map(|idx| cell_str(row.get(idx)))
+        .any(|text| {
+            text.to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| word == "betalning" || word == "inbetalning")
+        })
+}
+
 fn field_date(date: NaiveDate) -> String {
     date.format("%Y-%m-%d").to_string()
 }
 
 fn field_from_account_id(parser: &CarPayXlsxParser, base_type: &str) -> String {
-    if base_type == "expense" {
-        parser.account_id.clone()
-    } else {
-        "EXTERNAL_PAYER".to_string()
+    match base_type {
+        "expense" => parser.account_id.clone(),
+        "internal_transfer" => parser.funding_account_id.clone(),
+        _ => "EXTERNAL_PAYER".to_string(),
     }
 }
 
@@ -144,12 +285,20 @@ fn field_to_account_id(parser: &CarPayXlsxParser, base_type: &str) -> String {
     }
 }
 
+/// Refunds are booked like other platforms' refunds: type `income`,
+/// category `refund`.
 fn field_type(base_type: &str) -> String {
-    base_type.to_string()
+    match base_type {
+        "refund" => "income".to_string(),
+        other => other.to_string(),
+    }
 }
 
-fn field_category() -> String {
-    "uncategorized".to_string()
+fn field_category(base_type: &str) -> String {
+    match base_type {
+        "refund" => "refund".to_string(),
+        _ => "uncategorized".to_string(),
+    }
 }
 
 fn field_amount(amount: f64) -> f64 {
@@ -160,13 +309,40 @@ fn field_currency(parser: &CarPayXlsxParser) -> String {
     parser.currency.clone()
 }
 
-fn field_description(row: &[Data], cols: &HeaderColumns, sheet_name: &str) -> String {
-    let reference = cols
-        .reference
+fn field_reference(row: &[Data], cols: &HeaderColumns) -> String {
+    cols.reference
         .and_then(|idx| cell_str(row.get(idx)))
         .unwrap_or_default()
         .trim()
-        .to_string();
+        .to_string()
+}
+
+/// Reads the `Originalbelopp`/`Valuta` columns, if present.
+///
+/// Only charges in a currency other than the account currency carry an
+/// original amount; domestic rows (or blank cells) yield `None`.
+fn field_original_amount(
+    parser: &CarPayXlsxParser,
+    row: &[Data],
+    cols: &HeaderColumns,
+) -> Option<OriginalAmount> {
+    let currency = cols
+        .original_currency
+        .and_then(|idx| cell_str(row.get(idx)))?
+        .trim()
+        .to_uppercase();
+
+    if currency.is_empty() || currency == parser.currency.to_uppercase() {
+        return None;
+    }
+
+    let amount = parse_amount(row.get(cols.original_amount?)).ok()?;
+
+    Some(OriginalAmount { amount, currency })
+}
+
+fn field_description(row: &[Data], cols: &HeaderColumns, sheet_name: &str) -> String {
+    let reference = field_reference(row, cols);
 
     let merchant = cols
         .merchant
@@ -230,7 +406,7 @@ fn field_description_en() -> Option<String> {
 
 fn field_txn_id(
     parser: &CarPayXlsxParser,
-    date: NaiveDate,
+    date: NaiveDateTime,
     amount: f64,
     description: &str,
     sheet_name: &str,
@@ -288,16 +464,16 @@ fn is_effectively_empty_row(date_cell: Option<&Data>, amount_cell: Option<&Data>
     ds.trim().is_empty() && as_.trim().is_empty()
 }
 
-fn parse_excel_date(cell: Option<&Data>) -> Result<NaiveDate> {
+fn parse_excel_date(cell: Option<&Data>) -> Result<NaiveDateTime> {
     let Some(c) = cell else {
         return Err(anyhow!("empty date"));
     };
 
     match c {
-        Data::Float(f) => excel_serial_to_date(*f),
-        Data::Int(i) => excel_serial_to_date(*i as f64),
+        Data::Float(f) => excel_serial_to_datetime(*f),
+        Data::Int(i) => excel_serial_to_datetime(*i as f64),
         Data::String(s) => parse_date_string(s),
-        Data::DateTime(dt) => excel_serial_to_date(dt.as_f64()),
+        Data::DateTime(dt) => excel_serial_to_datetime(dt.as_f64()),
         Data::DateTimeIso(s) | Data::DurationIso(s) => parse_date_string(s),
         _ => {
             let s = c.to_string();
@@ -306,31 +482,25 @@ fn parse_excel_date(cell: Option<&Data>) -> Result<NaiveDate> {
     }
 }
 
-fn parse_date_string(s: &str) -> Result<NaiveDate> {
+fn parse_date_string(s: &str) -> Result<NaiveDateTime> {
     let s = s.trim();
     if s.is_empty() {
         return Err(anyhow!("empty date string"));
     }
 
     if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
-        return Ok(dt.date());
+        return Ok(dt);
     }
 
     if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-        return Ok(d);
+        return Ok(d.and_time(NaiveTime::MIN));
     }
 
     Err(anyhow!("unsupported date format: {}", s))
 }
 
-fn excel_serial_to_date(v: f64) -> Result<NaiveDate> {
-    if !v.is_finite() {
-        return Err(anyhow!("non-finite excel date"));
-    }
-
-    let days = v.floor() as i64;
-    let base = NaiveDate::from_ymd_opt(1899, 12, 30).ok_or_else(|| anyhow!("bad base date"))?;
-    Ok(base + Duration::days(days))
+fn excel_serial_to_datetime(v: f64) -> Result<NaiveDateTime> {
+    excel_serial_to_naive_datetime(v).ok_or_else(|| anyhow!("invalid excel date: {}", v))
 }
 
 fn parse_amount(cell: Option<&Data>) -> Result<f64> {
@@ -364,7 +534,7 @@ fn trim_midspaces(s: &str) -> String {
 
 fn make_txn_id(
     account_id: &str,
-    date: NaiveDate,
+    date: NaiveDateTime,
     amount: f64,
     currency: &str,
     description: &str,
@@ -374,7 +544,7 @@ fn make_txn_id(
     let seed = format!(
         "{}|{}|{:.8}|{}|{}|{}|{}",
         account_id,
-        date.format("%Y-%m-%d"),
+        txn_id_datetime_key(date),
         amount,
         currency,
         description.trim(),
@@ -388,3 +558,272 @@ fn make_txn_id(
 
     format!("CARPAY-{}", hex::encode(&hash[..12]))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::Range;
+
+    fn sheet(rows: &[Vec<Data>]) -> Range<Data> {
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(1) as u32;
+        let mut range = Range::new((0, 0), (rows.len() as u32 - 1, width - 1));
+        for (r, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                range.set_value((r as u32, c as u32), cell.clone());
+            }
+        }
+        range
+    }
+
+    fn text(s: &str) -> Data {
+        Data::String(s.to_string())
+    }
+
+    fn installment_sheet() -> Range<Data> {
+        sheet(&[
+            vec![
+                text("Kontonummer"),
+                text("Datum"),
+                text("Försäljningsställe"),
+                text("Referens"),
+                text("Belopp"),
+            ],
+            vec![
+                text("1234"),
+                text("2026-03-25"),
+                text("Elgiganten"),
+                text("R-77"),
+                Data::Float(500.0),
+            ],
+            vec![
+                text("1234"),
+                text("2026-01-25"),
+                text("Elgiganten"),
+                text("R-77"),
+                Data::Float(500.0),
+            ],
+            vec![
+                text("1234"),
+                text("2026-02-25"),
+                text("Elgiganten"),
+                text("R-77"),
+                Data::Float(500.0),
+            ],
+            vec![
+                text("1234"),
+                text("2026-02-03"),
+                text("Circle K"),
+                text(""),
+                Data::Float(649.9),
+            ],
+        ])
+    }
+
+    #[test]
+    fn installments_are_kept_separate_by_default() {
+        let parser = CarPayXlsxParser::new("CARPAY");
+        let txns = parse_sheet(
+            &parser,
+            &installment_sheet(),
+            "Kostnadsuppföljning",
+            "test.xlsx",
+        )
+        .unwrap();
+
+        assert_eq!(txns.len(), 4);
+    }
+
+    #[test]
+    fn collapse_installments_merges_rows_sharing_a_reference() {
+        let parser = CarPayXlsxParser::new("CARPAY").with_collapse_installments(true);
+        let txns = parse_sheet(
+            &parser,
+            &installment_sheet(),
+            "Kostnadsuppföljning",
+            "test.xlsx",
+        )
+        .unwrap();
+
+        assert_eq!(txns.len(), 2);
+
+        let purchase = &txns[0];
+        assert_eq!(purchase["date"], "2026-01-25");
+        assert_eq!(purchase["amount"].as_f64(), Some(1500.0));
+        assert_eq!(purchase["type"], "expense");
+        let description = purchase["description"].as_str().unwrap();
+        assert!(description.contains("ref=R-77"));
+        assert!(description.contains("installments=3"));
+
+        assert_eq!(txns[1]["amount"].as_f64(), Some(649.9));
+    }
+
+    #[test]
+    fn serial_time_of_day_feeds_txn_id_but_not_date() {
+        let parser = CarPayXlsxParser::new("CARPAY");
+        let parse = |date: Data| {
+            let range = sheet(&[
+                vec![
+                    text("Kontonummer"),
+                    text("Datum"),
+                    text("Försäljningsställe"),
+                    text("Referens"),
+                    text("Belopp"),
+                ],
+                vec![
+                    text("1234"),
+                    date,
+                    text("Circle K"),
+                    text(""),
+                    Data::Float(649.9),
+                ],
+            ]);
+            parse_sheet(&parser, &range, "Kostnadsuppföljning", "test.xlsx")
+                .unwrap()
+                .remove(0)
+        };
+
+        let midnight = parse(Data::Float(46056.0));
+        let afternoon = parse(Data::Float(46056.0 + 52527.0 / 86400.0));
+        let as_text = parse(text("2026-02-03"));
+
+        assert_eq!(midnight["date"], "2026-02-03");
+        assert_eq!(afternoon["date"], "2026-02-03");
+        assert_ne!(midnight["txn_id"], afternoon["txn_id"]);
+        // A date-only serial keeps the id of the plain date text.
+        assert_eq!(midnight["txn_id"], as_text["txn_id"]);
+    }
+
+    #[test]
+    fn foreign_currency_rows_carry_the_original_amount() {
+        let parser = CarPayXlsxParser::new("CARPAY");
+        let range = sheet(&[
+            vec![
+                text("Kontonummer"),
+                text("Datum"),
+                text("Försäljningsställe"),
+                text("Originalbelopp"),
+                text("Valuta"),
+                text("Belopp"),
+            ],
+            vec![
+                text("1234"),
+                text("2026-04-02"),
+                text("Circle K"),
+                Data::Float(649.9),
+                text("SEK"),
+                Data::Float(649.9),
+            ],
+            vec![
+                text("1234"),
+                text("2026-04-05"),
+                text("Shell Milano"),
+                Data::Float(60.0),
+                text("EUR"),
+                Data::Float(691.2),
+            ],
+        ]);
+
+        let txns = parse_sheet(&parser, &range, "Kostnadsuppföljning", "test.xlsx").unwrap();
+        assert_eq!(txns.len(), 2);
+
+        let domestic = &txns[0];
+        assert_eq!(domestic["currency"], "SEK");
+        assert!(domestic.get("original_amount").is_none());
+        assert!(domestic.get("original_currency").is_none());
+
+        let foreign = &txns[1];
+        assert_eq!(foreign["amount"].as_f64(), Some(691.2));
+        assert_eq!(foreign["currency"], "SEK");
+        assert_eq!(foreign["original_amount"].as_f64(), Some(60.0));
+        assert_eq!(foreign["original_currency"], "EUR");
+    }
+
+    #[test]
+    fn negative_rows_are_refunds_or_bill_payments() {
+        let range = sheet(&[
+            vec![
+                text("Kontonummer"),
+                text("Datum"),
+                text("Försäljningsställe"),
+                text("Varuslag"),
+                text("Belopp"),
+            ],
+            vec![
+                text("1234"),
+                text("2026-03-01"),
+                text("ICA Maxi"),
+                text("Livsmedel"),
+                Data::Float(250.0),
+            ],
+            vec![
+                text("1234"),
+                text("2026-03-05"),
+                text("ICA Maxi"),
+                text("Livsmedel"),
+                Data::Float(-80.0),
+            ],
+            vec![
+                text("1234"),
+                text("2026-03-28"),
+                text(""),
+                text("Inbetalning"),
+                Data::Float(-2000.0),
+            ],
+        ]);
+        let parser = CarPayXlsxParser::new("CARPAY").with_funding_account_id("SEB_CHECKING");
+        let txns = parse_sheet(&parser, &range, "Kontoutdrag", "test.xlsx").unwrap();
+
+        let purchase = &txns[0];
+        assert_eq!(purchase["type"], "expense");
+        assert_eq!(purchase["category"], "uncategorized");
+        assert_eq!(purchase["from_account_id"], "CARPAY");
+        assert_eq!(purchase["to_account_id"], "EXTERNAL_PAYEE");
+
+        let refund = &txns[1];
+        assert_eq!(refund["type"], "income");
+        assert_eq!(refund["category"], "refund");
+        assert_eq!(refund["from_account_id"], "EXTERNAL_PAYER");
+        assert_eq!(refund["to_account_id"], "CARPAY");
+        assert_eq!(refund["amount"], 80.0);
+
+        let payment = &txns[2];
+        assert_eq!(payment["type"], "internal_transfer");
+        assert_eq!(payment["category"], "uncategorized");
+        assert_eq!(payment["from_account_id"], "SEB_CHECKING");
+        assert_eq!(payment["to_account_id"], "CARPAY");
+        assert_eq!(payment["amount"], 2000.0);
+    }
+
+    #[test]
+    fn negative_card_payment_is_a_refund_not_a_bill_payment() {
+        let range = sheet(&[
+            vec![
+                text("Kontonummer"),
+                text("Datum"),
+                text("Försäljningsställe"),
+                text("Varuslag"),
+                text("Belopp"),
+            ],
+            vec![
+                text("1234"),
+                text("2026-03-06"),
+                text("Circle K"),
+                text("Kortbetalning"),
+                Data::Float(-120.0),
+            ],
+            vec![
+                text("1234"),
+                text("2026-03-28"),
+                text(""),
+                text("Betalning - tack"),
+                Data::Float(-2000.0),
+            ],
+        ]);
+        let parser = CarPayXlsxParser::new("CARPAY").with_funding_account_id("SEB_CHECKING");
+        let txns = parse_sheet(&parser, &range, "Kontoutdrag", "test.xlsx").unwrap();
+
+        assert_eq!(txns[0]["type"], "income");
+        assert_eq!(txns[0]["category"], "refund");
+        assert_eq!(txns[1]["type"], "internal_transfer");
+    }
+}
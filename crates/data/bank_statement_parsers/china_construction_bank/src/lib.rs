@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use serde_json::Value;
 
 mod accounts;
@@ -11,6 +12,11 @@ pub const PARSER_NAME: &str = "ccb";
 pub struct CcbXlsParser {
     pub account_id: String,
     pub only_nonempty_rows: bool,
+    /// When set, each row's amount sign is checked against the change in the
+    /// 余额 (balance) column since the previous row.
+    pub balance_check: bool,
+    /// Rows dated before this are skipped.
+    pub since: Option<NaiveDate>,
 }
 
 impl CcbXlsParser {
@@ -18,6 +24,8 @@ impl CcbXlsParser {
         Self {
             account_id: account_id.into(),
             only_nonempty_rows: true,
+            balance_check: false,
+            since: None,
         }
     }
 
@@ -26,6 +34,17 @@ impl CcbXlsParser {
         self
     }
 
+    pub fn with_balance_check(mut self, balance_check: bool) -> Self {
+        self.balance_check = balance_check;
+        self
+    }
+
+    /// Skips rows dated before `since`, before their txn_id is computed.
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = Some(since);
+        self
+    }
+
     pub fn create_accounts(&self) -> Vec<Value> {
         accounts::create_accounts(self)
     }
@@ -39,10 +58,21 @@ impl CcbXlsParser {
     }
 
     pub fn parse_file(&self, xls_path: &str) -> Result<Vec<Value>> {
+        Ok(self.parse_file_with_warnings(xls_path)?.transactions)
+    }
+
+    /// Like `parse_file`, but also returns the balance-check warnings.
+    pub fn parse_file_with_warnings(&self, xls_path: &str) -> Result<ParsedCcb> {
         transactions::parse_transactions(self, xls_path)
     }
 }
 
+pub struct ParsedCcb {
+    pub transactions: Vec<Value>,
+    /// Balance-check findings; empty unless `with_balance_check(true)`.
+    pub warnings: Vec<String>,
+}
+
 /// Merges CCB transactions into an existing database.json Value.
 /// Assumes database.json has a top level "transactions": [] array.
 /// Automatically skips duplicate transactions based on txn_id.
@@ -1,28 +1,75 @@
 use anyhow::{anyhow, Context, Result};
-use calamine::{open_workbook, Data, Reader, Xls};
+use calamine::{open_workbook, Data, Reader, Xls, Xlsx};
 use chrono::NaiveDate;
-use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::io::Read;
 use utils::{build_transaction, TransactionInput};
 
-use crate::CcbXlsParser;
+use crate::{CcbXlsParser, ParsedCcb};
 
-pub fn parse_transactions(parser: &CcbXlsParser, xls_path: &str) -> Result<Vec<Value>> {
-    let mut workbook: Xls<_> =
-        open_workbook(xls_path).with_context(|| format!("Cannot open {}", xls_path))?;
+/// Legacy `.xls` exports are OLE compound files; newer web exports are
+/// `.xlsx` (zip). Both are read into the same sheet range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WorkbookFormat {
+    Xls,
+    Xlsx,
+}
+
+pub fn parse_transactions(parser: &CcbXlsParser, xls_path: &str) -> Result<ParsedCcb> {
+    let range = match detect_workbook_format(xls_path)? {
+        WorkbookFormat::Xls => {
+            let workbook: Xls<_> =
+                open_workbook(xls_path).with_context(|| format!("Cannot open {}", xls_path))?;
+            first_sheet_range(workbook, xls_path)?
+        }
+        WorkbookFormat::Xlsx => {
+            let workbook: Xlsx<_> =
+                open_workbook(xls_path).with_context(|| format!("Cannot open {}", xls_path))?;
+            first_sheet_range(workbook, xls_path)?
+        }
+    };
+
+    parse_range(parser, &range, xls_path)
+}
 
+fn first_sheet_range<R>(mut workbook: R, xls_path: &str) -> Result<calamine::Range<Data>>
+where
+    R: Reader<std::io::BufReader<std::fs::File>>,
+    R::Error: std::error::Error + Send + Sync + 'static,
+{
     let sheet_name = workbook
         .sheet_names()
         .first()
         .cloned()
         .ok_or_else(|| anyhow!("No sheets found in {}", xls_path))?;
 
-    let range = workbook
+    workbook
         .worksheet_range(&sheet_name)
-        .with_context(|| format!("Cannot read sheet '{}' in {}", sheet_name, xls_path))?;
+        .with_context(|| format!("Cannot read sheet '{}' in {}", sheet_name, xls_path))
+}
+
+/// Checks the magic bytes first, since exports are sometimes renamed, and
+/// falls back to the extension.
+fn detect_workbook_format(path: &str) -> Result<WorkbookFormat> {
+    let mut magic = [0u8; 4];
+    let mut file = std::fs::File::open(path).with_context(|| format!("Cannot open {}", path))?;
+    let read = file.read(&mut magic)?;
+
+    match &magic[..read] {
+        [0xD0, 0xCF, 0x11, 0xE0] => Ok(WorkbookFormat::Xls),
+        [b'P', b'K', 0x03, 0x04] => Ok(WorkbookFormat::Xlsx),
+        _ if path.to_ascii_lowercase().ends_with(".xlsx") => Ok(WorkbookFormat::Xlsx),
+        _ => Ok(WorkbookFormat::Xls),
+    }
+}
 
-    let (header_row_idx, header_map) = find_header_row_ccb(&range).context(
+fn parse_range(
+    parser: &CcbXlsParser,
+    range: &calamine::Range<Data>,
+    xls_path: &str,
+) -> Result<ParsedCcb> {
+    let (header_row_idx, header_map) = find_header_row_ccb(range).context(
         "Could not find CCB header row starting with '序号' and containing '交易日期'",
     )?;
 
@@ -35,10 +82,13 @@ pub fn parse_transactions(parser: &CcbXlsParser, xls_path: &str) -> Result<Vec<V
     let c_amount = *header_map
         .get("交易金额")
         .context("Missing column: 交易金额")?;
+    let c_balance = header_map.get("余额").copied();
     let c_location = header_map.get("交易地点/附言").copied();
     let c_counterparty = header_map.get("对方账号与户名").copied();
 
     let mut out = Vec::new();
+    let mut warnings = Vec::new();
+    let mut previous_balance: Option<f64> = None;
 
     for (row_idx, row) in range.rows().enumerate().skip(header_row_idx + 1) {
         let seq = cell_str(row.get(c_seq)).trim().to_string();
@@ -63,10 +113,29 @@ pub fn parse_transactions(parser: &CcbXlsParser, xls_path: &str) -> Result<Vec<V
 
         let date = parse_ccb_date(&date_raw)
             .with_context(|| format!("Invalid date '{}' in {}", date_raw, xls_path))?;
+        if parser.since.is_some_and(|since| date < since) {
+            continue;
+        }
 
         let amount_signed = parse_ccb_amount(&amount_raw)
             .with_context(|| format!("Invalid amount '{}' in {}", amount_raw, xls_path))?;
 
+        let balance = c_balance.and_then(|idx| parse_ccb_amount(&cell_str(row.get(idx))).ok());
+
+        if parser.balance_check {
+            if let (Some(prev), Some(curr)) = (previous_balance, balance) {
+                if let Some(warning) =
+                    check_balance_delta(prev, curr, amount_signed, row_idx + 1, &seq)
+                {
+                    log::warn!("{}", warning);
+                    warnings.push(warning);
+                }
+            }
+        }
+        if balance.is_some() {
+            previous_balance = balance;
+        }
+
         let base_type = infer_base_type(amount_signed);
         let amount = amount_signed.abs();
 
@@ -101,7 +170,34 @@ pub fn parse_transactions(parser: &CcbXlsParser, xls_path: &str) -> Result<Vec<V
         }));
     }
 
-    Ok(out)
+    Ok(ParsedCcb {
+        transactions: out,
+        warnings,
+    })
+}
+
+/// Compares the stated amount sign with the running balance delta from the
+/// previous row. Returns a warning when they disagree.
+fn check_balance_delta(
+    previous_balance: f64,
+    balance: f64,
+    amount_signed: f64,
+    row_number: usize,
+    seq: &str,
+) -> Option<String> {
+    let delta = balance - previous_balance;
+    if delta.abs() < 1e-9 || amount_signed.abs() < 1e-9 {
+        return None;
+    }
+
+    if (delta > 0.0) == (amount_signed > 0.0) {
+        return None;
+    }
+
+    Some(format!(
+        "Row {} (序号 {}): amount {:.2} disagrees with balance change {:.2} -> {:.2}",
+        row_number, seq, amount_signed, previous_balance, balance
+    ))
 }
 
 fn field_date(date: NaiveDate) -> String {
@@ -193,7 +289,7 @@ fn infer_base_type(amount_signed: f64) -> &'static str {
 
 fn find_header_row_ccb(range: &calamine::Range<Data>) -> Result<(usize, HashMap<String, usize>)> {
     for (r_idx, row) in range.rows().enumerate() {
-        let first = cell_str(row.get(0)).trim().to_string();
+        let first = cell_str(row.first()).trim().to_string();
         if first == "序号" {
             let mut map = HashMap::new();
             for (c_idx, cell) in row.iter().enumerate() {
@@ -253,12 +349,22 @@ fn parse_ccb_amount(raw: &str) -> Result<f64> {
     Ok(s.parse::<f64>()?)
 }
 
+/// Chinese currency names used in the 币别 column, mapped to ISO codes.
+const CCB_CURRENCIES: &[(&str, &str)] = &[
+    ("人民币", "CNY"),
+    ("美元", "USD"),
+    ("欧元", "EUR"),
+    ("港币", "HKD"),
+    ("英镑", "GBP"),
+    ("日元", "JPY"),
+];
+
 fn normalize_ccb_currency(currency_raw: &str) -> &'static str {
-    if currency_raw.contains("人民币") {
-        "CNY"
-    } else {
-        "CNY"
-    }
+    CCB_CURRENCIES
+        .iter()
+        .find(|(name, _)| currency_raw.contains(name))
+        .map(|(_, iso)| *iso)
+        .unwrap_or("CNY")
 }
 
 fn make_ccb_txn_id(
@@ -287,3 +393,92 @@ fn make_ccb_txn_id(
 
     format!("CCB-{}", hex::encode(&hash[..12]))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance_sheet() -> calamine::Range<Data> {
+        let rows: Vec<Vec<&str>> = vec![
+            vec!["序号", "摘要", "币别", "交易日期", "交易金额", "余额"],
+            vec!["1", "工资", "人民币", "20260301", "5000.00", "5000.00"],
+            vec!["2", "消费", "人民币", "20260302", "-200.00", "4800.00"],
+            // Stated as income, but the balance went down.
+            vec!["3", "转账", "人民币", "20260303", "300.00", "4500.00"],
+        ];
+
+        let mut range = calamine::Range::new((0, 0), (rows.len() as u32 - 1, 5));
+        for (r, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                range.set_value((r as u32, c as u32), Data::String(cell.to_string()));
+            }
+        }
+        range
+    }
+
+    #[test]
+    fn balance_check_warns_when_sign_contradicts_balance_delta() {
+        let parser = CcbXlsParser::new("CCB_CHECKING").with_balance_check(true);
+        let parsed = parse_range(&parser, &balance_sheet(), "test.xls").unwrap();
+
+        assert_eq!(parsed.transactions.len(), 3);
+        assert_eq!(parsed.warnings.len(), 1);
+        assert!(parsed.warnings[0].contains("序号 3"));
+    }
+
+    #[test]
+    fn balance_check_is_off_by_default() {
+        let parser = CcbXlsParser::new("CCB_CHECKING");
+        let parsed = parse_range(&parser, &balance_sheet(), "test.xls").unwrap();
+
+        assert_eq!(parsed.transactions.len(), 3);
+        assert!(parsed.warnings.is_empty());
+    }
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn detects_workbook_format_from_magic_bytes() {
+        assert_eq!(
+            detect_workbook_format(&fixture("ccb_statement.xls")).unwrap(),
+            WorkbookFormat::Xls
+        );
+        assert_eq!(
+            detect_workbook_format(&fixture("ccb_statement.xlsx")).unwrap(),
+            WorkbookFormat::Xlsx
+        );
+    }
+
+    #[test]
+    fn xls_and_xlsx_exports_produce_identical_transactions() {
+        let parser = CcbXlsParser::new("CCB_CHECKING");
+        let xls = parser.parse_file(&fixture("ccb_statement.xls")).unwrap();
+        let xlsx = parser.parse_file(&fixture("ccb_statement.xlsx")).unwrap();
+
+        assert_eq!(xls.len(), 3);
+        assert_eq!(xls, xlsx);
+        assert_eq!(xls[0]["type"], "income");
+        assert_eq!(xls[0]["amount"], 5000.0);
+        assert_eq!(xls[2]["type"], "expense");
+        assert_eq!(xls[2]["amount"], 1000.0);
+    }
+
+    #[test]
+    fn normalize_ccb_currency_maps_chinese_names_to_iso_codes() {
+        assert_eq!(normalize_ccb_currency("人民币"), "CNY");
+        assert_eq!(normalize_ccb_currency("美元"), "USD");
+        assert_eq!(normalize_ccb_currency("欧元"), "EUR");
+        assert_eq!(normalize_ccb_currency("港币"), "HKD");
+        assert_eq!(normalize_ccb_currency("英镑"), "GBP");
+        assert_eq!(normalize_ccb_currency("日元"), "JPY");
+        assert_eq!(normalize_ccb_currency("美元现汇"), "USD");
+    }
+
+    #[test]
+    fn normalize_ccb_currency_falls_back_to_cny() {
+        assert_eq!(normalize_ccb_currency("澳元"), "CNY");
+        assert_eq!(normalize_ccb_currency(""), "CNY");
+    }
+}
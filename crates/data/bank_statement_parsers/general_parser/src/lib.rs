@@ -44,6 +44,12 @@ impl GeneralParser {
     }
 }
 
+impl Default for GeneralParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct GeneralImportContract {
     parser: GeneralParser,
     ai: OllamaClient,
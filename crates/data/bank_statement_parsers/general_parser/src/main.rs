@@ -3,6 +3,7 @@ use general_parser::GeneralImportContract;
 use std::env;
 
 fn main() -> Result<()> {
+    utils::init_console_logging();
     let args: Vec<String> = env::args().collect();
     let mut contract = GeneralImportContract::new();
     utils::run_parser_contract_cli(&mut contract, &args, "../../../../database")
@@ -346,10 +346,9 @@ fn decode_csv_text(raw: &[u8], path: &str) -> Result<(String, String, Vec<Format
     ))
 }
 
-fn parse_csv_rows(
-    text: &str,
-    path: &str,
-) -> Result<(Vec<Value>, Vec<String>, char, Vec<FormatIssue>)> {
+type ParsedCsvRows = (Vec<Value>, Vec<String>, char, Vec<FormatIssue>);
+
+fn parse_csv_rows(text: &str, path: &str) -> Result<ParsedCsvRows> {
     let delimiters = [',', ';', '\t', '|'];
     let mut best_rows = Vec::new();
     let mut best_headers = Vec::new();
@@ -774,9 +773,7 @@ fn uniquify_headers(headers: Vec<String>) -> Vec<String> {
 
 fn normalize_text(input: &str) -> String {
     input
-        .replace('\u{00A0}', " ")
-        .replace('\r', " ")
-        .replace('\n', " ")
+        .replace(['\u{00A0}', '\r', '\n'], " ")
         .split_whitespace()
         .collect::<Vec<_>>()
         .join(" ")
@@ -0,0 +1,169 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::{parse_f64_opt, IbkrCsvParser, ParsedIbkr, SectionHeader};
+
+const CASH_REPORT_SECTION: &str = "Cash Report";
+
+/// Rows converted to the base currency; the per-currency rows carry the same
+/// cash in its own currency.
+const BASE_CURRENCY_SUMMARY: &str = "Base Currency Summary";
+
+/// Default allowed difference between the Cash Report and the parsed
+/// transactions, per currency.
+pub const CASH_REPORT_TOLERANCE: f64 = 0.01;
+
+/// Records the "Starting Cash" and "Ending Cash" rows of the Cash Report as
+/// `currency -> (starting, ending)`.
+pub fn parse_cash_report_row(
+    section: &str,
+    headers: &HashMap<String, SectionHeader>,
+    row: &[String],
+    cash_report: &mut HashMap<String, (f64, f64)>,
+) -> Result<bool> {
+    if section != CASH_REPORT_SECTION {
+        return Ok(false);
+    }
+
+    let header = headers
+        .get(section)
+        .ok_or_else(|| anyhow!("Missing header for section {}", section))?;
+
+    let summary = header.get(row, "Currency Summary").unwrap_or("").trim();
+    let currency = header.get(row, "Currency").unwrap_or("").trim();
+    if currency.is_empty() || currency == BASE_CURRENCY_SUMMARY {
+        return Ok(true);
+    }
+    let Some(total) = parse_f64_opt(header.get(row, "Total").unwrap_or("")) else {
+        return Ok(true);
+    };
+
+    match summary {
+        "Starting Cash" => cash_report.entry(currency.to_string()).or_default().0 = total,
+        "Ending Cash" => cash_report.entry(currency.to_string()).or_default().1 = total,
+        _ => {}
+    }
+    Ok(true)
+}
+
+/// Compares each Cash Report currency's `ending - starting` with the net cash
+/// effect of the parsed transactions and logs and returns a warning per
+/// mismatch larger than `tolerance`.
+///
+/// Cash lives in the checking account, so every flow in or out of it counts.
+/// Trade commissions are booked from the savings account to an external payee
+/// but are paid in cash, so they count as well; trades themselves move cash
+/// into securities and are covered by the checking side.
+pub fn reconcile_cash_report(
+    parser: &IbkrCsvParser,
+    parsed: &ParsedIbkr,
+    tolerance: f64,
+) -> Vec<String> {
+    let mut net_by_currency: HashMap<&str, f64> = HashMap::new();
+    for txn in &parsed.transactions {
+        let currency = txn.get("currency").and_then(|v| v.as_str()).unwrap_or("");
+        *net_by_currency.entry(currency).or_default() += cash_effect(parser, txn);
+    }
+
+    let mut currencies: Vec<&String> = parsed.cash_report.keys().collect();
+    currencies.sort();
+
+    let warnings: Vec<String> = currencies
+        .into_iter()
+        .filter_map(|currency| {
+            let (starting, ending) = parsed.cash_report[currency];
+            let expected = ending - starting;
+            let net = net_by_currency
+                .get(currency.as_str())
+                .copied()
+                .unwrap_or(0.0);
+            ((net - expected).abs() > tolerance).then(|| {
+                format!(
+                    "Cash Report {}: starting {:.2} + parsed net {:.2} = {:.2}, but IBKR reports ending cash {:.2}",
+                    currency,
+                    starting,
+                    net,
+                    starting + net,
+                    ending
+                )
+            })
+        })
+        .collect();
+
+    for warning in &warnings {
+        log::warn!("{}", warning);
+    }
+    warnings
+}
+
+fn cash_effect(parser: &IbkrCsvParser, txn: &Value) -> f64 {
+    let field = |key: &str| txn.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    let amount = txn.get("amount").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let (from, to) = (field("from_account_id"), field("to_account_id"));
+    let checking = parser.account_id_checking.as_str();
+    let savings = parser.account_id_savings.as_str();
+
+    if from == checking && to != checking {
+        -amount
+    } else if to == checking && from != checking {
+        amount
+    } else if from == savings && to != savings {
+        -amount
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATEMENT: &str = "\
+Statement,Header,Field Name,Field Value
+Statement,Data,Period,\"January 1, 2025 - January 31, 2025\"
+Cash Report,Header,Currency Summary,Currency,Total,Securities,Futures,Month to Date,Year to Date,
+Cash Report,Data,Starting Cash,Base Currency Summary,1100,1100,0,,,
+Cash Report,Data,Starting Cash,EUR,1000,1000,0,,,
+Cash Report,Data,Starting Cash,USD,110,110,0,,,
+Cash Report,Data,Deposits,EUR,600,600,0,600,600,
+Cash Report,Data,Ending Cash,Base Currency Summary,1590,1590,0,,,
+Cash Report,Data,Ending Cash,EUR,1490,1490,0,,,
+Cash Report,Data,Ending Cash,USD,110,110,0,,,
+Deposits & Withdrawals,Header,Currency,Settle Date,Description,Amount
+Deposits & Withdrawals,Data,EUR,2025-01-05,Electronic Fund Transfer,600
+Deposits & Withdrawals,Data,Total,,,600
+Trades,Header,DataDiscriminator,Asset Category,Currency,Symbol,Date/Time,Quantity,T. Price,Proceeds,Comm/Fee
+Trades,Data,Order,Stocks,EUR,VWCE,\"2025-01-10, 09:30:00\",1,100,-100,-1
+Fees,Header,Subtitle,Currency,Date,Description,Amount
+Fees,Data,Other Fees,EUR,2025-01-31,Market data,-9
+";
+
+    #[test]
+    fn parses_cash_report_per_currency() {
+        let parsed = IbkrCsvParser::new()
+            .parse_reader(STATEMENT.as_bytes())
+            .unwrap();
+
+        assert_eq!(parsed.cash_report.len(), 2);
+        assert_eq!(parsed.cash_report["EUR"], (1000.0, 1490.0));
+        assert_eq!(parsed.cash_report["USD"], (110.0, 110.0));
+    }
+
+    #[test]
+    fn reconciles_cash_report_with_transactions() {
+        let parser = IbkrCsvParser::new();
+        let mut parsed = parser.parse_reader(STATEMENT.as_bytes()).unwrap();
+
+        // 1000 + 600 deposit - 100 buy - 1 commission - 9 fee = 1490.
+        assert!(reconcile_cash_report(&parser, &parsed, CASH_REPORT_TOLERANCE).is_empty());
+
+        parsed
+            .cash_report
+            .insert("EUR".to_string(), (1000.0, 1500.0));
+        let warnings = reconcile_cash_report(&parser, &parsed, CASH_REPORT_TOLERANCE);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Cash Report EUR"));
+        assert!(warnings[0].contains("ending cash 1500.00"));
+    }
+}
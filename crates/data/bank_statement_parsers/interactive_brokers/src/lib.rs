@@ -7,10 +7,14 @@ use std::io::Read;
 use std::path::Path;
 
 mod accounts;
+mod cash_report;
 mod instruments;
+mod mtm;
 mod positions;
 mod transactions;
 
+pub use cash_report::{reconcile_cash_report, CASH_REPORT_TOLERANCE};
+
 pub const PARSER_NAME: &str = "ibkr";
 
 pub const IBKR_CHECKING: &str = "IBKR_CHECKING";
@@ -92,6 +96,8 @@ impl IbkrCsvParser {
         let mut instruments_out: Vec<Value> = Vec::new();
         let mut positions_out: Vec<Value> = Vec::new();
         let mut transactions_out: Vec<Value> = Vec::new();
+        let mut mtm_rows: Vec<mtm::MtmRowData> = Vec::new();
+        let mut cash_report: HashMap<String, (f64, f64)> = HashMap::new();
 
         let mut instrument_key_to_id: HashMap<String, String> = HashMap::new();
         let mut instrument_id_remap: HashMap<String, String> = HashMap::new();
@@ -135,6 +141,14 @@ impl IbkrCsvParser {
                 continue;
             }
 
+            if mtm::parse_mtm_row(section, &headers, &row, &mut mtm_rows)? {
+                continue;
+            }
+
+            if cash_report::parse_cash_report_row(section, &headers, &row, &mut cash_report)? {
+                continue;
+            }
+
             if positions::parse_position_row(
                 self,
                 section,
@@ -164,11 +178,23 @@ impl IbkrCsvParser {
             &instrument_id_remap,
         );
 
+        let mtm_performance = mtm::build_mtm_entries(
+            self,
+            &mtm_rows,
+            statement_end
+                .or(fallback_statement_date)
+                .unwrap_or_else(|| NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()),
+            &instrument_key_to_id,
+            &instrument_id_remap,
+        );
+
         Ok(ParsedIbkr {
             statement_end,
             instruments: instruments_out,
             positions: positions_out,
             transactions: transactions_out,
+            mtm_performance,
+            cash_report,
         })
     }
 }
@@ -184,6 +210,12 @@ pub struct ParsedIbkr {
     pub instruments: Vec<Value>,
     pub positions: Vec<Value>,
     pub transactions: Vec<Value>,
+    /// Per-instrument "Mark-to-Market Performance Summary" rows for the
+    /// statement period, keyed by `instrument_id` and `date`.
+    pub mtm_performance: Vec<Value>,
+    /// "Cash Report" starting and ending cash per currency, as
+    /// `currency -> (starting, ending)`. See [`reconcile_cash_report`].
+    pub cash_report: HashMap<String, (f64, f64)>,
 }
 
 pub fn merge_instruments_with_deduplication(
@@ -222,8 +254,7 @@ fn parse_statement_end(period: &str) -> Result<NaiveDate> {
     let normalized = period
         .trim()
         .trim_matches('"')
-        .replace('–', "-")
-        .replace('—', "-");
+        .replace(['–', '—'], "-");
 
     if let Some((_, end)) = normalized.split_once('-') {
         return parse_flexible_date(end.trim());
@@ -321,7 +352,7 @@ pub(crate) fn build_instrument_id(
     symbol: &str,
 ) -> String {
     let sid = security_id.trim();
-    if looks_like_isin(sid) {
+    if utils::is_valid_isin(sid) {
         return format!("IBKR_{}", sid);
     }
 
@@ -386,13 +417,6 @@ fn instrument_completeness_score(inst: &Value) -> usize {
         .count()
 }
 
-fn looks_like_isin(s: &str) -> bool {
-    if s.len() != 12 {
-        return false;
-    }
-    s.chars().all(|c| c.is_ascii_alphanumeric())
-}
-
 pub(crate) fn build_txn_id(
     prefix: &str,
     date: NaiveDate,
@@ -401,17 +425,16 @@ pub(crate) fn build_txn_id(
     description: &str,
     extra: &str,
 ) -> String {
-    let key = format!(
-        "{}|{}|{:.8}|{}|{}|{}",
+    utils::compute_txn_id(
         prefix,
-        date.format("%Y-%m-%d"),
-        amount,
-        currency.trim(),
-        description.trim(),
-        extra.trim()
-    );
-    let hash = make_hash_id(&key);
-    format!("{}-{}", prefix, &hash[..24])
+        &[
+            &date.format("%Y-%m-%d").to_string(),
+            &format!("{:.8}", amount),
+            currency,
+            description,
+            extra,
+        ],
+    )
 }
 
 pub(crate) fn make_hash_id(s: &str) -> String {
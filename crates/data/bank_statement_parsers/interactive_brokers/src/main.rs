@@ -25,9 +25,31 @@ impl utils::ParserContract for IbkrImportContract {
     }
 
     fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        utils::ConcurrentParserContract::parse_file_shared(self, input_file_path)
+    }
+
+    fn as_concurrent(&self) -> Option<&dyn utils::ConcurrentParserContract> {
+        Some(self)
+    }
+
+    fn finalize_entities(
+        &mut self,
+        mut entities: utils::ParsedEntities,
+    ) -> Result<utils::ParsedEntities> {
+        entities.accounts = self.parser.create_accounts();
+        Ok(entities)
+    }
+
+    fn pipeline_profile(&self) -> utils::PipelineProfile {
+        utils::PipelineProfile::Default
+    }
+}
+
+impl utils::ConcurrentParserContract for IbkrImportContract {
+    fn parse_file_shared(&self, input_file_path: &str) -> Result<utils::ParsedEntities> {
         let parsed = self.parser.parse_file(input_file_path)?;
 
-        println!(
+        log::info!(
             "  ✓ Found {} txns, {} instruments, {} positions (as_of={})",
             parsed.transactions.len(),
             parsed.instruments.len(),
@@ -38,6 +60,12 @@ impl utils::ParserContract for IbkrImportContract {
                 .unwrap_or_else(|| "unknown".to_string())
         );
 
+        ibkr_parser::reconcile_cash_report(
+            &self.parser,
+            &parsed,
+            ibkr_parser::CASH_REPORT_TOLERANCE,
+        );
+
         Ok(utils::ParsedEntities {
             accounts: Vec::new(),
             instruments: parsed.instruments,
@@ -45,21 +73,10 @@ impl utils::ParserContract for IbkrImportContract {
             transactions: parsed.transactions,
         })
     }
-
-    fn finalize_entities(
-        &mut self,
-        mut entities: utils::ParsedEntities,
-    ) -> Result<utils::ParsedEntities> {
-        entities.accounts = self.parser.create_accounts();
-        Ok(entities)
-    }
-
-    fn pipeline_profile(&self) -> utils::PipelineProfile {
-        utils::PipelineProfile::Default
-    }
 }
 
 fn main() -> Result<()> {
+    utils::init_console_logging();
     let args: Vec<String> = env::args().collect();
     let mut contract = IbkrImportContract::new();
     utils::run_parser_contract_cli(&mut contract, &args, "../../../../database")
@@ -0,0 +1,157 @@
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::{
+    build_instrument_id, null_if_empty_string, parse_f64_opt, primary_symbol,
+    resolve_instrument_id, IbkrCsvParser, SectionHeader,
+};
+
+const MTM_SECTION: &str = "Mark-to-Market Performance Summary";
+
+/// A row of the MTM summary. The instrument is linked once the whole file is
+/// read, because "Financial Instrument Information" comes after this section.
+#[derive(Debug, Clone)]
+pub(crate) struct MtmRowData {
+    asset_category: String,
+    symbol: String,
+    prior_quantity: Option<f64>,
+    current_quantity: Option<f64>,
+    prior_price: Option<f64>,
+    current_price: Option<f64>,
+    pnl_position: Option<f64>,
+    pnl_transaction: Option<f64>,
+    pnl_commissions: Option<f64>,
+    pnl_other: Option<f64>,
+    pnl_total: Option<f64>,
+}
+
+impl MtmRowData {
+    fn from_row(header: &SectionHeader, row: &[String]) -> Self {
+        let number = |col: &str| parse_f64_opt(header.get(row, col).unwrap_or(""));
+        Self {
+            asset_category: header
+                .get(row, "Asset Category")
+                .unwrap_or("")
+                .trim()
+                .to_string(),
+            symbol: header.get(row, "Symbol").unwrap_or("").trim().to_string(),
+            prior_quantity: number("Prior Quantity"),
+            current_quantity: number("Current Quantity"),
+            prior_price: number("Prior Price"),
+            current_price: number("Current Price"),
+            pnl_position: number("Mark-to-Market P/L Position"),
+            pnl_transaction: number("Mark-to-Market P/L Transaction"),
+            pnl_commissions: number("Mark-to-Market P/L Commissions"),
+            pnl_other: number("Mark-to-Market P/L Other"),
+            pnl_total: number("Mark-to-Market P/L Total"),
+        }
+    }
+}
+
+pub fn parse_mtm_row(
+    section: &str,
+    headers: &HashMap<String, SectionHeader>,
+    row: &[String],
+    rows: &mut Vec<MtmRowData>,
+) -> Result<bool> {
+    if section != MTM_SECTION {
+        return Ok(false);
+    }
+
+    let header = headers
+        .get(section)
+        .ok_or_else(|| anyhow!("Missing header for section {}", section))?;
+
+    let row_data = MtmRowData::from_row(header, row);
+    if !field_is_total_row(&row_data) {
+        rows.push(row_data);
+    }
+    Ok(true)
+}
+
+/// Turns the collected rows into `mtm_performance` entries keyed by
+/// `instrument_id` and the statement end date.
+pub fn build_mtm_entries(
+    parser: &IbkrCsvParser,
+    rows: &[MtmRowData],
+    as_of_date: NaiveDate,
+    instrument_key_to_id: &HashMap<String, String>,
+    instrument_id_remap: &HashMap<String, String>,
+) -> Vec<Value> {
+    rows.iter()
+        .map(|row| {
+            json!({
+                "instrument_id": field_instrument_id(row, instrument_key_to_id, instrument_id_remap),
+                "date": as_of_date.format("%Y-%m-%d").to_string(),
+                "account_id": parser.account_id_savings,
+                "asset_category": null_if_empty_string(&row.asset_category),
+                "symbol": null_if_empty_string(&row.symbol),
+                "prior_quantity": row.prior_quantity,
+                "current_quantity": row.current_quantity,
+                "prior_price": row.prior_price,
+                "current_price": row.current_price,
+                "mtm_pnl_position": row.pnl_position,
+                "mtm_pnl_transaction": row.pnl_transaction,
+                "mtm_pnl_commissions": row.pnl_commissions,
+                "mtm_pnl_other": row.pnl_other,
+                "mtm_pnl_total": row.pnl_total
+            })
+        })
+        .collect()
+}
+
+/// IBKR marks (sub)totals with "Total..." in the asset category column and
+/// leaves the symbol empty.
+fn field_is_total_row(row: &MtmRowData) -> bool {
+    row.symbol.is_empty() || row.asset_category.starts_with("Total")
+}
+
+fn field_instrument_id(
+    row: &MtmRowData,
+    instrument_key_to_id: &HashMap<String, String>,
+    instrument_id_remap: &HashMap<String, String>,
+) -> String {
+    let key = format!("{}|{}", row.asset_category, row.symbol);
+    match instrument_key_to_id.get(&key) {
+        Some(id) => resolve_instrument_id(id, instrument_id_remap),
+        None => build_instrument_id("", "", &row.asset_category, &primary_symbol(&row.symbol)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::IbkrCsvParser;
+
+    const STATEMENT: &str = "\
+Statement,Header,Field Name,Field Value
+Statement,Data,Period,\"January 1, 2025 - January 31, 2025\"
+Mark-to-Market Performance Summary,Header,Asset Category,Symbol,Prior Quantity,Current Quantity,Prior Price,Current Price,Mark-to-Market P/L Position,Mark-to-Market P/L Transaction,Mark-to-Market P/L Commissions,Mark-to-Market P/L Other,Mark-to-Market P/L Total,Code
+Mark-to-Market Performance Summary,Data,Stocks,VWCE,10,12,100,105,50,8,-2,0,56,
+Mark-to-Market Performance Summary,Data,Stocks,AAPL,5,5,200,190,-50,0,0,0,-50,
+Mark-to-Market Performance Summary,Data,Total Stocks,,,,,,0,8,-2,0,6,
+Mark-to-Market Performance Summary,Data,Total P/L for Statement Period,,,,,,0,8,-2,0,6,
+Financial Instrument Information,Header,Asset Category,Symbol,Description,Conid,Security ID,Listing Exch,Multiplier,Type,Code
+Financial Instrument Information,Data,Stocks,VWCE,VANGUARD FTSE ALL-WORLD,12345,IE00BK5BQT80,IBIS2,1,ETF,
+";
+
+    #[test]
+    fn parses_mtm_rows_linked_to_instruments() {
+        let parsed = IbkrCsvParser::new()
+            .parse_reader(STATEMENT.as_bytes())
+            .unwrap();
+
+        let mtm = &parsed.mtm_performance;
+        assert_eq!(mtm.len(), 2);
+
+        assert_eq!(mtm[0]["instrument_id"], "IBKR_IE00BK5BQT80");
+        assert_eq!(mtm[0]["date"], "2025-01-31");
+        assert_eq!(mtm[0]["prior_quantity"], 10.0);
+        assert_eq!(mtm[0]["current_price"], 105.0);
+        assert_eq!(mtm[0]["mtm_pnl_total"], 56.0);
+
+        assert_eq!(mtm[1]["instrument_id"], "IBKR_Stocks_AAPL");
+        assert_eq!(mtm[1]["mtm_pnl_position"], -50.0);
+    }
+}
@@ -9,6 +9,7 @@ use crate::{
     IbkrCsvParser, SectionHeader,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub fn parse_position_row(
     parser: &IbkrCsvParser,
     section: &str,
@@ -22,9 +22,21 @@ pub enum FileType {
     Portfolio,
 }
 
+/// How reliable a `FileType` guess from `detect_file_type` is.
+///
+/// `Low` means no filename, sheet-name or header marker matched and the
+/// detector fell back to its default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetectionConfidence {
+    High,
+    Low,
+}
+
 pub struct IntesaSanpaoloParser {
     pub account_id_checking: String,
     pub account_id_trading: String,
+    /// Rows dated before this are skipped.
+    pub since: Option<NaiveDate>,
 }
 
 impl IntesaSanpaoloParser {
@@ -32,21 +44,37 @@ impl IntesaSanpaoloParser {
         Self {
             account_id_checking: INTESA_CHECKING.to_string(),
             account_id_trading: INTESA_SAVINGS.to_string(),
+            since: None,
         }
     }
 
+    /// Skips rows dated before `since`, before their txn_id is computed.
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = Some(since);
+        self
+    }
+
     pub fn create_accounts(&self) -> Vec<Value> {
         accounts::create_all_accounts(self)
     }
 
-    pub fn detect_file_type<P: AsRef<Path>>(&self, path: P) -> Result<FileType> {
-        let path_str = path.as_ref().to_string_lossy().to_lowercase();
+    pub fn detect_file_type<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(FileType, DetectionConfidence)> {
+        // Only the file name: a directory such as `.../intesa_sanpaolo/...`
+        // says nothing about the export inside it.
+        let path_str = path
+            .as_ref()
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
 
         if path_str.contains("patrimonio")
             || path_str.contains("portfolio")
             || path_str.contains("holdings")
         {
-            return Ok(FileType::Portfolio);
+            return Ok((FileType::Portfolio, DetectionConfidence::High));
         }
 
         if path_str.contains("movimenti")
@@ -54,58 +82,50 @@ impl IntesaSanpaoloParser {
             || path_str.contains("transactions")
             || path_str.contains("intesa_sanpaolo")
         {
-            return Ok(FileType::Transactions);
+            return Ok((FileType::Transactions, DetectionConfidence::High));
         }
 
         let mut workbook: Xlsx<_> = open_workbook(&path)
             .with_context(|| format!("Failed to open workbook: {}", path.as_ref().display()))?;
 
         let sheet_names = workbook.sheet_names().to_vec();
-        for name in &sheet_names {
-            let name_lower = name.to_lowercase();
-            if name_lower.contains("lista operazione") || name_lower.contains("movimenti") {
-                return Ok(FileType::Transactions);
-            }
-            if name_lower.contains("patrimonio") || name_lower.contains("portfolio") {
-                return Ok(FileType::Portfolio);
-            }
-        }
+        let first_range = sheet_names
+            .first()
+            .and_then(|name| workbook.worksheet_range(name).ok());
 
-        if let Some(first_sheet) = sheet_names.first() {
-            if let Ok(range) = workbook.worksheet_range(first_sheet) {
-                for row_idx in 0..range.get_size().0.min(15) {
-                    for col_idx in 0..range.get_size().1 {
-                        if let Some(cell) = range.get((row_idx, col_idx)) {
-                            let text = cell.to_string().to_lowercase();
-                            if text == "isin" {
-                                return Ok(FileType::Portfolio);
-                            }
-                            if text.contains("importo") && (text.contains("data") || row_idx < 3) {
-                                for check_col in 0..range.get_size().1 {
-                                    if let Some(check_cell) = range.get((row_idx, check_col)) {
-                                        let check_text = check_cell.to_string().to_lowercase();
-                                        if check_text.contains("operazione") || check_text == "data" {
-                                            return Ok(FileType::Transactions);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        Ok(detect_file_type_from_sheets(&sheet_names, first_range.as_ref()))
+    }
+
+    pub fn parse_file<P: AsRef<Path>>(&self, path: P) -> Result<ParsedIntesa> {
+        let (file_type, confidence) = self.detect_file_type(&path)?;
+        let mut parsed = self.parse_file_with_type(&path, file_type)?;
+
+        if confidence == DetectionConfidence::Low {
+            let warning = format!(
+                "Could not determine file type for {}, defaulting to transactions",
+                path.as_ref().display()
+            );
+            log::warn!("{}", warning);
+            parsed.warnings.push(warning);
         }
 
-        println!(
-            "⚠️  Could not determine file type for {}, defaulting to transactions",
-            path.as_ref().display()
-        );
-        Ok(FileType::Transactions)
+        Ok(parsed)
     }
 
-    pub fn parse_file<P: AsRef<Path>>(&self, path: P) -> Result<ParsedIntesa> {
-        let file_type = self.detect_file_type(&path)?;
-        self.parse_file_with_type(path, file_type)
+    /// Like [`Self::parse_file`], but refuses to guess: a low-confidence
+    /// detection is an error instead of a fallback to transactions. Use
+    /// [`Self::parse_file_with_type`] to force a type.
+    pub fn parse_file_strict<P: AsRef<Path>>(&self, path: P) -> Result<ParsedIntesa> {
+        let (file_type, confidence) = self.detect_file_type(&path)?;
+
+        if confidence == DetectionConfidence::Low {
+            return Err(anyhow!(
+                "Could not determine file type for {} (not a recognised transactions or portfolio export)",
+                path.as_ref().display()
+            ));
+        }
+
+        self.parse_file_with_type(&path, file_type)
     }
 
     pub fn parse_file_with_type<P: AsRef<Path>>(
@@ -127,6 +147,7 @@ impl IntesaSanpaoloParser {
                     positions: Vec::new(),
                     instruments: Vec::new(),
                     file_type,
+                    warnings: Vec::new(),
                 })
             }
             FileType::Portfolio => {
@@ -137,6 +158,7 @@ impl IntesaSanpaoloParser {
                     positions,
                     instruments,
                     file_type,
+                    warnings: Vec::new(),
                 })
             }
         }
@@ -154,6 +176,52 @@ pub struct ParsedIntesa {
     pub positions: Vec<Value>,
     pub instruments: Vec<Value>,
     pub file_type: FileType,
+    /// Non-fatal issues found while parsing (e.g. a low-confidence file type guess).
+    pub warnings: Vec<String>,
+}
+
+/// Inspects sheet names and the first rows of the first sheet to guess the file type.
+///
+/// Falls back to `FileType::Transactions` with `DetectionConfidence::Low` when
+/// no known marker is found.
+pub(crate) fn detect_file_type_from_sheets(
+    sheet_names: &[String],
+    first_range: Option<&calamine::Range<calamine::Data>>,
+) -> (FileType, DetectionConfidence) {
+    for name in sheet_names {
+        let name_lower = name.to_lowercase();
+        if name_lower.contains("lista operazione") || name_lower.contains("movimenti") {
+            return (FileType::Transactions, DetectionConfidence::High);
+        }
+        if name_lower.contains("patrimonio") || name_lower.contains("portfolio") {
+            return (FileType::Portfolio, DetectionConfidence::High);
+        }
+    }
+
+    if let Some(range) = first_range {
+        for row_idx in 0..range.get_size().0.min(15) {
+            for col_idx in 0..range.get_size().1 {
+                if let Some(cell) = range.get((row_idx, col_idx)) {
+                    let text = cell.to_string().to_lowercase();
+                    if text == "isin" {
+                        return (FileType::Portfolio, DetectionConfidence::High);
+                    }
+                    if text.contains("importo") && (text.contains("data") || row_idx < 3) {
+                        for check_col in 0..range.get_size().1 {
+                            if let Some(check_cell) = range.get((row_idx, check_col)) {
+                                let check_text = check_cell.to_string().to_lowercase();
+                                if check_text.contains("operazione") || check_text == "data" {
+                                    return (FileType::Transactions, DetectionConfidence::High);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    (FileType::Transactions, DetectionConfidence::Low)
 }
 
 pub(crate) fn parse_date_or_serial(s: &str) -> Result<NaiveDate> {
@@ -161,10 +229,8 @@ pub(crate) fn parse_date_or_serial(s: &str) -> Result<NaiveDate> {
 
     if let Ok(serial) = s.parse::<f64>() {
         if (1.0..100000.0).contains(&serial) {
-            let days = serial.floor() as i64;
-            let base_date = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
-            if let Some(date) = base_date.checked_add_signed(chrono::Duration::days(days)) {
-                return Ok(date);
+            if let Some(datetime) = utils::excel_serial_to_naive_datetime(serial) {
+                return Ok(datetime.date());
             }
         }
     }
@@ -191,6 +257,15 @@ pub(crate) fn parse_date(s: &str) -> Result<NaiveDate> {
     Err(anyhow!("Unrecognized date format: {}", s))
 }
 
+/// Parses an amount written as text, e.g. "1.234,56 €".
+///
+/// A comma marks Italian formatting: dots are thousands separators and the
+/// comma is the decimal point. Without a comma, a dot is read as a thousands
+/// separator when every group after it has exactly three digits and the first
+/// group is 1-3 digits without a leading zero ("1.234" and "12.345.678" are
+/// thousands; "1.23" and "0.125" are decimals). The limit: a US-style value
+/// with exactly three decimals, such as "1.234" shares, reads as 1234. Numeric
+/// cells never hit this; use [`parse_amount_cell`] for spreadsheet cells.
 pub(crate) fn parse_amount(s: &str) -> Result<f64> {
     let s = s.trim();
     if s.is_empty() || s == "-" || s == "--" {
@@ -203,6 +278,8 @@ pub(crate) fn parse_amount(s: &str) -> Result<f64> {
     if cleaned.contains(',') {
         cleaned = cleaned.replace('.', "");
         cleaned = cleaned.replace(',', ".");
+    } else if has_dot_thousands_separators(&cleaned) {
+        cleaned = cleaned.replace('.', "");
     }
 
     cleaned
@@ -210,6 +287,31 @@ pub(crate) fn parse_amount(s: &str) -> Result<f64> {
         .with_context(|| format!("Failed to parse amount: {}", s))
 }
 
+/// Reads an amount cell. Numeric cells are taken as-is; text cells go through
+/// [`parse_amount`].
+pub(crate) fn parse_amount_cell(cell: &calamine::Data) -> Result<f64> {
+    match cell {
+        calamine::Data::Float(value) => Ok(*value),
+        calamine::Data::Int(value) => Ok(*value as f64),
+        other => parse_amount(&other.to_string()),
+    }
+}
+
+fn has_dot_thousands_separators(s: &str) -> bool {
+    let digits = s.trim_start_matches(['-', '+']);
+    let mut groups = digits.split('.');
+    let Some(first) = groups.next() else {
+        return false;
+    };
+    let first_ok = (1..=3).contains(&first.len())
+        && !first.starts_with('0')
+        && first.chars().all(|c| c.is_ascii_digit());
+    let mut rest = groups.peekable();
+    first_ok
+        && rest.peek().is_some()
+        && rest.all(|group| group.len() == 3 && group.chars().all(|c| c.is_ascii_digit()))
+}
+
 pub(crate) fn extract_portfolio_date(
     range: &calamine::Range<calamine::Data>,
 ) -> Option<NaiveDate> {
@@ -325,9 +427,19 @@ pub(crate) fn make_hash(s: &str) -> String {
     hex::encode(hash)
 }
 
+/// Bond coupons (cedola/cedole) and accrued interest (rateo/ratei) are
+/// trading-account income whatever sign the export gives them. Taxes and fees
+/// on a coupon ("imposta su cedola") stay expenses.
+pub(crate) fn intesa_is_coupon(desc: &str) -> bool {
+    let d = desc.to_lowercase();
+    !intesa_force_expense(desc)
+        && d.split(|c: char| !c.is_alphanumeric())
+            .any(|word| matches!(word, "cedola" | "cedole" | "rateo" | "ratei"))
+}
+
 pub(crate) fn intesa_force_expense(desc: &str) -> bool {
     let d = desc.to_lowercase();
-    d.contains("canone") || d.contains("spese") || d.contains("imposta")
+    d.contains("canone") || d.contains("spese") || d.contains("imposta") || d.contains("ritenuta")
 }
 
 pub fn merge_instruments_with_deduplication(
@@ -336,3 +448,71 @@ pub fn merge_instruments_with_deduplication(
 ) -> Result<(Value, utils::MergeStats)> {
     utils::merge_instruments_with_deduplication(template, new_instruments)
 }
+
+/// Builds a sheet of string cells for parser tests.
+#[cfg(test)]
+pub(crate) fn range_from_rows(rows: &[&[&str]]) -> calamine::Range<calamine::Data> {
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(1) as u32;
+    let mut range = calamine::Range::new((0, 0), (rows.len() as u32 - 1, width - 1));
+    for (r, row) in rows.iter().enumerate() {
+        for (c, text) in row.iter().enumerate() {
+            range.set_value(
+                (r as u32, c as u32),
+                calamine::Data::String(text.to_string()),
+            );
+        }
+    }
+    range
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::Data;
+
+    #[test]
+    fn detect_file_type_reports_low_confidence_on_ambiguous_workbook() {
+        let sheet_names = vec!["Foglio1".to_string()];
+        let range = range_from_rows(&[&["Riepilogo", "Valore"], &["Totale", "1.234,56"]]);
+
+        let (file_type, confidence) = detect_file_type_from_sheets(&sheet_names, Some(&range));
+
+        assert_eq!(file_type, FileType::Transactions);
+        assert_eq!(confidence, DetectionConfidence::Low);
+    }
+
+    #[test]
+    fn parse_file_strict_rejects_ambiguous_workbook() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/riepilogo.xlsx");
+
+        let parser = IntesaSanpaoloParser::new();
+        let strict = parser.parse_file_strict(path);
+        let lenient = parser.parse_file(path).unwrap();
+
+        assert!(strict.is_err());
+        assert_eq!(lenient.file_type, FileType::Transactions);
+        assert_eq!(lenient.warnings.len(), 1);
+    }
+
+    #[test]
+    fn parse_amount_disambiguates_dot_separators() {
+        assert_eq!(parse_amount("1.234").unwrap(), 1234.0);
+        assert_eq!(parse_amount("1.23").unwrap(), 1.23);
+        assert_eq!(parse_amount("1.234,56").unwrap(), 1234.56);
+        assert_eq!(parse_amount("1234").unwrap(), 1234.0);
+        assert_eq!(parse_amount("-12.345.678").unwrap(), -12345678.0);
+        assert_eq!(parse_amount("0.125").unwrap(), 0.125);
+        assert_eq!(parse_amount_cell(&Data::Float(1.234)).unwrap(), 1.234);
+    }
+
+    #[test]
+    fn detect_file_type_reports_high_confidence_on_portfolio_headers() {
+        let sheet_names = vec!["Foglio1".to_string()];
+        let range = range_from_rows(&[&["Titolo", "ISIN", "Quantità"]]);
+
+        let (file_type, confidence) = detect_file_type_from_sheets(&sheet_names, Some(&range));
+
+        assert_eq!(file_type, FileType::Portfolio);
+        assert_eq!(confidence, DetectionConfidence::High);
+    }
+}
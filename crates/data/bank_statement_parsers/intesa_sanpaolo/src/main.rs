@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use std::env;
 
 use intesa_sanpaolo::IntesaSanpaoloParser;
@@ -24,10 +25,36 @@ impl utils::ParserContract for IntesaImportContract {
         &[utils::InputFormat::Excel]
     }
 
+    fn set_since(&mut self, since: NaiveDate) {
+        self.parser.since = Some(since);
+    }
+
     fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        utils::ConcurrentParserContract::parse_file_shared(self, input_file_path)
+    }
+
+    fn as_concurrent(&self) -> Option<&dyn utils::ConcurrentParserContract> {
+        Some(self)
+    }
+
+    fn finalize_entities(
+        &mut self,
+        mut entities: utils::ParsedEntities,
+    ) -> Result<utils::ParsedEntities> {
+        entities.accounts = self.parser.create_accounts();
+        Ok(entities)
+    }
+
+    fn pipeline_profile(&self) -> utils::PipelineProfile {
+        utils::PipelineProfile::Default
+    }
+}
+
+impl utils::ConcurrentParserContract for IntesaImportContract {
+    fn parse_file_shared(&self, input_file_path: &str) -> Result<utils::ParsedEntities> {
         let parsed = self.parser.parse_file(input_file_path)?;
 
-        println!(
+        log::info!(
             "  ✓ Found {} txns, {} instruments, {} positions",
             parsed.transactions.len(),
             parsed.instruments.len(),
@@ -41,21 +68,10 @@ impl utils::ParserContract for IntesaImportContract {
             transactions: parsed.transactions,
         })
     }
-
-    fn finalize_entities(
-        &mut self,
-        mut entities: utils::ParsedEntities,
-    ) -> Result<utils::ParsedEntities> {
-        entities.accounts = self.parser.create_accounts();
-        Ok(entities)
-    }
-
-    fn pipeline_profile(&self) -> utils::PipelineProfile {
-        utils::PipelineProfile::Default
-    }
 }
 
 fn main() -> Result<()> {
+    utils::init_console_logging();
     let args: Vec<String> = env::args().collect();
     let mut contract = IntesaImportContract::new();
     utils::run_parser_contract_cli(&mut contract, &args, "../../../../database")
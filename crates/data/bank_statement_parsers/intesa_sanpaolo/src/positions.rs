@@ -7,7 +7,7 @@ use utils::{build_position, PositionInput};
 use crate::instruments::{map_instrument_from_row, PortfolioRowData};
 use crate::{
     extract_portfolio_date, extract_row_date_from_column_g, is_portfolio_date_header, make_hash,
-    parse_amount, parse_date_or_serial, IntesaSanpaoloParser,
+    parse_amount_cell, parse_date_or_serial, IntesaSanpaoloParser,
 };
 
 pub fn parse_portfolio<R: std::io::Read + std::io::Seek>(
@@ -114,19 +114,19 @@ fn parse_portfolio_sheet(
                 .unwrap_or_default(),
             quantity: quantita_col
                 .and_then(|col| range.get((row_idx, col)))
-                .and_then(|c| parse_amount(&c.to_string()).ok()),
+                .and_then(|c| parse_amount_cell(c).ok()),
             cost_price: prezzo_carico_col
                 .and_then(|col| range.get((row_idx, col)))
-                .and_then(|c| parse_amount(&c.to_string()).ok()),
+                .and_then(|c| parse_amount_cell(c).ok()),
             close_price: prezzo_mercato_col
                 .and_then(|col| range.get((row_idx, col)))
-                .and_then(|c| parse_amount(&c.to_string()).ok()),
+                .and_then(|c| parse_amount_cell(c).ok()),
             market_value: controvalore_col
                 .and_then(|col| range.get((row_idx, col)))
-                .and_then(|c| parse_amount(&c.to_string()).ok()),
+                .and_then(|c| parse_amount_cell(c).ok()),
             cost_basis: valore_carico_col
                 .and_then(|col| range.get((row_idx, col)))
-                .and_then(|c| parse_amount(&c.to_string()).ok()),
+                .and_then(|c| parse_amount_cell(c).ok()),
             position_as_of_date: as_of_col
                 .and_then(|col| range.get((row_idx, col)))
                 .and_then(|c| parse_date_or_serial(&c.to_string()).ok())
@@ -5,8 +5,8 @@ use serde_json::Value;
 use utils::{build_transaction, TransactionInput};
 
 use crate::{
-    determine_transaction_type, intesa_force_expense, make_txn_id, parse_amount, parse_date_or_serial,
-    IntesaSanpaoloParser,
+    determine_transaction_type, intesa_force_expense, intesa_is_coupon, make_txn_id, parse_amount,
+    parse_amount_cell, parse_date_or_serial, IntesaSanpaoloParser,
 };
 
 pub fn parse_transactions<R: std::io::Read + std::io::Seek>(
@@ -69,6 +69,9 @@ fn parse_transaction_sheet(
 
         let date = parse_date_or_serial(&date_str)
             .with_context(|| format!("Failed to parse date: {} at row {}", date_str, row_idx))?;
+        if parser.since.is_some_and(|since| date < since) {
+            continue;
+        }
 
         let description = field_description(range, row_idx, &mapping);
         let currency = field_currency(range, row_idx, &mapping);
@@ -77,12 +80,14 @@ fn parse_transaction_sheet(
         let from_account_id = field_from_account_id(parser, account_id, amount, &description, &txn_type);
         let to_account_id = field_to_account_id(parser, account_id, amount, &description, &txn_type);
         let normalized_amount = field_normalized_amount(amount);
+        let category = field_category(&description);
 
         let row_data = TransactionRowData {
             date,
             from_account_id,
             to_account_id,
             transaction_type: txn_type,
+            category,
             amount: normalized_amount,
             currency,
             description,
@@ -179,6 +184,7 @@ struct TransactionRowData {
     from_account_id: String,
     to_account_id: String,
     transaction_type: String,
+    category: String,
     amount: f64,
     currency: String,
     description: String,
@@ -190,7 +196,7 @@ fn map_transaction_from_row(row: &TransactionRowData) -> Value {
         from_account_id: field_from(row),
         to_account_id: field_to(row),
         transaction_type: field_transaction_type(row),
-        category: field_category(),
+        category: field_category_out(row),
         amount: field_amount_abs(row),
         currency: field_currency_out(row),
         description: field_description_out(row),
@@ -215,8 +221,8 @@ fn field_transaction_type(row: &TransactionRowData) -> String {
     row.transaction_type.clone()
 }
 
-fn field_category() -> String {
-    "uncategorized".to_string()
+fn field_category_out(row: &TransactionRowData) -> String {
+    row.category.clone()
 }
 
 fn field_amount_abs(row: &TransactionRowData) -> f64 {
@@ -260,19 +266,21 @@ fn field_amount(
             anyhow::bail!("empty importo");
         }
 
-        return parse_amount(&importo_str)
+        return parse_amount_cell(importo_cell)
             .with_context(|| format!("Failed to parse amount: {}", importo_str));
     }
 
-    let accrediti_str = mapping
+    let accrediti_cell = mapping
         .accrediti_col
-        .and_then(|col| range.get((row_idx, col)))
+        .and_then(|col| range.get((row_idx, col)));
+    let accrediti_str = accrediti_cell
         .map(|c| c.to_string().trim().to_string())
         .unwrap_or_default();
 
-    let addebiti_str = mapping
+    let addebiti_cell = mapping
         .addebiti_col
-        .and_then(|col| range.get((row_idx, col)))
+        .and_then(|col| range.get((row_idx, col)));
+    let addebiti_str = addebiti_cell
         .map(|c| c.to_string().trim().to_string())
         .unwrap_or_default();
 
@@ -281,9 +289,12 @@ fn field_amount(
     }
 
     if !accrediti_str.is_empty() {
-        parse_amount(&accrediti_str).with_context(|| format!("Failed to parse accrediti: {}", accrediti_str))
+        accrediti_cell
+            .map_or_else(|| parse_amount(&accrediti_str), parse_amount_cell)
+            .with_context(|| format!("Failed to parse accrediti: {}", accrediti_str))
     } else {
-        let addebiti_amount = parse_amount(&addebiti_str)
+        let addebiti_amount = addebiti_cell
+            .map_or_else(|| parse_amount(&addebiti_str), parse_amount_cell)
             .with_context(|| format!("Failed to parse addebiti: {}", addebiti_str))?;
         Ok(-addebiti_amount)
     }
@@ -347,6 +358,10 @@ fn field_account_id<'a>(
     mapping: &HeaderMapping,
     description: &str,
 ) -> &'a str {
+    if intesa_is_coupon(description) {
+        return &parser.account_id_trading;
+    }
+
     if let Some(col) = mapping.conto_col {
         let conto_text = range
             .get((row_idx, col))
@@ -367,6 +382,10 @@ fn field_account_id<'a>(
 }
 
 fn field_type(account_id: &str, amount: f64, description: &str) -> String {
+    if intesa_is_coupon(description) {
+        return "income".to_string();
+    }
+
     let (mut txn_type, _, _) = determine_transaction_type(account_id, amount);
 
     if intesa_force_expense(description) {
@@ -383,6 +402,10 @@ fn field_from_account_id(
     description: &str,
     txn_type: &str,
 ) -> String {
+    if intesa_is_coupon(description) {
+        return "EXTERNAL_PAYER".to_string();
+    }
+
     let (_, mut from_account, _) = determine_transaction_type(account_id, amount);
 
     if intesa_force_expense(description) || txn_type == "expense" {
@@ -393,12 +416,16 @@ fn field_from_account_id(
 }
 
 fn field_to_account_id(
-    _parser: &IntesaSanpaoloParser,
+    parser: &IntesaSanpaoloParser,
     account_id: &str,
     amount: f64,
     description: &str,
     txn_type: &str,
 ) -> String {
+    if intesa_is_coupon(description) {
+        return parser.account_id_trading.clone();
+    }
+
     let (_, _, mut to_account) = determine_transaction_type(account_id, amount);
 
     if intesa_force_expense(description) || txn_type == "expense" {
@@ -411,3 +438,63 @@ fn field_to_account_id(
 fn field_normalized_amount(amount: f64) -> f64 {
     amount.abs()
 }
+
+fn field_category(description: &str) -> String {
+    if intesa_is_coupon(description) {
+        "coupon".to_string()
+    } else {
+        "uncategorized".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range_from_rows;
+
+    #[test]
+    fn coupon_rows_are_trading_income_and_pos_rows_are_untouched() {
+        let range = range_from_rows(&[
+            &["Data", "Operazione", "Dettagli", "Conto o carta", "Valuta", "Importo"],
+            &["15/03/2024", "Accredito cedole", "BTP 2030", "Conto corrente", "EUR", "-12,50"],
+            &["16/03/2024", "Pagamento POS", "SUPERMERCATO", "Carta di debito", "EUR", "-30,00"],
+        ]);
+        let parser = IntesaSanpaoloParser::new();
+
+        let txns = parse_transaction_sheet(&parser, &range).unwrap();
+
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0]["type"], "income");
+        assert_eq!(txns[0]["category"], "coupon");
+        assert_eq!(txns[0]["from_account_id"], "EXTERNAL_PAYER");
+        assert_eq!(txns[0]["to_account_id"], parser.account_id_trading.as_str());
+        assert_eq!(txns[0]["amount"], 12.5);
+
+        assert_eq!(txns[1]["type"], "expense");
+        assert_eq!(txns[1]["category"], "uncategorized");
+        assert_eq!(txns[1]["from_account_id"], parser.account_id_checking.as_str());
+        assert_eq!(txns[1]["to_account_id"], "EXTERNAL_PAYEE");
+    }
+
+    #[test]
+    fn tax_on_coupon_and_instalment_rows_are_not_coupons() {
+        let range = range_from_rows(&[
+            &["Data", "Operazione", "Dettagli", "Conto o carta", "Valuta", "Importo"],
+            &["15/03/2024", "Imposta su cedola", "BTP 2030", "Conto corrente", "EUR", "-1,56"],
+            &["20/03/2024", "Rateizzazione acquisto", "Finanziamento", "Conto corrente", "EUR", "-50,00"],
+        ]);
+        let parser = IntesaSanpaoloParser::new();
+
+        let txns = parse_transaction_sheet(&parser, &range).unwrap();
+
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0]["type"], "expense");
+        assert_eq!(txns[0]["category"], "uncategorized");
+        assert_eq!(txns[0]["from_account_id"], parser.account_id_checking.as_str());
+        assert_eq!(txns[0]["to_account_id"], "EXTERNAL_PAYEE");
+
+        assert_eq!(txns[1]["type"], "expense");
+        assert_eq!(txns[1]["category"], "uncategorized");
+        assert_eq!(txns[1]["from_account_id"], parser.account_id_checking.as_str());
+    }
+}
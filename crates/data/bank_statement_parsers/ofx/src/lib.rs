@@ -0,0 +1,72 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde_json::Value;
+
+mod accounts;
+mod transactions;
+
+pub const PARSER_NAME: &str = "ofx";
+
+/// Generic parser for OFX/QFX bank downloads, for banks without a dedicated
+/// parser crate. Reads both SGML-style OFX 1.x and XML OFX 2.x.
+pub struct OfxParser {
+    pub account_id: String,
+    pub institution: String,
+    /// Used when the statement has no `<CURDEF>`.
+    pub default_currency: String,
+    /// Rows dated before this are skipped.
+    pub since: Option<NaiveDate>,
+}
+
+impl OfxParser {
+    pub fn new(account_id: impl Into<String>) -> Self {
+        Self {
+            account_id: account_id.into(),
+            institution: "OFX import".to_string(),
+            default_currency: "EUR".to_string(),
+            since: None,
+        }
+    }
+
+    /// Skips rows dated before `since`, before their txn_id is computed.
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn with_institution(mut self, institution: impl Into<String>) -> Self {
+        self.institution = institution.into();
+        self
+    }
+
+    pub fn with_default_currency(mut self, currency: impl Into<String>) -> Self {
+        self.default_currency = currency.into();
+        self
+    }
+
+    pub fn create_accounts(&self) -> Vec<Value> {
+        accounts::create_accounts(self)
+    }
+
+    pub fn parse_file(&self, ofx_path: &str) -> Result<Vec<Value>> {
+        transactions::parse_transactions(self, ofx_path)
+    }
+
+    pub fn parse_str(&self, content: &str) -> Result<Vec<Value>> {
+        transactions::parse_content(self, content)
+    }
+}
+
+pub fn merge_transactions_into_template(
+    template: Value,
+    new_txns: Vec<Value>,
+) -> Result<(Value, utils::transactions::MergeStats)> {
+    utils::merge_transactions_with_deduplication(template, new_txns)
+}
+
+pub fn merge_accounts_into_template(
+    template: Value,
+    new_accounts: Vec<Value>,
+) -> Result<(Value, utils::accounts::MergeStats)> {
+    utils::merge_accounts_with_deduplication(template, new_accounts)
+}
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::env;
+
+use ofx_parser::OfxParser;
+
+struct OfxImportContract {
+    parser: OfxParser,
+}
+
+impl OfxImportContract {
+    fn new() -> Self {
+        Self {
+            // Change the account id and institution to the bank being imported.
+            parser: OfxParser::new("OFX_ACCOUNT").with_institution("OFX import"),
+        }
+    }
+}
+
+impl utils::ParserContract for OfxImportContract {
+    fn parser_name(&self) -> &'static str {
+        ofx_parser::PARSER_NAME
+    }
+
+    fn supported_input_formats(&self) -> &'static [utils::InputFormat] {
+        &[utils::InputFormat::Ofx]
+    }
+
+    fn set_since(&mut self, since: NaiveDate) {
+        self.parser.since = Some(since);
+    }
+
+    fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        utils::ConcurrentParserContract::parse_file_shared(self, input_file_path)
+    }
+
+    fn as_concurrent(&self) -> Option<&dyn utils::ConcurrentParserContract> {
+        Some(self)
+    }
+
+    fn finalize_entities(
+        &mut self,
+        mut entities: utils::ParsedEntities,
+    ) -> Result<utils::ParsedEntities> {
+        entities.accounts = self.parser.create_accounts();
+        Ok(entities)
+    }
+
+    fn pipeline_profile(&self) -> utils::PipelineProfile {
+        utils::PipelineProfile::Default
+    }
+}
+
+impl utils::ConcurrentParserContract for OfxImportContract {
+    fn parse_file_shared(&self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        let txns = self
+            .parser
+            .parse_file(input_file_path)
+            .with_context(|| format!("Failed parsing {}", input_file_path))?;
+
+        Ok(utils::ParsedEntities {
+            transactions: txns,
+            ..Default::default()
+        })
+    }
+}
+
+fn main() -> Result<()> {
+    utils::init_console_logging();
+    // Usage:
+    //   ofx_parser [database_path] [output_path]
+    //
+    // Auto-discovers all .ofx/.qfx files in current directory.
+    let args: Vec<String> = env::args().collect();
+    let mut contract = OfxImportContract::new();
+    utils::run_parser_contract_cli(&mut contract, &args, "../../../../database")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::ParserContract;
+
+    fn fixture_files() -> Vec<String> {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+        let mut files: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path().to_string_lossy().into_owned())
+            .filter(|path| path.ends_with(".ofx"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn concurrent_parsing_matches_sequential_parsing() {
+        let input_files = fixture_files();
+        assert_eq!(input_files.len(), 4);
+
+        let mut contract = OfxImportContract::new();
+        let mut sequential = utils::ParsedEntities::default();
+        utils::for_each_input_file(&input_files, |path| {
+            sequential.append(contract.parse_file(path)?);
+            Ok(())
+        })
+        .unwrap();
+
+        let concurrent_contract = contract.as_concurrent().unwrap();
+        let mut concurrent = utils::ParsedEntities::default();
+        let results = utils::parse_input_files_concurrently(&input_files, |path| {
+            concurrent_contract.parse_file_shared(path)
+        });
+        for result in results {
+            concurrent.append(result.unwrap());
+        }
+
+        assert_eq!(concurrent.transactions.len(), 24);
+        assert_eq!(concurrent.transactions, sequential.transactions);
+        let dates: Vec<&str> = concurrent
+            .transactions
+            .iter()
+            .map(|txn| txn["date"].as_str().unwrap())
+            .collect();
+        assert!(dates.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use serde_json::Value;
+use utils::{build_transaction, compute_txn_id, TransactionInput};
+
+use crate::OfxParser;
+
+pub fn parse_transactions(parser: &OfxParser, ofx_path: &str) -> Result<Vec<Value>> {
+    let bytes = std::fs::read(ofx_path).with_context(|| format!("Cannot open {}", ofx_path))?;
+    // OFX 1.x files are often Windows-1252; only the tag values can be
+    // non-ASCII, so a lossy decode keeps the structure intact.
+    let content = String::from_utf8_lossy(&bytes);
+    parse_content(parser, &content)
+}
+
+/// Walks every `<STMTTRN>` aggregate. Both OFX versions close aggregates,
+/// while OFX 1.x (SGML) leaves leaf elements such as `<TRNAMT>` unclosed, so
+/// leaf values are read up to the next tag regardless of version.
+pub fn parse_content(parser: &OfxParser, content: &str) -> Result<Vec<Value>> {
+    let currency = leaf_value(content, "CURDEF").unwrap_or_else(|| parser.default_currency.clone());
+
+    let mut out = Vec::new();
+    for (idx, block) in stmttrn_blocks(content).enumerate() {
+        if let Some(since) = parser.since {
+            if field_date(block).with_context(|| format!("STMTTRN #{}", idx + 1))? < since {
+                continue;
+            }
+        }
+        out.push(
+            build_stmttrn(parser, block, &currency, idx)
+                .with_context(|| format!("STMTTRN #{}", idx + 1))?,
+        );
+    }
+    Ok(out)
+}
+
+fn stmttrn_blocks(content: &str) -> impl Iterator<Item = &str> {
+    content.split("<STMTTRN>").skip(1).map(|rest| {
+        let end = rest.find("</STMTTRN>").unwrap_or(rest.len());
+        &rest[..end]
+    })
+}
+
+fn build_stmttrn(parser: &OfxParser, block: &str, currency: &str, idx: usize) -> Result<Value> {
+    let signed_amount = field_amount(block)?;
+    let date = field_date(block)?;
+    let description = field_description(block);
+    let is_income = signed_amount >= 0.0;
+
+    // `FITID` is unique per account at the bank, so it alone is the dedup key.
+    let txn_id = match leaf_value(block, "FITID") {
+        Some(fitid) => compute_txn_id("OFX", &[&parser.account_id, &fitid]),
+        None => compute_txn_id(
+            "OFX",
+            &[
+                &parser.account_id,
+                &date.format("%Y-%m-%d").to_string(),
+                &format!("{:.8}", signed_amount),
+                &description,
+                &idx.to_string(),
+            ],
+        ),
+    };
+
+    Ok(build_transaction(&TransactionInput {
+        date: date.format("%Y-%m-%d").to_string(),
+        from_account_id: field_from_account_id(parser, is_income),
+        to_account_id: field_to_account_id(parser, is_income),
+        transaction_type: field_type(is_income),
+        category: field_category(),
+        amount: signed_amount.abs(),
+        currency: currency.to_string(),
+        description,
+        description_en: None,
+        txn_id,
+    }))
+}
+
+/// Returns the text after `<TAG>` up to the next `<`, entity-decoded.
+fn leaf_value(content: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = content.find(&open)? + open.len();
+    let rest = &content[start..];
+    let end = rest.find('<').unwrap_or(rest.len());
+    let value = decode_entities(rest[..end].trim());
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn field_amount(block: &str) -> Result<f64> {
+    let raw = leaf_value(block, "TRNAMT").ok_or_else(|| anyhow!("Missing TRNAMT"))?;
+    raw.replace(',', ".")
+        .parse::<f64>()
+        .with_context(|| format!("Invalid TRNAMT: {}", raw))
+}
+
+/// `DTPOSTED` is `YYYYMMDD` optionally followed by time and a `[tz]` suffix.
+fn field_date(block: &str) -> Result<NaiveDate> {
+    let raw = leaf_value(block, "DTPOSTED").ok_or_else(|| anyhow!("Missing DTPOSTED"))?;
+    let ymd = raw.get(..8).unwrap_or(&raw);
+    NaiveDate::parse_from_str(ymd, "%Y%m%d").with_context(|| format!("Invalid DTPOSTED: {}", raw))
+}
+
+fn field_description(block: &str) -> String {
+    match (leaf_value(block, "NAME"), leaf_value(block, "MEMO")) {
+        (Some(name), Some(memo)) if name != memo => format!("{} - {}", name, memo),
+        (Some(name), _) => name,
+        (None, Some(memo)) => memo,
+        (None, None) => String::new(),
+    }
+}
+
+fn field_from_account_id(parser: &OfxParser, is_income: bool) -> String {
+    if is_income {
+        "EXTERNAL_PAYER".to_string()
+    } else {
+        parser.account_id.clone()
+    }
+}
+
+fn field_to_account_id(parser: &OfxParser, is_income: bool) -> String {
+    if is_income {
+        parser.account_id.clone()
+    } else {
+        "EXTERNAL_PAYEE".to_string()
+    }
+}
+
+fn field_type(is_income: bool) -> String {
+    if is_income { "income" } else { "expense" }.to_string()
+}
+
+fn field_category() -> String {
+    "uncategorized".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFX_V1: &str = "OFXHEADER:100\n\
+DATA:OFXSGML\n\
+VERSION:102\n\
+\n\
+<OFX>\n\
+<BANKMSGSRSV1><STMTTRNRS><STMTRS>\n\
+<CURDEF>USD\n\
+<BANKTRANLIST>\n\
+<STMTTRN>\n\
+<TRNTYPE>DEBIT\n\
+<DTPOSTED>20240105120000[-5:EST]\n\
+<TRNAMT>-42.50\n\
+<FITID>2024010501\n\
+<NAME>CORNER GROCERY\n\
+<MEMO>Card 1234\n\
+</STMTTRN>\n\
+<STMTTRN>\n\
+<TRNTYPE>CREDIT\n\
+<DTPOSTED>20240110\n\
+<TRNAMT>1500.00\n\
+<FITID>2024011001\n\
+<NAME>PAYROLL\n\
+</STMTTRN>\n\
+</BANKTRANLIST>\n\
+</STMTRS></STMTTRNRS></BANKMSGSRSV1>\n\
+</OFX>\n";
+
+    const OFX_V2: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE"?>
+<OFX>
+  <BANKMSGSRSV1><STMTTRNRS><STMTRS>
+    <CURDEF>EUR</CURDEF>
+    <BANKTRANLIST>
+      <STMTTRN>
+        <TRNTYPE>DEBIT</TRNTYPE>
+        <DTPOSTED>20240203</DTPOSTED>
+        <TRNAMT>-9.99</TRNAMT>
+        <FITID>A-77</FITID>
+        <NAME>Books &amp; Co</NAME>
+      </STMTTRN>
+    </BANKTRANLIST>
+  </STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>
+"#;
+
+    fn parser() -> OfxParser {
+        OfxParser::new("MYBANK_CHECKING")
+    }
+
+    #[test]
+    fn parses_sgml_ofx_v1() {
+        let txns = parser().parse_str(OFX_V1).unwrap();
+
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0]["date"], "2024-01-05");
+        assert_eq!(txns[0]["type"], "expense");
+        assert_eq!(txns[0]["from_account_id"], "MYBANK_CHECKING");
+        assert_eq!(txns[0]["to_account_id"], "EXTERNAL_PAYEE");
+        assert_eq!(txns[0]["amount"], 42.5);
+        assert_eq!(txns[0]["currency"], "USD");
+        assert_eq!(txns[0]["description"], "CORNER GROCERY - Card 1234");
+
+        assert_eq!(txns[1]["type"], "income");
+        assert_eq!(txns[1]["from_account_id"], "EXTERNAL_PAYER");
+        assert_eq!(txns[1]["to_account_id"], "MYBANK_CHECKING");
+        assert_eq!(txns[1]["amount"], 1500.0);
+        assert_eq!(txns[1]["description"], "PAYROLL");
+    }
+
+    #[test]
+    fn parses_xml_ofx_v2() {
+        let txns = parser().parse_str(OFX_V2).unwrap();
+
+        assert_eq!(txns.len(), 1);
+        assert_eq!(txns[0]["date"], "2024-02-03");
+        assert_eq!(txns[0]["amount"], 9.99);
+        assert_eq!(txns[0]["currency"], "EUR");
+        assert_eq!(txns[0]["description"], "Books & Co");
+    }
+
+    #[test]
+    fn txn_id_depends_only_on_account_and_fitid() {
+        let first = parser().parse_str(OFX_V1).unwrap();
+        let edited = OFX_V1.replace("CORNER GROCERY", "Corner Grocery Inc");
+        let second = parser().parse_str(&edited).unwrap();
+
+        assert_eq!(first[0]["txn_id"], second[0]["txn_id"]);
+        assert_ne!(first[0]["txn_id"], first[1]["txn_id"]);
+        assert!(first[0]["txn_id"].as_str().unwrap().starts_with("OFX-"));
+    }
+
+    #[test]
+    fn missing_curdef_uses_default_currency() {
+        let content = OFX_V1.replace("<CURDEF>USD\n", "");
+        let txns = parser()
+            .with_default_currency("GBP")
+            .parse_str(&content)
+            .unwrap();
+
+        assert_eq!(txns[0]["currency"], "GBP");
+    }
+
+    #[test]
+    fn since_skips_earlier_rows_and_keeps_cutoff_day() {
+        let all = parser().parse_str(OFX_V1).unwrap();
+        let since = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let recent = parser().with_since(since).parse_str(OFX_V1).unwrap();
+
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0]["date"], "2024-01-10");
+        assert_eq!(recent[0]["txn_id"], all[1]["txn_id"]);
+    }
+}
@@ -1,4 +1,4 @@
-//! Maps Revolut account variants (current/savings) into normalized account records.
+//! Maps Revolut account variants (current/savings/other products) into normalized account records.
 
 use serde_json::Value;
 use utils::{build_account, AccountInput};
@@ -17,6 +17,8 @@ pub fn create_used_accounts(parser: &RevolutCsvParser, used_account_ids: &[Strin
             out.push(build_current_account(parser));
         } else if account_id == &parser.account_id_savings {
             out.push(build_savings_account(parser));
+        } else {
+            out.push(build_product_account(account_id));
         }
     }
 
@@ -45,6 +47,17 @@ fn build_savings_account(parser: &RevolutCsvParser) -> Value {
     })
 }
 
+fn build_product_account(account_id: &str) -> Value {
+    build_account(&AccountInput {
+        account_id: account_id.to_string(),
+        institution: field_institution(),
+        country: field_country(),
+        iban: field_iban(),
+        bic: field_bic(),
+        is_active: field_is_active(),
+    })
+}
+
 fn field_account_id_current(parser: &RevolutCsvParser) -> String {
     parser.account_id_current.clone()
 }
@@ -1,6 +1,9 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::io::Read;
+use utils::StatusFilter;
 
 mod accounts;
 mod instruments;
@@ -10,12 +13,31 @@ mod transactions;
 pub const PARSER_NAME: &str = "revolut";
 
 pub struct RevolutCsvParser {
+    pub account_id_base: String,
     pub account_id_current: String,
     pub account_id_savings: String,
-    pub only_completed: bool,
+    /// Explicit `Product` column value -> account ID routes. `Current` and
+    /// `Savings` fall back to the two account IDs above when not listed.
+    pub product_account_map: HashMap<String, String>,
+    /// Which `State` values become transactions. Pending rows are tagged
+    /// `"pending": true`.
+    pub status_filter: StatusFilter,
+    /// When set, each row's `Balance` is checked against the previous
+    /// balance of the same product and currency plus the signed amount minus
+    /// the fee.
+    pub balance_validation: bool,
+    /// Rows dated before this are skipped.
+    pub since: Option<NaiveDate>,
 }
 
 impl RevolutCsvParser {
+    /// Creates a parser for the given Revolut account ID.
+    ///
+    /// Both account IDs are derived from `account_id` by stripping a trailing
+    /// `_CURRENT`/`_SAVINGS` and re-appending the suffix, so `REVOLUT` yields
+    /// `REVOLUT_CURRENT` and `REVOLUT_SAVINGS`. This derivation is only a
+    /// fallback: use [`RevolutCsvParser::with_savings_account_id`] when the
+    /// savings ID does not follow that pattern.
     pub fn new(account_id: impl Into<String>) -> Self {
         let input = account_id.into();
 
@@ -27,12 +49,73 @@ impl RevolutCsvParser {
         Self {
             account_id_current: format!("{}_CURRENT", base),
             account_id_savings: format!("{}_SAVINGS", base),
-            only_completed: true,
+            account_id_base: base,
+            product_account_map: HashMap::new(),
+            status_filter: StatusFilter::CompletedOnly,
+            balance_validation: false,
+            since: None,
+        }
+    }
+
+    /// Overrides the auto-derived savings account ID.
+    pub fn with_savings_account_id(mut self, account_id: impl Into<String>) -> Self {
+        self.account_id_savings = account_id.into();
+        self
+    }
+
+    /// Routes rows whose `Product` column equals `product` to `account_id`.
+    pub fn with_product_account_id(
+        mut self,
+        product: impl Into<String>,
+        account_id: impl Into<String>,
+    ) -> Self {
+        self.product_account_map
+            .insert(product.into(), account_id.into());
+        self
+    }
+
+    /// Resolves the account for a `Product` value: an explicit mapping wins,
+    /// then `Current`/`Savings` (or an empty product) use the default IDs, and
+    /// any other product gets `{base}_{PRODUCT}`, e.g. `REVOLUT_COMMODITIES`.
+    pub fn account_id_for_product(&self, product: Option<&str>) -> String {
+        let product = product.map(str::trim).unwrap_or("");
+        if let Some(account_id) = self.product_account_map.get(product) {
+            return account_id.clone();
+        }
+
+        match product {
+            "" | "Current" => self.account_id_current.clone(),
+            "Savings" => self.account_id_savings.clone(),
+            other => format!(
+                "{}_{}",
+                self.account_id_base,
+                other
+                    .to_uppercase()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join("_")
+            ),
         }
     }
 
     pub fn with_only_completed(mut self, only_completed: bool) -> Self {
-        self.only_completed = only_completed;
+        self.status_filter = StatusFilter::from_only_completed(only_completed);
+        self
+    }
+
+    pub fn with_status_filter(mut self, status_filter: StatusFilter) -> Self {
+        self.status_filter = status_filter;
+        self
+    }
+
+    pub fn with_balance_validation(mut self, balance_validation: bool) -> Self {
+        self.balance_validation = balance_validation;
+        self
+    }
+
+    /// Skips rows dated before `since`, before their txn_id is computed.
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = Some(since);
         self
     }
 
@@ -44,11 +127,24 @@ impl RevolutCsvParser {
         transactions::parse_transactions(self, reader)
     }
 
+    /// Like [`RevolutCsvParser::parse_reader`], but also returns the
+    /// balance-validation warnings.
+    pub fn parse_reader_with_warnings<R: Read>(&self, reader: R) -> Result<ParsedRevolut> {
+        transactions::parse_transactions_with_warnings(self, reader)
+    }
+
     pub fn create_used_accounts(&self, used_account_ids: &[String]) -> Vec<Value> {
         accounts::create_used_accounts(self, used_account_ids)
     }
 }
 
+pub struct ParsedRevolut {
+    pub transactions: Vec<Value>,
+    pub used_account_ids: Vec<String>,
+    /// Balance discontinuities; empty unless `with_balance_validation(true)`.
+    pub warnings: Vec<String>,
+}
+
 /// Merges Revolut transactions into an existing database.json Value.
 /// Assumes database.json has a top level "transactions": [] array.
 /// Automatically skips duplicate transactions based on txn_id.
@@ -105,6 +201,75 @@ Transfer,Savings,2026-01-07 10:00:00,2026-01-07 10:00:00,Transfer from pocket,12
         assert!(used_accounts.contains(&"REVOLUT_SAVINGS".to_string()));
     }
 
+    #[test]
+    fn new_derives_savings_account_id_from_base() {
+        let parser = RevolutCsvParser::new("REVOLUT_CURRENT");
+
+        assert_eq!(parser.account_id_current, "REVOLUT_CURRENT");
+        assert_eq!(parser.account_id_savings, "REVOLUT_SAVINGS");
+    }
+
+    #[test]
+    fn with_savings_account_id_overrides_derivation() {
+        let csv = "Type,Product,Started Date,Completed Date,Description,Amount,Fee,Currency,State,Balance\n\
+Transfer,Savings,2026-01-07 10:00:00,2026-01-07 10:00:00,Transfer from pocket,12.0,0,EUR,COMPLETED,250\n";
+
+        let parser = RevolutCsvParser::new("REVOLUT_CURRENT_ACCOUNT")
+            .with_savings_account_id("REVOLUT_VAULT");
+        let (_txns, used_accounts) = parser.parse_reader(csv.as_bytes()).unwrap();
+
+        assert_eq!(parser.account_id_savings, "REVOLUT_VAULT");
+        assert!(used_accounts.contains(&"REVOLUT_VAULT".to_string()));
+    }
+
+    const PRODUCT_CSV: &str = "Type,Product,Started Date,Completed Date,Description,Amount,Fee,Currency,State,Balance\n\
+Card Payment,Current,2026-01-05 10:00:00,2026-01-05 10:00:00,Coffee,-4.5,0,EUR,COMPLETED,100\n\
+Transfer,Savings,2026-01-07 10:00:00,2026-01-07 10:00:00,Interest,1.0,0,EUR,COMPLETED,250\n\
+Exchange,Commodities,2026-01-08 10:00:00,2026-01-08 10:00:00,Exchanged to XAU,20.0,0,EUR,COMPLETED,20\n\
+Trade,Crypto Pocket,2026-01-09 10:00:00,2026-01-09 10:00:00,Bought BTC,15.0,0,EUR,COMPLETED,15\n";
+
+    #[test]
+    fn account_id_for_product_uses_defaults_for_current_and_savings() {
+        let parser = RevolutCsvParser::new("REVOLUT");
+
+        assert_eq!(
+            parser.account_id_for_product(Some("Current")),
+            "REVOLUT_CURRENT"
+        );
+        assert_eq!(parser.account_id_for_product(None), "REVOLUT_CURRENT");
+        assert_eq!(
+            parser.account_id_for_product(Some("Savings")),
+            "REVOLUT_SAVINGS"
+        );
+    }
+
+    #[test]
+    fn mapped_product_routes_to_its_account() {
+        let parser =
+            RevolutCsvParser::new("REVOLUT").with_product_account_id("Commodities", "REVOLUT_GOLD");
+        let (txns, used_accounts) = parser.parse_reader(PRODUCT_CSV.as_bytes()).unwrap();
+
+        assert_eq!(txns[2]["to_account_id"], "REVOLUT_GOLD");
+        assert!(used_accounts.contains(&"REVOLUT_GOLD".to_string()));
+        assert_eq!(
+            parser.create_used_accounts(&used_accounts).len(),
+            used_accounts.len()
+        );
+    }
+
+    #[test]
+    fn unmapped_product_gets_a_derived_account_id() {
+        let parser = RevolutCsvParser::new("REVOLUT");
+        let (_txns, used_accounts) = parser.parse_reader(PRODUCT_CSV.as_bytes()).unwrap();
+
+        assert!(used_accounts.contains(&"REVOLUT_COMMODITIES".to_string()));
+        assert!(used_accounts.contains(&"REVOLUT_CRYPTO_POCKET".to_string()));
+
+        let accounts = parser.create_used_accounts(&["REVOLUT_CRYPTO_POCKET".to_string()]);
+        assert_eq!(accounts[0]["account_id"], "REVOLUT_CRYPTO_POCKET");
+        assert_eq!(accounts[0]["institution"], "Revolut");
+    }
+
     #[test]
     fn create_used_accounts_returns_only_requested_accounts() {
         let parser = RevolutCsvParser::new("REVOLUT");
@@ -117,4 +282,79 @@ Transfer,Savings,2026-01-07 10:00:00,2026-01-07 10:00:00,Transfer from pocket,12
             .unwrap();
         assert_eq!(account_id, "REVOLUT_CURRENT");
     }
+
+    const BALANCE_CSV: &str =
+        "Type,Product,Started Date,Completed Date,Description,Amount,Fee,Currency,State,Balance\n\
+Topup,Current,2026-01-01 10:00:00,2026-01-01 10:00:00,Top-up,100.0,0,EUR,COMPLETED,100\n\
+Card Payment,Current,2026-01-02 10:00:00,2026-01-02 10:00:00,Coffee,-4.5,0.5,EUR,COMPLETED,95\n\
+Card Payment,Current,2026-01-05 10:00:00,2026-01-05 10:00:00,Groceries,-20.0,0,EUR,COMPLETED,45\n";
+
+    #[test]
+    fn balance_validation_warns_on_missing_rows() {
+        let parser = RevolutCsvParser::new("REVOLUT").with_balance_validation(true);
+        let parsed = parser
+            .parse_reader_with_warnings(BALANCE_CSV.as_bytes())
+            .unwrap();
+
+        // 95 - 20 = 75, but the statement says 45: a 30 EUR row is missing.
+        assert_eq!(parsed.warnings.len(), 1);
+        assert!(parsed.warnings[0].starts_with("Row 3 (REVOLUT_CURRENT EUR)"));
+        assert!(parsed.warnings[0].contains("expected 75.00"));
+    }
+
+    #[test]
+    fn balance_validation_is_off_by_default() {
+        let parser = RevolutCsvParser::new("REVOLUT");
+        let parsed = parser
+            .parse_reader_with_warnings(BALANCE_CSV.as_bytes())
+            .unwrap();
+
+        assert!(parsed.warnings.is_empty());
+        assert_eq!(parsed.transactions.len(), 4);
+    }
+
+    const STATUS_CSV: &str =
+        "Type,Product,Started Date,Completed Date,Description,Amount,Fee,Currency,State,Balance\n\
+Card Payment,Current,2026-01-05 10:00:00,2026-01-05 10:00:00,Coffee,-4.5,0,EUR,COMPLETED,100\n\
+Card Payment,Current,2026-01-06 10:00:00,,Hotel hold,-80.0,1.0,EUR,PENDING,\n\
+Card Payment,Current,2026-01-07 10:00:00,,Declined,-3.0,0,EUR,DECLINED,\n";
+
+    #[test]
+    fn status_filter_controls_pending_rows() {
+        let parse = |filter| {
+            let parser = RevolutCsvParser::new("REVOLUT").with_status_filter(filter);
+            parser.parse_reader(STATUS_CSV.as_bytes()).unwrap().0
+        };
+
+        let completed = parse(StatusFilter::CompletedOnly);
+        assert_eq!(completed.len(), 1);
+        assert!(completed[0].get("pending").is_none());
+
+        // The pending row and its fee are both tagged.
+        let with_pending = parse(StatusFilter::IncludePending);
+        assert_eq!(with_pending.len(), 3);
+        assert_eq!(with_pending[1]["pending"], true);
+        assert_eq!(with_pending[2]["pending"], true);
+
+        assert_eq!(parse(StatusFilter::All).len(), 4);
+    }
+
+    #[test]
+    fn with_since_skips_rows_before_cutoff() {
+        let all = RevolutCsvParser::new("REVOLUT")
+            .parse_reader(PRODUCT_CSV.as_bytes())
+            .unwrap()
+            .0;
+        let since = NaiveDate::from_ymd_opt(2026, 1, 7).unwrap();
+        let recent = RevolutCsvParser::new("REVOLUT")
+            .with_since(since)
+            .parse_reader(PRODUCT_CSV.as_bytes())
+            .unwrap()
+            .0;
+
+        let dates: Vec<&str> = recent.iter().map(|t| t["date"].as_str().unwrap()).collect();
+        assert_eq!(dates, vec!["2026-01-07", "2026-01-08", "2026-01-09"]);
+        // Later rows keep the txn_ids of a full import.
+        assert_eq!(recent[0]["txn_id"], all[1]["txn_id"]);
+    }
 }
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use std::{collections::HashSet, env, fs::File, io::Read};
 
 use revolut::RevolutCsvParser;
@@ -26,17 +27,21 @@ impl utils::ParserContract for RevolutImportContract {
         &[utils::InputFormat::Csv]
     }
 
+    fn set_since(&mut self, since: NaiveDate) {
+        self.parser.since = Some(since);
+    }
+
     fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
         let mut csv_file = File::open(input_file_path)
             .with_context(|| format!("Cannot open {}", input_file_path))?;
         let mut csv_buf = Vec::new();
         csv_file.read_to_end(&mut csv_buf)?;
 
-        let (txns, used_accounts) = self.parser.parse_reader(csv_buf.as_slice())?;
-        self.used_account_ids.extend(used_accounts);
+        let parsed = self.parser.parse_reader_with_warnings(csv_buf.as_slice())?;
+        self.used_account_ids.extend(parsed.used_account_ids);
 
         Ok(utils::ParsedEntities {
-            transactions: txns,
+            transactions: parsed.transactions,
             ..Default::default()
         })
     }
@@ -56,6 +61,7 @@ impl utils::ParserContract for RevolutImportContract {
 }
 
 fn main() -> Result<()> {
+    utils::init_console_logging();
     // Usage:
     //   revolut_parser [database_path] [output_path]
     //
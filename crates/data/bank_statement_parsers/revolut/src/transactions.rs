@@ -5,11 +5,11 @@ use chrono::{NaiveDate, NaiveDateTime};
 use serde::Deserialize;
 use serde_json::Value;
 use sha2::{Digest, Sha256};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::Read;
-use utils::{build_transaction, TransactionInput};
+use utils::{build_transaction, mark_pending, RowStatus, TransactionInput};
 
-use crate::RevolutCsvParser;
+use crate::{ParsedRevolut, RevolutCsvParser};
 
 #[derive(Debug, Deserialize)]
 struct RevolutRow {
@@ -48,6 +48,14 @@ pub fn parse_transactions<R: Read>(
     parser: &RevolutCsvParser,
     reader: R,
 ) -> Result<(Vec<Value>, Vec<String>)> {
+    let parsed = parse_transactions_with_warnings(parser, reader)?;
+    Ok((parsed.transactions, parsed.used_account_ids))
+}
+
+pub fn parse_transactions_with_warnings<R: Read>(
+    parser: &RevolutCsvParser,
+    reader: R,
+) -> Result<ParsedRevolut> {
     let mut csv_reader = csv::ReaderBuilder::new()
         .flexible(true)
         .trim(csv::Trim::All)
@@ -55,18 +63,22 @@ pub fn parse_transactions<R: Read>(
 
     let mut out: Vec<Value> = Vec::new();
     let mut used_accounts = HashSet::new();
+    let mut pending_exchange: Option<ExchangeLeg> = None;
+    let mut warnings = Vec::new();
+    let mut previous_balances: HashMap<(String, String), f64> = HashMap::new();
 
     for (idx, row) in csv_reader.deserialize::<RevolutRow>().enumerate() {
         let row = row.with_context(|| format!("CSV deserialize error at row {}", idx + 1))?;
 
-        if parser.only_completed {
-            let state = row.state.as_deref().unwrap_or("");
-            if state != "COMPLETED" {
-                continue;
-            }
+        let status = field_status(&row);
+        if !parser.status_filter.keeps(status) {
+            continue;
         }
 
         let date = field_date(&row).with_context(|| format!("Invalid date at row {}", idx + 1))?;
+        if parser.since.is_some_and(|since| date < since) {
+            continue;
+        }
         let description = field_description(&row);
         let currency = field_currency(&row);
         let amount = field_amount(&row);
@@ -85,9 +97,33 @@ pub fn parse_transactions<R: Read>(
         {
             used_accounts.insert(parser.account_id_savings.clone());
         }
+        if from_account_id == account_id || to_account_id == account_id {
+            used_accounts.insert(account_id.clone());
+        }
 
         let txn_id = field_txn_id(&account_id, date, amount, &currency, &description, idx + 1);
 
+        // Exchange legs are paired only when they are adjacent rows with the
+        // same timestamp and description; anything else resets the candidate.
+        let exchange_leg = is_exchange_row(&row).then(|| ExchangeLeg {
+            timestamp: field_timestamp(&row),
+            description: description.clone(),
+            amount,
+            out_index: out.len(),
+            txn_id: txn_id.clone(),
+        });
+        let mut fx_group_id = None;
+        pending_exchange = match (pending_exchange.take(), exchange_leg) {
+            (Some(first), Some(second)) if first.pairs_with(&second) => {
+                let group_id = field_fx_group_id(&first.txn_id, &second.txn_id);
+                set_fx_group_id(&mut out[first.out_index], &group_id);
+                fx_group_id = Some(group_id);
+                None
+            }
+            (_, leg) => leg,
+        };
+        let main_txn_index = out.len();
+
         out.push(build_transaction(&TransactionInput {
             date: date.format("%Y-%m-%d").to_string(),
             from_account_id,
@@ -101,6 +137,10 @@ pub fn parse_transactions<R: Read>(
             txn_id,
         }));
 
+        if let Some(group_id) = fx_group_id {
+            set_fx_group_id(&mut out[main_txn_index], &group_id);
+        }
+
         if let Some(fee) = field_fee(&row) {
             if fee != 0.0 {
                 let fee_description = format!("Fees: {}", description);
@@ -115,12 +155,12 @@ pub fn parse_transactions<R: Read>(
 
                 out.push(build_transaction(&TransactionInput {
                     date: date.format("%Y-%m-%d").to_string(),
-                    from_account_id: account_id,
+                    from_account_id: account_id.clone(),
                     to_account_id: "EXTERNAL_PAYEE".to_string(),
                     transaction_type: "expense".to_string(),
                     category: field_category(),
                     amount: fee.abs(),
-                    currency,
+                    currency: currency.clone(),
                     description: fee_description,
                     description_en: field_description_en(),
                     txn_id: fee_txn_id,
@@ -128,10 +168,57 @@ pub fn parse_transactions<R: Read>(
             }
         }
 
-        let _ = &row.balance;
+        if status == RowStatus::Pending {
+            out[main_txn_index..].iter_mut().for_each(mark_pending);
+        }
+
+        if parser.balance_validation {
+            if let Some(balance) = field_balance(&row) {
+                let key = (account_id, currency);
+                if let Some(&previous) = previous_balances.get(&key) {
+                    if let Some(warning) = check_balance_continuity(
+                        previous,
+                        balance,
+                        amount,
+                        field_fee(&row).unwrap_or(0.0),
+                        idx + 1,
+                        &key,
+                    ) {
+                        log::warn!("{}", warning);
+                        warnings.push(warning);
+                    }
+                }
+                previous_balances.insert(key, balance);
+            }
+        }
     }
 
-    Ok((out, used_accounts.into_iter().collect()))
+    Ok(ParsedRevolut {
+        transactions: out,
+        used_account_ids: used_accounts.into_iter().collect(),
+        warnings,
+    })
+}
+
+/// Checks that `balance` equals `previous_balance + amount - fee`, i.e. that
+/// no rows of the same account and currency are missing in between.
+fn check_balance_continuity(
+    previous_balance: f64,
+    balance: f64,
+    amount: f64,
+    fee: f64,
+    row_number: usize,
+    (account_id, currency): &(String, String),
+) -> Option<String> {
+    let expected = previous_balance + amount - fee;
+    if (balance - expected).abs() < 0.005 {
+        return None;
+    }
+
+    Some(format!(
+        "Row {} ({} {}): balance {:.2} does not follow previous balance {:.2} with amount {:.2} and fee {:.2} (expected {:.2}); rows may be missing",
+        row_number, account_id, currency, balance, previous_balance, amount, fee, expected
+    ))
 }
 
 fn field_date(row: &RevolutRow) -> Result<NaiveDate> {
@@ -153,10 +240,7 @@ fn field_date(row: &RevolutRow) -> Result<NaiveDate> {
 }
 
 fn field_account_id(parser: &RevolutCsvParser, row: &RevolutRow) -> String {
-    match row.product.as_deref() {
-        Some("Savings") => parser.account_id_savings.clone(),
-        _ => parser.account_id_current.clone(),
-    }
+    parser.account_id_for_product(row.product.as_deref())
 }
 
 fn field_type(row: &RevolutRow, amount: f64, description: &str) -> String {
@@ -183,10 +267,22 @@ fn field_description_en() -> Option<String> {
     None
 }
 
+fn field_status(row: &RevolutRow) -> RowStatus {
+    match row.state.as_deref().unwrap_or("") {
+        "COMPLETED" => RowStatus::Completed,
+        "PENDING" => RowStatus::Pending,
+        _ => RowStatus::Other,
+    }
+}
+
 fn field_fee(row: &RevolutRow) -> Option<f64> {
     row.fee
 }
 
+fn field_balance(row: &RevolutRow) -> Option<f64> {
+    row.balance
+}
+
 fn field_txn_id(
     account_id: &str,
     date: NaiveDate,
@@ -212,6 +308,57 @@ fn field_txn_id(
     format!("REVOLUT-{}", hex::encode(&hash[..12]))
 }
 
+/// One side of a currency conversion, remembered until the next row is read.
+struct ExchangeLeg {
+    timestamp: String,
+    description: String,
+    amount: f64,
+    out_index: usize,
+    txn_id: String,
+}
+
+impl ExchangeLeg {
+    /// Two legs form a conversion when they share timestamp and description
+    /// and move money in opposite directions.
+    fn pairs_with(&self, other: &ExchangeLeg) -> bool {
+        self.timestamp == other.timestamp
+            && self.description == other.description
+            && (self.amount < 0.0) != (other.amount < 0.0)
+    }
+}
+
+fn is_exchange_row(row: &RevolutRow) -> bool {
+    row.revolut_type
+        .as_deref()
+        .map(|t| t.to_lowercase().contains("exchange"))
+        .unwrap_or(false)
+}
+
+fn field_timestamp(row: &RevolutRow) -> String {
+    row.completed_date
+        .as_ref()
+        .or(row.started_date.as_ref())
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn field_fx_group_id(first_txn_id: &str, second_txn_id: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}|{}", first_txn_id, second_txn_id).as_bytes());
+    let hash = hasher.finalize();
+
+    format!("REVOLUT-FX-{}", hex::encode(&hash[..12]))
+}
+
+fn set_fx_group_id(txn: &mut Value, group_id: &str) {
+    if let Some(obj) = txn.as_object_mut() {
+        obj.insert(
+            "fx_group_id".to_string(),
+            Value::String(group_id.to_string()),
+        );
+    }
+}
+
 fn field_accounts(
     parser: &RevolutCsvParser,
     account_id: &str,
@@ -233,13 +380,13 @@ fn infer_type(amount: f64, revolut_type: Option<&str>, description: &str) -> Str
     let rt = revolut_type.unwrap_or("").to_lowercase();
 
     if rt.contains("transfer") {
-        if description.starts_with("Transfer to ") {
-            "expense".to_string()
-        } else if description.starts_with("To ") && !description.contains("To pocket") {
+        if description.starts_with("Transfer to ")
+            || (description.starts_with("To ") && !description.contains("To pocket"))
+        {
             "expense".to_string()
-        } else if description.starts_with("Transfer from ") {
-            "income".to_string()
-        } else if description.starts_with("Payment from ") && !description.contains("From pocket") {
+        } else if description.starts_with("Transfer from ")
+            || (description.starts_with("Payment from ") && !description.contains("From pocket"))
+        {
             "income".to_string()
         } else if description.contains("To pocket") || description.contains("Pocket Withdrawal") {
             "internal_transfer".to_string()
@@ -378,4 +525,44 @@ Exchange,Current,2026-01-04 14:57:50,2026-01-04 14:57:50,Exchanged to EUR,231.50
             Some(231.5)
         );
     }
+
+    #[test]
+    fn exchange_rows_are_paired_with_shared_fx_group_id() {
+        let csv = "Type,Product,Started Date,Completed Date,Description,Amount,Fee,Currency,State,Balance\n\
+Exchange,Current,2026-02-10 09:12:00,2026-02-10 09:12:00,Exchanged to SEK,-100.00,0.00,EUR,COMPLETED,400.00\n\
+Exchange,Current,2026-02-10 09:12:00,2026-02-10 09:12:00,Exchanged to SEK,1148.20,0.00,SEK,COMPLETED,1148.20\n\
+Card Payment,Current,2026-02-11 12:00:00,2026-02-11 12:00:00,Coffee,-4.5,0,EUR,COMPLETED,395.50\n";
+
+        let parser = RevolutCsvParser::new("REVOLUT");
+        let (txns, _used_accounts) = parse_transactions(&parser, csv.as_bytes()).unwrap();
+
+        assert_eq!(txns.len(), 3);
+
+        let debit_group = txns[0].get("fx_group_id").and_then(|v| v.as_str());
+        let credit_group = txns[1].get("fx_group_id").and_then(|v| v.as_str());
+        assert!(debit_group.is_some());
+        assert_eq!(debit_group, credit_group);
+        assert_eq!(
+            txns[0].get("currency").and_then(|v| v.as_str()),
+            Some("EUR")
+        );
+        assert_eq!(
+            txns[1].get("currency").and_then(|v| v.as_str()),
+            Some("SEK")
+        );
+
+        assert!(txns[2].get("fx_group_id").is_none());
+    }
+
+    #[test]
+    fn single_exchange_leg_is_left_unpaired() {
+        let csv = "Type,Product,Started Date,Completed Date,Description,Amount,Fee,Currency,State,Balance\n\
+Exchange,Current,2026-02-10 09:12:00,2026-02-10 09:12:00,Exchanged to SEK,-100.00,0.00,EUR,COMPLETED,400.00\n";
+
+        let parser = RevolutCsvParser::new("REVOLUT");
+        let (txns, _used_accounts) = parse_transactions(&parser, csv.as_bytes()).unwrap();
+
+        assert_eq!(txns.len(), 1);
+        assert!(txns[0].get("fx_group_id").is_none());
+    }
 }
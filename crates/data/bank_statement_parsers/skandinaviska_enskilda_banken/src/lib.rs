@@ -1,5 +1,7 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use serde_json::Value;
+use std::io::Read;
 
 mod accounts;
 mod instruments;
@@ -21,6 +23,11 @@ pub struct SebXlsxParser {
     // Optional, but helps internal transfer mapping
     pub checking_account_number_digits: Option<String>,
     pub savings_account_number_digits: Option<String>,
+
+    // Field delimiter used by `parse_csv_reader` (SEB exports use ';')
+    pub csv_delimiter: u8,
+    /// Rows dated before this are skipped.
+    pub since: Option<NaiveDate>,
 }
 
 impl SebXlsxParser {
@@ -30,9 +37,17 @@ impl SebXlsxParser {
             account_id_savings: savings_account_id.into(),
             checking_account_number_digits: None,
             savings_account_number_digits: None,
+            csv_delimiter: b';',
+            since: None,
         }
     }
 
+    /// Skips rows dated before `since`, before their txn_id is computed.
+    pub fn with_since(mut self, since: NaiveDate) -> Self {
+        self.since = Some(since);
+        self
+    }
+
     /// If you know the account numbers (digits-only) you can set them and improve internal transfer detection.
     /// Example digits-only: "50200105205", "50371807786"
     pub fn with_account_numbers(
@@ -45,6 +60,11 @@ impl SebXlsxParser {
         self
     }
 
+    pub fn with_csv_delimiter(mut self, delimiter: u8) -> Self {
+        self.csv_delimiter = delimiter;
+        self
+    }
+
     pub fn create_accounts(&self) -> Vec<Value> {
         accounts::create_accounts(self)
     }
@@ -52,6 +72,11 @@ impl SebXlsxParser {
     pub fn parse_file(&self, path: &str, account_id: &str) -> Result<Vec<Value>> {
         transactions::parse_transactions(self, path, account_id)
     }
+
+    /// Parses a CSV export (semicolon-delimited by default, optional UTF-8 BOM).
+    pub fn parse_csv_reader<R: Read>(&self, reader: R, account_id: &str) -> Result<Vec<Value>> {
+        transactions::parse_csv_transactions(self, reader, account_id)
+    }
 }
 
 pub fn merge_transactions_into_template(
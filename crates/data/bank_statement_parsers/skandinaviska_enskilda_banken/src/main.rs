@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
-use std::env;
+use chrono::NaiveDate;
+use std::{env, fs::File};
 
 use seb::SebXlsxParser;
 
@@ -26,10 +27,9 @@ impl SebImportContract {
     fn resolve_account_id<'a>(&'a self, input_file_path: &str) -> &'a str {
         let lower = input_file_path.to_lowercase();
 
+        // Anything not recognisably a savings export is treated as the checking account.
         if lower.contains("saving") || lower.contains("savings") || lower.contains("spark") {
             &self.savings_id
-        } else if lower.contains("check") || lower.contains("current") || lower.contains("privat") {
-            &self.checking_id
         } else {
             &self.checking_id
         }
@@ -42,21 +42,19 @@ impl utils::ParserContract for SebImportContract {
     }
 
     fn supported_input_formats(&self) -> &'static [utils::InputFormat] {
-        &[utils::InputFormat::Excel]
+        &[utils::InputFormat::Excel, utils::InputFormat::Csv]
     }
 
-    fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
-        let account_id = self.resolve_account_id(input_file_path);
+    fn set_since(&mut self, since: NaiveDate) {
+        self.parser.since = Some(since);
+    }
 
-        let txns = self
-            .parser
-            .parse_file(input_file_path, account_id)
-            .with_context(|| format!("Failed parsing {}", input_file_path))?;
+    fn parse_file(&mut self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        utils::ConcurrentParserContract::parse_file_shared(self, input_file_path)
+    }
 
-        Ok(utils::ParsedEntities {
-            transactions: txns,
-            ..Default::default()
-        })
+    fn as_concurrent(&self) -> Option<&dyn utils::ConcurrentParserContract> {
+        Some(self)
     }
 
     fn finalize_entities(
@@ -72,7 +70,28 @@ impl utils::ParserContract for SebImportContract {
     }
 }
 
+impl utils::ConcurrentParserContract for SebImportContract {
+    fn parse_file_shared(&self, input_file_path: &str) -> Result<utils::ParsedEntities> {
+        let account_id = self.resolve_account_id(input_file_path);
+
+        let txns = if input_file_path.to_lowercase().ends_with(".csv") {
+            let file = File::open(input_file_path)
+                .with_context(|| format!("Cannot open {}", input_file_path))?;
+            self.parser.parse_csv_reader(file, account_id)
+        } else {
+            self.parser.parse_file(input_file_path, account_id)
+        }
+        .with_context(|| format!("Failed parsing {}", input_file_path))?;
+
+        Ok(utils::ParsedEntities {
+            transactions: txns,
+            ..Default::default()
+        })
+    }
+}
+
 fn main() -> Result<()> {
+    utils::init_console_logging();
     let args: Vec<String> = env::args().collect();
     let mut contract = SebImportContract::new();
     utils::run_parser_contract_cli(&mut contract, &args, "../../../../database")
@@ -1,9 +1,12 @@
 use anyhow::{anyhow, Context, Result};
 use calamine::{open_workbook, Data, Reader, Xlsx};
-use chrono::{Duration, NaiveDate};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde_json::Value;
-use sha2::{Digest, Sha256};
-use utils::{build_transaction, TransactionInput};
+use std::io::Read;
+use utils::{
+    build_transaction, compute_txn_id, excel_serial_to_naive_datetime, txn_id_datetime_key,
+    TransactionInput,
+};
 
 use crate::{SebMeta, SebXlsxParser};
 
@@ -26,29 +29,79 @@ pub fn parse_transactions(
         .context("Failed to read worksheet range")?;
 
     let rows: Vec<&[Data]> = range.rows().collect();
+    parse_rows(parser, &rows, account_id)
+}
+
+pub fn parse_csv_transactions<R: Read>(
+    parser: &SebXlsxParser,
+    mut reader: R,
+    account_id: &str,
+) -> Result<Vec<Value>> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf).context("Failed to read CSV input")?;
+    let content = buf.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&buf);
+
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .delimiter(parser.csv_delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content);
+
+    let mut cells: Vec<Vec<Data>> = Vec::new();
+    for (idx, record) in csv_reader.records().enumerate() {
+        let record = record.with_context(|| format!("CSV read error at row {}", idx + 1))?;
+        cells.push(
+            record
+                .iter()
+                .map(|field| {
+                    if field.is_empty() {
+                        Data::Empty
+                    } else {
+                        Data::String(field.to_string())
+                    }
+                })
+                .collect(),
+        );
+    }
+
+    let rows: Vec<&[Data]> = cells.iter().map(Vec::as_slice).collect();
+    parse_rows(parser, &rows, account_id)
+}
+
+fn parse_rows(parser: &SebXlsxParser, rows: &[&[Data]], account_id: &str) -> Result<Vec<Value>> {
     if rows.is_empty() {
         return Ok(vec![]);
     }
 
-    let file_meta = extract_meta(&rows);
-    let (header_row_idx, col_date, col_description, col_amount, col_currency) = find_columns(&rows)?;
+    let file_meta = extract_meta(rows);
+    let columns = find_columns(rows)?;
 
     let mut out = Vec::new();
 
-    for (idx, row) in rows.iter().enumerate().skip(header_row_idx + 1) {
+    for (idx, row) in rows.iter().enumerate().skip(columns.header_row + 1) {
         if row.is_empty() || row.iter().all(|c| matches!(c, Data::Empty)) {
             continue;
         }
 
-        let date = parse_date_cell(row, col_date)
+        let datetime = parse_date_cell(row, columns.date)
             .with_context(|| format!("Failed to parse date at row {}", idx + 1))?;
+        let date = datetime.date();
+        if parser.since.is_some_and(|since| date < since) {
+            continue;
+        }
+
+        let value_date = columns
+            .value_date
+            .and_then(|col| parse_date_cell(row, col).ok())
+            .map(|value_datetime| value_datetime.date());
 
-        let description = get_string_cell(row, col_description).unwrap_or_default();
+        let description = get_string_cell(row, columns.description).unwrap_or_default();
 
-        let amount = parse_amount_cell(row, col_amount)
+        let amount = parse_row_amount(row, &columns.amount)
             .with_context(|| format!("Failed to parse amount at row {}", idx + 1))?;
 
-        let currency = if let Some(c) = col_currency {
+        let currency = if let Some(c) = columns.currency {
             get_string_cell(row, c).unwrap_or_else(|| "SEK".to_string())
         } else {
             "SEK".to_string()
@@ -58,7 +111,7 @@ pub fn parse_transactions(
         let (from_account_id, to_account_id) =
             determine_accounts(account_id, &txn_type, amount, &description, &file_meta, parser);
 
-        out.push(build_transaction(&TransactionInput {
+        let mut txn = build_transaction(&TransactionInput {
             date: field_date(date),
             from_account_id,
             to_account_id,
@@ -68,8 +121,12 @@ pub fn parse_transactions(
             currency,
             description: description.clone(),
             description_en: field_description_en(),
-            txn_id: field_txn_id(account_id, date, amount, &description, idx + 1),
-        }));
+            txn_id: field_txn_id(account_id, datetime, amount, &description, idx + 1),
+        });
+        if let Some(value_date) = value_date {
+            txn["value_date"] = Value::String(field_date(value_date));
+        }
+        out.push(txn);
     }
 
     Ok(out)
@@ -93,21 +150,59 @@ fn field_description_en() -> Option<String> {
 
 fn field_txn_id(
     account_id: &str,
-    date: NaiveDate,
+    date: NaiveDateTime,
     signed_amount: f64,
     description: &str,
     row_index: usize,
 ) -> String {
-    make_txn_id(account_id, date, signed_amount, "SEK", description, row_index)
+    compute_txn_id(
+        "SEB",
+        &[
+            account_id,
+            &txn_id_datetime_key(date),
+            &format!("{:.8}", signed_amount),
+            "SEK",
+            description,
+            &row_index.to_string(),
+        ],
+    )
 }
 
-fn find_columns(rows: &[&[Data]]) -> Result<(usize, usize, usize, usize, Option<usize>)> {
+/// Where the amount of a row lives in the sheet.
+#[derive(Debug, Clone, PartialEq)]
+enum AmountColumns {
+    /// One signed column (e.g. "Belopp"), negative for outflows.
+    Signed(usize),
+    /// Separate deposit/withdrawal columns (e.g. "Insättning"/"Uttag"), both positive.
+    DepositWithdrawal { deposit: usize, withdrawal: usize },
+}
+
+#[derive(Debug, Clone)]
+struct SebColumns {
+    header_row: usize,
+    /// Booking date ("Bokföringsdatum"), emitted as `date`.
+    date: usize,
+    /// Value date ("Valutadatum"), emitted as `value_date`. Interest and
+    /// cross-border rows can have a value date in another month.
+    value_date: Option<usize>,
+    description: usize,
+    amount: AmountColumns,
+    currency: Option<usize>,
+}
+
+fn find_columns(rows: &[&[Data]]) -> Result<SebColumns> {
     for (row_idx, row) in rows.iter().enumerate().take(30) {
         let headers: Vec<String> = row.iter().map(|c| c.to_string().to_lowercase()).collect();
 
-        let date_col = headers.iter().position(|h| {
-            h.contains("booking date") || h.contains("bokföringsdatum") || h == "date" || h == "datum"
-        });
+        let booking_date_col = headers
+            .iter()
+            .position(|h| h.contains("booking date") || h.contains("bokföringsdatum"));
+        let value_date_col = headers
+            .iter()
+            .position(|h| h.contains("value date") || h.contains("valutadatum"));
+        let date_col = booking_date_col
+            .or_else(|| headers.iter().position(|h| h == "date" || h == "datum"))
+            .or(value_date_col);
 
         let desc_col = headers.iter().position(|h| {
             h == "text" || h.contains("description") || h.contains("beskrivning") || h.contains("transaktion")
@@ -119,16 +214,41 @@ fn find_columns(rows: &[&[Data]]) -> Result<(usize, usize, usize, usize, Option<
                 && !h.contains("saldo")
         });
 
-        let currency_col = headers
+        let deposit_col = headers
+            .iter()
+            .position(|h| h.contains("insättning") || h.contains("deposit") || h == "credit");
+
+        let withdrawal_col = headers
             .iter()
-            .position(|h| h.contains("currency") || h.contains("valuta"));
+            .position(|h| h.contains("uttag") || h.contains("withdrawal") || h == "debit");
 
-        if let (Some(d), Some(t), Some(a)) = (date_col, desc_col, amount_col) {
-            return Ok((row_idx, d, t, a, currency_col));
+        let currency_col = headers.iter().position(|h| {
+            (h.contains("currency") || h.contains("valuta"))
+                && !h.contains("datum")
+                && !h.contains("date")
+        });
+
+        let amount = match (amount_col, deposit_col, withdrawal_col) {
+            (Some(a), _, _) => Some(AmountColumns::Signed(a)),
+            (None, Some(deposit), Some(withdrawal)) => {
+                Some(AmountColumns::DepositWithdrawal { deposit, withdrawal })
+            }
+            _ => None,
+        };
+
+        if let (Some(date), Some(description), Some(amount)) = (date_col, desc_col, amount) {
+            return Ok(SebColumns {
+                header_row: row_idx,
+                date,
+                value_date: value_date_col.filter(|col| *col != date),
+                description,
+                amount,
+                currency: currency_col,
+            });
         }
     }
 
-    Err(anyhow!("Could not determine column layout from Excel file"))
+    Err(anyhow!("Could not determine column layout from statement header"))
 }
 
 fn extract_meta(rows: &[&[Data]]) -> SebMeta {
@@ -153,32 +273,32 @@ fn extract_meta(rows: &[&[Data]]) -> SebMeta {
     }
 }
 
-fn parse_date_cell(row: &[Data], col: usize) -> Result<NaiveDate> {
+fn parse_date_cell(row: &[Data], col: usize) -> Result<NaiveDateTime> {
     if col >= row.len() {
         return Err(anyhow!("Date column index out of bounds"));
     }
 
     match &row[col] {
         Data::String(s) => parse_date_string(s),
-        Data::DateTime(dt) => excel_serial_to_date(dt.as_f64()),
-        Data::Float(f) => excel_serial_to_date(*f),
-        Data::Int(i) => excel_serial_to_date(*i as f64),
+        Data::DateTime(dt) => excel_serial_to_datetime(dt.as_f64()),
+        Data::Float(f) => excel_serial_to_datetime(*f),
+        Data::Int(i) => excel_serial_to_datetime(*i as f64),
         Data::DateTimeIso(s) | Data::DurationIso(s) => parse_date_string(s),
         _ => Err(anyhow!("Unsupported date cell type: {:?}", row[col])),
     }
 }
 
-fn excel_serial_to_date(value: f64) -> Result<NaiveDate> {
-    let base = NaiveDate::from_ymd_opt(1899, 12, 30).ok_or_else(|| anyhow!("Invalid base date"))?;
-    let days = value.floor() as i64;
-    base.checked_add_signed(Duration::days(days))
-        .ok_or_else(|| anyhow!("Date calculation overflow"))
+fn excel_serial_to_datetime(value: f64) -> Result<NaiveDateTime> {
+    excel_serial_to_naive_datetime(value).ok_or_else(|| anyhow!("Invalid Excel date: {}", value))
 }
 
-fn parse_date_string(s: &str) -> Result<NaiveDate> {
+fn parse_date_string(s: &str) -> Result<NaiveDateTime> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
+        return Ok(dt);
+    }
+
     let formats = [
         "%Y-%m-%d",
-        "%Y-%m-%d %H:%M:%S",
         "%d/%m/%Y",
         "%d.%m.%Y",
         "%Y/%m/%d",
@@ -188,7 +308,7 @@ fn parse_date_string(s: &str) -> Result<NaiveDate> {
 
     for fmt in formats {
         if let Ok(d) = NaiveDate::parse_from_str(s, fmt) {
-            return Ok(d);
+            return Ok(d.and_time(NaiveTime::MIN));
         }
     }
 
@@ -220,8 +340,7 @@ fn parse_amount_cell(row: &[Data], col: usize) -> Result<f64> {
         Data::Int(i) => Ok(*i as f64),
         Data::String(s) => {
             let cleaned = s
-                .replace(' ', "")
-                .replace('\u{A0}', "")
+                .replace([' ', '\u{A0}'], "")
                 .replace(',', ".");
             cleaned
                 .parse::<f64>()
@@ -231,6 +350,35 @@ fn parse_amount_cell(row: &[Data], col: usize) -> Result<f64> {
     }
 }
 
+/// Returns the signed amount of a row for either column layout.
+///
+/// In the deposit/withdrawal layout the populated column decides the sign;
+/// if both are populated the net of the two is used.
+fn parse_row_amount(row: &[Data], columns: &AmountColumns) -> Result<f64> {
+    match columns {
+        AmountColumns::Signed(col) => parse_amount_cell(row, *col),
+        AmountColumns::DepositWithdrawal { deposit, withdrawal } => {
+            let deposit = parse_optional_amount_cell(row, *deposit)?;
+            let withdrawal = parse_optional_amount_cell(row, *withdrawal)?;
+
+            match (deposit, withdrawal) {
+                (Some(d), None) => Ok(d.abs()),
+                (None, Some(w)) => Ok(-w.abs()),
+                (Some(d), Some(w)) => Ok(d.abs() - w.abs()),
+                (None, None) => Err(anyhow!("Both deposit and withdrawal cells are empty")),
+            }
+        }
+    }
+}
+
+fn parse_optional_amount_cell(row: &[Data], col: usize) -> Result<Option<f64>> {
+    match row.get(col) {
+        None | Some(Data::Empty) => Ok(None),
+        Some(Data::String(s)) if s.trim().is_empty() => Ok(None),
+        Some(_) => parse_amount_cell(row, col).map(Some),
+    }
+}
+
 fn infer_type(amount: f64, description: &str) -> String {
     let d = description.to_lowercase();
 
@@ -263,43 +411,8 @@ fn determine_accounts(
 ) -> (String, String) {
     match txn_type {
         "internal_transfer" => {
-            let desc_digits = normalize_digits(description);
-
-            let checking_digits = parser.checking_account_number_digits.clone();
-            let savings_digits = parser.savings_account_number_digits.clone();
-            let this_digits = file_meta.account_number_digits.clone();
-
-            let is_checking = this_account_id == parser.account_id_checking;
-            let is_savings = this_account_id == parser.account_id_savings;
-
-            let counterpart_id = if is_checking {
-                if let Some(sd) = savings_digits.as_deref() {
-                    if !sd.is_empty() && desc_digits.contains(sd) {
-                        Some(parser.account_id_savings.clone())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else if is_savings {
-                if let Some(cd) = checking_digits.as_deref() {
-                    if !cd.is_empty() && desc_digits.contains(cd) {
-                        Some(parser.account_id_checking.clone())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-            .or_else(|| {
-                let _ = this_digits;
-                None
-            })
-            .unwrap_or_else(|| "INTERNAL_UNKNOWN".to_string());
+            let counterpart_id = resolve_internal_counterpart(this_account_id, description, file_meta, parser)
+                .unwrap_or_else(|| "INTERNAL_UNKNOWN".to_string());
 
             if amount < 0.0 {
                 (this_account_id.to_string(), counterpart_id)
@@ -319,27 +432,210 @@ fn determine_accounts(
     }
 }
 
-fn make_txn_id(
-    account_id: &str,
-    date: NaiveDate,
-    signed_amount: f64,
-    currency: &str,
+/// Finds the other SEB account of an internal transfer.
+///
+/// A transfer is wired to the checking/savings pair when the description
+/// carries the counterpart's account number or names it (e.g. "sparkonto").
+/// Returns `None` when the counterpart cannot be identified.
+fn resolve_internal_counterpart(
+    this_account_id: &str,
     description: &str,
-    row_index: usize,
-) -> String {
-    let seed = format!(
-        "{}|{}|{:.8}|{}|{}|{}",
-        account_id,
-        date.format("%Y-%m-%d"),
-        signed_amount,
-        currency,
-        description.trim(),
-        row_index
-    );
-
-    let mut hasher = Sha256::new();
-    hasher.update(seed.as_bytes());
-    let hash = hasher.finalize();
-
-    format!("SEB-{}", hex::encode(&hash[..12]))
+    file_meta: &SebMeta,
+    parser: &SebXlsxParser,
+) -> Option<String> {
+    let desc_lower = description.to_lowercase();
+    let desc_digits = normalize_digits(description);
+
+    let mentions_digits = |digits: Option<&str>| {
+        digits
+            .map(|d| !d.is_empty() && desc_digits.contains(d))
+            .unwrap_or(false)
+    };
+
+    let mentions_savings = mentions_digits(parser.savings_account_number_digits.as_deref())
+        || desc_lower.contains("sparkonto")
+        || desc_lower.contains("savings")
+        || desc_lower.contains(&parser.account_id_savings.to_lowercase());
+
+    let mentions_checking = mentions_digits(parser.checking_account_number_digits.as_deref())
+        || desc_lower.contains("privatkonto")
+        || desc_lower.contains("checking")
+        || desc_lower.contains(&parser.account_id_checking.to_lowercase());
+
+    // Fall back to the account number printed in the file header when the
+    // caller passed an ID that is neither of the two configured accounts.
+    let this_digits = file_meta.account_number_digits.as_deref();
+    let is_checking = this_account_id == parser.account_id_checking
+        || (this_digits.is_some() && this_digits == parser.checking_account_number_digits.as_deref());
+    let is_savings = this_account_id == parser.account_id_savings
+        || (this_digits.is_some() && this_digits == parser.savings_account_number_digits.as_deref());
+
+    if is_checking && mentions_savings {
+        Some(parser.account_id_savings.clone())
+    } else if is_savings && mentions_checking {
+        Some(parser.account_id_checking.clone())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Data {
+        Data::String(s.to_string())
+    }
+
+    #[test]
+    fn parse_rows_handles_deposit_withdrawal_columns() {
+        let header = vec![
+            text("Bokföringsdatum"),
+            text("Text"),
+            text("Insättning"),
+            text("Uttag"),
+        ];
+        let deposit = vec![
+            text("2026-01-05"),
+            text("Swish från Anna"),
+            Data::Float(250.0),
+            Data::Empty,
+        ];
+        let withdrawal = vec![
+            text("2026-01-06"),
+            text("ICA Maxi"),
+            Data::Empty,
+            text("123,45"),
+        ];
+        let rows: Vec<&[Data]> = vec![&header, &deposit, &withdrawal];
+
+        let parser = SebXlsxParser::new("SEB_CHECKING", "SEB_SAVINGS");
+        let txns = parse_rows(&parser, &rows, "SEB_CHECKING").unwrap();
+
+        assert_eq!(txns.len(), 2);
+
+        assert_eq!(txns[0]["type"], "income");
+        assert_eq!(txns[0]["from_account_id"], "EXTERNAL_PAYER");
+        assert_eq!(txns[0]["to_account_id"], "SEB_CHECKING");
+        assert_eq!(txns[0]["amount"].as_f64(), Some(250.0));
+
+        assert_eq!(txns[1]["type"], "expense");
+        assert_eq!(txns[1]["from_account_id"], "SEB_CHECKING");
+        assert_eq!(txns[1]["to_account_id"], "EXTERNAL_PAYEE");
+        assert_eq!(txns[1]["amount"].as_f64(), Some(123.45));
+    }
+
+    #[test]
+    fn internal_transfer_to_savings_resolves_to_known_account() {
+        let parser = SebXlsxParser::new("SEB_CHECKING", "SEB_SAVINGS");
+        let meta = SebMeta {
+            account_number_digits: None,
+        };
+
+        let (from, to) = determine_accounts(
+            "SEB_CHECKING",
+            "internal_transfer",
+            -500.0,
+            "Överföring till sparkonto",
+            &meta,
+            &parser,
+        );
+
+        assert_eq!(from, "SEB_CHECKING");
+        assert_eq!(to, "SEB_SAVINGS");
+    }
+
+    #[test]
+    fn internal_transfer_with_unknown_counterpart_uses_placeholder() {
+        let parser = SebXlsxParser::new("SEB_CHECKING", "SEB_SAVINGS");
+        let meta = SebMeta {
+            account_number_digits: None,
+        };
+
+        let (from, to) = determine_accounts(
+            "SEB_CHECKING",
+            "internal_transfer",
+            200.0,
+            "Överföring 1234-5678",
+            &meta,
+            &parser,
+        );
+
+        assert_eq!(from, "INTERNAL_UNKNOWN");
+        assert_eq!(to, "SEB_CHECKING");
+    }
+
+    #[test]
+    fn internal_transfer_on_savings_mentioning_only_savings_is_unresolved() {
+        let parser = SebXlsxParser::new("SEB_CHECKING", "SEB_SAVINGS");
+        let meta = SebMeta {
+            account_number_digits: None,
+        };
+
+        let (from, to) = determine_accounts(
+            "SEB_SAVINGS",
+            "internal_transfer",
+            -300.0,
+            "Överföring till sparkonto",
+            &meta,
+            &parser,
+        );
+
+        assert_eq!(from, "SEB_SAVINGS");
+        assert_eq!(to, "INTERNAL_UNKNOWN");
+    }
+
+    #[test]
+    fn parse_csv_reader_reads_semicolon_export_with_bom() {
+        let csv = "\u{FEFF}Bokföringsdatum;Valutadatum;Verifikationsnummer;Text;Belopp;Saldo\n\
+2026-02-03;2026-02-03;5484381424;ICA Supermarket;-245,50;10 254,50\n\
+2026-02-25;2026-02-25;5484381425;Lön Februari;32 000,00;42 254,50\n";
+
+        let parser = SebXlsxParser::new("SEB_CHECKING", "SEB_SAVINGS");
+        let txns = parser.parse_csv_reader(csv.as_bytes(), "SEB_CHECKING").unwrap();
+
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0]["date"], "2026-02-03");
+        assert_eq!(txns[0]["type"], "expense");
+        assert_eq!(txns[0]["amount"].as_f64(), Some(245.5));
+        assert_eq!(txns[0]["currency"], "SEK");
+        assert_eq!(txns[1]["type"], "income");
+        assert_eq!(txns[1]["amount"].as_f64(), Some(32000.0));
+    }
+
+    #[test]
+    fn parse_rows_emits_booking_date_and_value_date() {
+        let header = vec![
+            text("Valutadatum"),
+            text("Bokföringsdatum"),
+            text("Text"),
+            text("Belopp"),
+            text("Saldo"),
+        ];
+        let interest = vec![
+            text("2025-12-31"),
+            text("2026-01-02"),
+            text("Ränta"),
+            text("12,34"),
+            text("1 012,34"),
+        ];
+        let rows: Vec<&[Data]> = vec![&header, &interest];
+
+        let parser = SebXlsxParser::new("SEB_CHECKING", "SEB_SAVINGS");
+        let txns = parse_rows(&parser, &rows, "SEB_CHECKING").unwrap();
+
+        assert_eq!(txns.len(), 1);
+        assert_eq!(txns[0]["date"], "2026-01-02");
+        assert_eq!(txns[0]["value_date"], "2025-12-31");
+        assert_eq!(txns[0]["currency"], "SEK");
+    }
+
+    #[test]
+    fn find_columns_prefers_signed_amount_column() {
+        let header = vec![text("Datum"), text("Text"), text("Belopp"), text("Saldo")];
+        let rows: Vec<&[Data]> = vec![&header];
+
+        let columns = find_columns(&rows).unwrap();
+        assert_eq!(columns.amount, AmountColumns::Signed(2));
+    }
 }
@@ -60,6 +60,7 @@ impl utils::ParserContract for TemplateImportContract {
 }
 
 fn main() -> Result<()> {
+    utils::init_console_logging();
     // Usage:
     //   template_parser [database_path] [output_path]
     //
@@ -4,7 +4,7 @@ use chrono::{NaiveDate, NaiveDateTime};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use utils::{build_transaction, TransactionInput};
+use utils::{build_transaction, mark_pending, RowStatus, TransactionInput};
 
 use crate::WeChatXlsxParser;
 
@@ -22,33 +22,50 @@ pub fn parse_transactions(parser: &WeChatXlsxParser, xlsx_path: &str) -> Result<
         .worksheet_range(&sheet_name)
         .with_context(|| format!("Cannot read sheet '{}' in {}", sheet_name, xlsx_path))?;
 
-    let (header_row_idx, header_map) =
-        find_header_row(&range).context("Could not find WeChat header row starting with '交易时间'")?;
-
-    let c_time = *header_map
-        .get("交易时间")
-        .context("Missing column: 交易时间")?;
-    let c_type = *header_map
-        .get("交易类型")
-        .context("Missing column: 交易类型")?;
-    let c_counterparty = *header_map
-        .get("交易对方")
-        .context("Missing column: 交易对方")?;
-    let c_product = *header_map.get("商品").context("Missing column: 商品")?;
-    let c_inout = *header_map.get("收/支").context("Missing column: 收/支")?;
-    let c_amount = *header_map
-        .get("金额(元)")
-        .context("Missing column: 金额(元)")?;
-    let c_paymethod = *header_map
-        .get("支付方式")
-        .context("Missing column: 支付方式")?;
-    let c_status = *header_map
-        .get("当前状态")
-        .context("Missing column: 当前状态")?;
-    let c_trade_no = *header_map
-        .get("交易单号")
-        .context("Missing column: 交易单号")?;
-    let c_note = header_map.get("备注").copied();
+    parse_range(parser, &range, xlsx_path)
+}
+
+/// Column roles shared by both export languages: (role, Chinese header,
+/// English header). Overseas accounts get the English headers.
+const COLUMNS: [(&str, &str, &str); 10] = [
+    ("time", "交易时间", "Transaction Time"),
+    ("type", "交易类型", "Transaction Type"),
+    ("counterparty", "交易对方", "Counterparty"),
+    ("product", "商品", "Item"),
+    ("inout", "收/支", "Income/Expenses"),
+    ("amount", "金额(元)", "Amount(RMB)"),
+    ("paymethod", "支付方式", "Payment Method"),
+    ("status", "当前状态", "Status"),
+    ("trade_no", "交易单号", "Transaction ID"),
+    ("note", "备注", "Remarks"),
+];
+
+fn parse_range(
+    parser: &WeChatXlsxParser,
+    range: &calamine::Range<Data>,
+    xlsx_path: &str,
+) -> Result<Vec<Value>> {
+    let (header_row_idx, header_map) = find_header_row(range).context(
+        "Could not find WeChat header row starting with '交易时间' or 'Transaction Time'",
+    )?;
+
+    let column = |role: &str| -> Result<usize> {
+        header_map.get(role).copied().ok_or_else(|| {
+            let (_, zh, en) = COLUMNS.iter().find(|(r, _, _)| *r == role).unwrap();
+            anyhow!("Missing column: {} / {}", zh, en)
+        })
+    };
+
+    let c_time = column("time")?;
+    let c_type = column("type")?;
+    let c_counterparty = column("counterparty")?;
+    let c_product = column("product")?;
+    let c_inout = column("inout")?;
+    let c_amount = column("amount")?;
+    let c_paymethod = column("paymethod")?;
+    let c_status = column("status")?;
+    let c_trade_no = column("trade_no")?;
+    let c_note = header_map.get("note").copied();
 
     let mut out = Vec::new();
 
@@ -59,25 +76,31 @@ pub fn parse_transactions(parser: &WeChatXlsxParser, xlsx_path: &str) -> Result<
         }
 
         let status = cell_str(row.get(c_status)).trim().to_string();
-        if parser.only_successful && !is_success_status(&status) {
+        let row_status = classify_status(&status);
+        if !parser.status_filter.keeps(row_status) {
             let inout_preview = cell_str(row.get(c_inout)).trim().to_string();
             let amount_preview = cell_str(row.get(c_amount)).trim().to_string();
-            println!(
-                "⚠️  Skipping transaction with status '{}' (收/支: {}, 金额: {})",
-                status, inout_preview, amount_preview
+            log::warn!(
+                "Skipping transaction with status '{}' (收/支: {}, 金额: {})",
+                status,
+                inout_preview,
+                amount_preview
             );
             continue;
         }
 
         let date = parse_wechat_datetime(&datetime_raw)
             .with_context(|| format!("Invalid datetime '{}' in {}", datetime_raw, xlsx_path))?;
+        if parser.since.is_some_and(|since| date < since) {
+            continue;
+        }
 
         let amount_raw = cell_str(row.get(c_amount));
         let amount = parse_wechat_amount(&amount_raw)
             .with_context(|| format!("Invalid amount '{}' in {}", amount_raw, xlsx_path))?;
 
         let inout = cell_str(row.get(c_inout)).trim().to_string();
-        let wx_type = cell_str(row.get(c_type)).trim().to_string();
+        let wx_type = canonical_wx_type(cell_str(row.get(c_type)).trim());
         let txn_type = classify_txn_type(&inout, &wx_type, amount);
 
         let counterparty = cell_str(row.get(c_counterparty)).trim().to_string();
@@ -92,10 +115,10 @@ pub fn parse_transactions(parser: &WeChatXlsxParser, xlsx_path: &str) -> Result<
         let description = build_description(&counterparty, &product, &wx_type, &paymethod, &note);
         let trade_no = cell_str(row.get(c_trade_no)).trim().to_string();
 
-        out.push(build_transaction(&TransactionInput {
+        let mut txn = build_transaction(&TransactionInput {
             date: field_date(date),
-            from_account_id: field_from_account_id(parser, &txn_type, amount),
-            to_account_id: field_to_account_id(parser, &txn_type, amount),
+            from_account_id: field_from_account_id(parser, &txn_type, &wx_type, amount),
+            to_account_id: field_to_account_id(parser, &txn_type, &wx_type, amount),
             transaction_type: field_type(&txn_type),
             category: field_category(),
             amount: field_amount(amount),
@@ -110,7 +133,19 @@ pub fn parse_transactions(parser: &WeChatXlsxParser, xlsx_path: &str) -> Result<
                 &trade_no,
                 row_idx + 1,
             ),
-        }));
+        });
+
+        if let (Some((bank, last4)), Some(obj)) =
+            (parse_funding_card(&paymethod), txn.as_object_mut())
+        {
+            obj.insert("funding_bank".to_string(), Value::String(bank));
+            obj.insert("funding_last4".to_string(), Value::String(last4));
+        }
+        if row_status == RowStatus::Pending {
+            mark_pending(&mut txn);
+        }
+
+        out.push(txn);
     }
 
     Ok(out)
@@ -120,11 +155,17 @@ fn field_date(date: NaiveDate) -> String {
     date.format("%Y-%m-%d").to_string()
 }
 
-fn field_from_account_id(parser: &WeChatXlsxParser, txn_type: &str, amount: f64) -> String {
+fn field_from_account_id(
+    parser: &WeChatXlsxParser,
+    txn_type: &str,
+    wx_type: &str,
+    amount: f64,
+) -> String {
     match txn_type {
         "expense" => parser.account_id.clone(),
         "income" => "EXTERNAL_PAYER".to_string(),
-        "internal_transfer" => parser.account_id.clone(),
+        "internal_transfer" if is_wallet_withdrawal(wx_type) => parser.account_id.clone(),
+        "internal_transfer" => "INTERNAL_SOURCE".to_string(),
         _ => {
             if amount < 0.0 {
                 parser.account_id.clone()
@@ -135,10 +176,16 @@ fn field_from_account_id(parser: &WeChatXlsxParser, txn_type: &str, amount: f64)
     }
 }
 
-fn field_to_account_id(parser: &WeChatXlsxParser, txn_type: &str, amount: f64) -> String {
+fn field_to_account_id(
+    parser: &WeChatXlsxParser,
+    txn_type: &str,
+    wx_type: &str,
+    amount: f64,
+) -> String {
     match txn_type {
         "expense" => "EXTERNAL_PAYEE".to_string(),
         "income" => parser.account_id.clone(),
+        "internal_transfer" if is_wallet_withdrawal(wx_type) => "INTERNAL_DESTINATION".to_string(),
         "internal_transfer" => parser.account_id.clone(),
         _ => {
             if amount < 0.0 {
@@ -150,6 +197,12 @@ fn field_to_account_id(parser: &WeChatXlsxParser, txn_type: &str, amount: f64) -
     }
 }
 
+/// 零钱提现 moves money out of the wallet to a bank card; 零钱充值 tops the
+/// wallet up from one. The card side is not part of this export.
+fn is_wallet_withdrawal(wx_type: &str) -> bool {
+    wx_type.contains("零钱提现")
+}
+
 fn field_type(txn_type: &str) -> String {
     txn_type.to_string()
 }
@@ -189,15 +242,17 @@ fn field_txn_id(
     )
 }
 
+/// Finds the header row (first cell `交易时间` or `Transaction Time`) and maps
+/// each recognised header, in either language, to its column role.
 fn find_header_row(range: &calamine::Range<Data>) -> Result<(usize, HashMap<String, usize>)> {
     for (r_idx, row) in range.rows().enumerate() {
-        let first = cell_str(row.get(0)).trim().to_string();
-        if first == "交易时间" {
+        let first = cell_str(row.first()).trim().to_string();
+        if first == "交易时间" || first.eq_ignore_ascii_case("Transaction Time") {
             let mut map = HashMap::new();
             for (c_idx, cell) in row.iter().enumerate() {
                 let name = cell_str(Some(cell)).trim().to_string();
-                if !name.is_empty() {
-                    map.insert(name, c_idx);
+                if let Some(role) = column_role(&name) {
+                    map.insert(role.to_string(), c_idx);
                 }
             }
             return Ok((r_idx, map));
@@ -207,6 +262,13 @@ fn find_header_row(range: &calamine::Range<Data>) -> Result<(usize, HashMap<Stri
     Err(anyhow!("Header row not found"))
 }
 
+fn column_role(header: &str) -> Option<&'static str> {
+    COLUMNS
+        .iter()
+        .find(|(_, zh, en)| header == *zh || header.eq_ignore_ascii_case(en))
+        .map(|(role, _, _)| *role)
+}
+
 fn cell_str(cell: Option<&Data>) -> String {
     let Some(c) = cell else {
         return String::new();
@@ -233,15 +295,60 @@ fn parse_wechat_amount(raw: &str) -> Result<f64> {
     if s.is_empty() {
         return Err(anyhow!("empty amount"));
     }
-    let s = s.replace('¥', "").replace(',', "").trim().to_string();
+    let s = s.replace(['¥', ','], "").trim().to_string();
     Ok(s.parse::<f64>()?)
 }
 
+/// Maps an English export's Transaction Type to the Chinese 交易类型 it
+/// stands for, so both exports classify, describe and hash rows alike.
+/// Chinese and unknown values are returned unchanged.
+fn canonical_wx_type(wx_type: &str) -> String {
+    let lower = wx_type.to_lowercase();
+    let canonical = if lower.contains("red packet") {
+        if lower.contains("refund") || lower.contains("return") {
+            "微信红包-退回"
+        } else if lower.contains("sent") || lower.contains("send") {
+            "微信红包-发红包"
+        } else if lower.contains("receive") {
+            "微信红包-收红包"
+        } else {
+            "微信红包"
+        }
+    } else {
+        match lower.as_str() {
+            "recharge" | "balance recharge" => "零钱充值",
+            "withdrawal" | "balance withdrawal" => "零钱提现",
+            "transfer" => "转账",
+            "transfer refund" => "转账-退款",
+            "qr code payment" => "扫二维码付款",
+            "qr code receipt" => "二维码收款",
+            "merchant consumption" => "商户消费",
+            "refund" => "退款",
+            _ => return wx_type.to_string(),
+        }
+    };
+    canonical.to_string()
+}
+
 fn classify_txn_type(inout: &str, wx_type: &str, amount: f64) -> String {
-    if inout == "支出" {
+    // Moving money between the wallet (零钱) and a linked bank card.
+    if wx_type.contains("零钱充值") || wx_type.contains("零钱提现") {
+        return "internal_transfer".to_string();
+    }
+
+    if wx_type.contains("红包") {
+        if wx_type.contains("发红包") || wx_type.contains("送红包") {
+            return "expense".to_string();
+        }
+        if wx_type.contains("收红包") || wx_type.contains("退回") {
+            return "income".to_string();
+        }
+    }
+
+    if inout == "支出" || inout.eq_ignore_ascii_case("Expenses") {
         return "expense".to_string();
     }
-    if inout == "收入" {
+    if inout == "收入" || inout.eq_ignore_ascii_case("Income") {
         return "income".to_string();
     }
 
@@ -262,6 +369,46 @@ fn classify_txn_type(inout: &str, wx_type: &str, amount: f64) -> String {
     }
 }
 
+/// Splits a 支付方式 like "招商银行(8866)" into the bank name and the card's
+/// last four digits. Wallet payments (零钱, 零钱通) and anything without a
+/// four-digit card suffix yield `None`.
+fn parse_funding_card(paymethod: &str) -> Option<(String, String)> {
+    let s = paymethod.trim().replace('（', "(").replace('）', ")");
+    let open = s.rfind('(')?;
+    let close = s[open..].find(')')? + open;
+
+    let bank = s[..open].trim();
+    let last4 = s[open + 1..close].trim();
+
+    if bank.is_empty() || last4.len() != 4 || !last4.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((bank.to_string(), last4.to_string()))
+}
+
+fn classify_status(status: &str) -> RowStatus {
+    if is_success_status(status) {
+        RowStatus::Completed
+    } else if is_pending_status(status) {
+        RowStatus::Pending
+    } else {
+        RowStatus::Other
+    }
+}
+
+/// "待接收", "处理中", "Pending", "Processing", ...
+fn is_pending_status(status: &str) -> bool {
+    let lower = status.to_ascii_lowercase();
+    status.contains("待")
+        || status.contains("处理中")
+        || status.contains("支付中")
+        || status.contains("转账中")
+        || ["pending", "processing", "awaiting"]
+            .iter()
+            .any(|word| lower.contains(word))
+}
+
 fn is_success_status(status: &str) -> bool {
     status.contains("成功")
         || status.contains("已收钱")
@@ -271,6 +418,32 @@ fn is_success_status(status: &str) -> bool {
         || status.contains("已收款")
         || status.contains("已入账")
         || status.contains("已存入")
+        || is_success_status_en(&status.to_ascii_lowercase())
+}
+
+/// English statuses: "Payment Successful", "Received", "Refunded", ...
+///
+/// Matches whole words so "Unsuccessful" and "Incomplete" do not count, and
+/// rejects statuses that say the step failed ("Refund failed", "Not received").
+fn is_success_status_en(status: &str) -> bool {
+    let words: Vec<&str> = status
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let has = |candidates: &[&str]| words.iter().any(|word| candidates.contains(word));
+
+    !has(&["not", "failed", "fail"])
+        && has(&[
+            "success",
+            "successful",
+            "succeeded",
+            "received",
+            "complete",
+            "completed",
+            "refund",
+            "refunded",
+            "deposited",
+        ])
 }
 
 fn build_description(
@@ -331,3 +504,413 @@ fn make_wechat_txn_id(
 
     format!("WECHAT-{}", hex::encode(&hash[..12]))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use calamine::Range;
+
+    fn sheet(rows: &[[&str; 11]]) -> Range<Data> {
+        let mut range = Range::new((0, 0), (rows.len() as u32 - 1, 10));
+        for (r, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                range.set_value((r as u32, c as u32), Data::String(cell.to_string()));
+            }
+        }
+        range
+    }
+
+    const ZH_HEADER: [&str; 11] = [
+        "交易时间",
+        "交易类型",
+        "交易对方",
+        "商品",
+        "收/支",
+        "金额(元)",
+        "支付方式",
+        "当前状态",
+        "交易单号",
+        "商户单号",
+        "备注",
+    ];
+
+    const EN_HEADER: [&str; 11] = [
+        "Transaction Time",
+        "Transaction Type",
+        "Counterparty",
+        "Item",
+        "Income/Expenses",
+        "Amount(RMB)",
+        "Payment Method",
+        "Status",
+        "Transaction ID",
+        "Merchant Tracking Number",
+        "Remarks",
+    ];
+
+    #[test]
+    fn english_headers_parse_like_chinese_headers() {
+        let parser = WeChatXlsxParser::new("WECHAT_CNY");
+        let zh = sheet(&[
+            ["微信支付账单明细", "", "", "", "", "", "", "", "", "", ""],
+            ZH_HEADER,
+            [
+                "2025-03-01 12:30:00",
+                "扫二维码付款",
+                "Luckin",
+                "咖啡",
+                "支出",
+                "¥18.00",
+                "招商银行(8866)",
+                "支付成功",
+                "T001",
+                "M001",
+                "/",
+            ],
+            [
+                "2025-03-02 09:00:00",
+                "转账",
+                "Li",
+                "/",
+                "收入",
+                "¥200.00",
+                "/",
+                "已收钱",
+                "T002",
+                "M002",
+                "/",
+            ],
+            [
+                "2025-03-03 18:00:00",
+                "微信红包",
+                "Wang",
+                "/",
+                "收入",
+                "¥66.00",
+                "/",
+                "已存入零钱",
+                "T003",
+                "/",
+                "/",
+            ],
+            [
+                "2025-03-04 10:00:00",
+                "零钱提现",
+                "招商银行(8866)",
+                "/",
+                "/",
+                "¥300.00",
+                "招商银行(8866)",
+                "提现已到账",
+                "T004",
+                "/",
+                "/",
+            ],
+        ]);
+        let en = sheet(&[
+            [
+                "WeChat Pay Statement",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+            ],
+            EN_HEADER,
+            [
+                "2025-03-01 12:30:00",
+                "QR code payment",
+                "Luckin",
+                "咖啡",
+                "Expenses",
+                "¥18.00",
+                "招商银行(8866)",
+                "Payment Successful",
+                "T001",
+                "M001",
+                "/",
+            ],
+            [
+                "2025-03-02 09:00:00",
+                "转账",
+                "Li",
+                "/",
+                "Income",
+                "¥200.00",
+                "/",
+                "Received",
+                "T002",
+                "M002",
+                "/",
+            ],
+            [
+                "2025-03-03 18:00:00",
+                "Red Packet",
+                "Wang",
+                "/",
+                "Income",
+                "¥66.00",
+                "/",
+                "Received",
+                "T003",
+                "/",
+                "/",
+            ],
+            [
+                "2025-03-04 10:00:00",
+                "Withdrawal",
+                "招商银行(8866)",
+                "/",
+                "/",
+                "¥300.00",
+                "招商银行(8866)",
+                "Withdrawal Completed",
+                "T004",
+                "/",
+                "/",
+            ],
+        ]);
+
+        let zh_txns = parse_range(&parser, &zh, "zh.xlsx").unwrap();
+        let en_txns = parse_range(&parser, &en, "en.xlsx").unwrap();
+
+        assert_eq!(zh_txns.len(), 4);
+        assert_eq!(zh_txns, en_txns);
+        assert_eq!(en_txns[0]["type"], "expense");
+        assert_eq!(en_txns[0]["funding_last4"], "8866");
+        assert_eq!(en_txns[1]["type"], "income");
+        assert_eq!(en_txns[1]["amount"], 200.0);
+        assert_eq!(en_txns[2]["type"], "income");
+        assert_eq!(en_txns[3]["type"], "internal_transfer");
+        assert_eq!(en_txns[3]["to_account_id"], "INTERNAL_DESTINATION");
+    }
+
+    #[test]
+    fn wallet_top_up_and_withdrawal_route_through_internal_placeholders() {
+        let parser = WeChatXlsxParser::new("WECHAT_CNY");
+        let range = sheet(&[
+            ZH_HEADER,
+            [
+                "2025-03-01 08:00:00",
+                "零钱充值",
+                "招商银行(8866)",
+                "/",
+                "/",
+                "¥500.00",
+                "招商银行(8866)",
+                "充值完成",
+                "T010",
+                "/",
+                "/",
+            ],
+            [
+                "2025-03-05 18:00:00",
+                "零钱提现",
+                "招商银行(8866)",
+                "/",
+                "/",
+                "¥120.00",
+                "零钱",
+                "提现已到账",
+                "T011",
+                "/",
+                "/",
+            ],
+        ]);
+
+        let txns = parse_range(&parser, &range, "wallet.xlsx").unwrap();
+
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0]["type"], "internal_transfer");
+        assert_eq!(txns[0]["from_account_id"], "INTERNAL_SOURCE");
+        assert_eq!(txns[0]["to_account_id"], "WECHAT_CNY");
+        assert_eq!(txns[1]["type"], "internal_transfer");
+        assert_eq!(txns[1]["from_account_id"], "WECHAT_CNY");
+        assert_eq!(txns[1]["to_account_id"], "INTERNAL_DESTINATION");
+    }
+
+    #[test]
+    fn skipped_status_row_logs_warning() {
+        let parser = WeChatXlsxParser::new("WECHAT_CNY");
+        let range = sheet(&[
+            ZH_HEADER,
+            [
+                "2025-03-01 12:30:00",
+                "商户消费",
+                "Luckin",
+                "咖啡",
+                "支出",
+                "¥18.00",
+                "/",
+                "支付失败",
+                "T001",
+                "M001",
+                "/",
+            ],
+        ]);
+
+        let mut txns = Vec::new();
+        let records = logger::capture_logs(|| {
+            txns = parse_range(&parser, &range, "failed.xlsx").unwrap();
+        });
+
+        assert!(txns.is_empty());
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].0, log::Level::Warn);
+        assert!(records[0].1.contains("支付失败"));
+    }
+
+    #[test]
+    fn classify_status_matches_english_statuses_as_whole_words() {
+        for status in [
+            "Payment Successful",
+            "Received",
+            "Refunded",
+            "Completed",
+            "Deposited",
+        ] {
+            assert_eq!(classify_status(status), RowStatus::Completed, "{}", status);
+        }
+        for status in [
+            "Unsuccessful",
+            "Payment Unsuccessful",
+            "Incomplete",
+            "Not received",
+            "Refund failed",
+        ] {
+            assert_eq!(classify_status(status), RowStatus::Other, "{}", status);
+        }
+    }
+
+    #[test]
+    fn classify_txn_type_recognizes_wx_types() {
+        let cases = [
+            ("微信红包-发红包", "expense"),
+            ("送红包", "expense"),
+            ("微信红包-收红包", "income"),
+            ("微信红包-退回", "income"),
+            ("零钱充值", "internal_transfer"),
+            ("零钱提现", "internal_transfer"),
+            ("充值", "income"),
+            ("提现", "expense"),
+            ("退款", "income"),
+        ];
+
+        for (wx_type, expected) in cases {
+            assert_eq!(
+                classify_txn_type("/", wx_type, 10.0),
+                expected,
+                "wx_type {}",
+                wx_type
+            );
+        }
+    }
+
+    #[test]
+    fn parse_funding_card_extracts_bank_and_last4() {
+        let card = |bank: &str, last4: &str| Some((bank.to_string(), last4.to_string()));
+
+        assert_eq!(parse_funding_card("招商银行(8866)"), card("招商银行", "8866"));
+        assert_eq!(
+            parse_funding_card("工商银行储蓄卡(1234)"),
+            card("工商银行储蓄卡", "1234")
+        );
+        assert_eq!(parse_funding_card("建设银行（0042）"), card("建设银行", "0042"));
+        assert_eq!(parse_funding_card(" 中国银行 (5678) "), card("中国银行", "5678"));
+    }
+
+    #[test]
+    fn parse_funding_card_ignores_wallet_and_malformed_methods() {
+        assert_eq!(parse_funding_card("零钱"), None);
+        assert_eq!(parse_funding_card("零钱通"), None);
+        assert_eq!(parse_funding_card("/"), None);
+        assert_eq!(parse_funding_card(""), None);
+        assert_eq!(parse_funding_card("(1234)"), None);
+        assert_eq!(parse_funding_card("招商银行(88a6)"), None);
+    }
+
+    #[test]
+    fn classify_txn_type_falls_back_to_inout_and_sign() {
+        assert_eq!(classify_txn_type("支出", "商户消费", 10.0), "expense");
+        assert_eq!(classify_txn_type("收入", "转账", 10.0), "income");
+        assert_eq!(classify_txn_type("/", "转账", -10.0), "expense");
+        assert_eq!(classify_txn_type("/", "转账", 10.0), "income");
+    }
+
+    #[test]
+    fn canonical_wx_type_maps_english_transaction_types() {
+        let cases = [
+            ("Red Packet", "微信红包"),
+            ("Red Packet Sent", "微信红包-发红包"),
+            ("Red Packet Refund", "微信红包-退回"),
+            ("Recharge", "零钱充值"),
+            ("Withdrawal", "零钱提现"),
+            ("Transfer", "转账"),
+            ("QR code payment", "扫二维码付款"),
+            ("Refund", "退款"),
+            ("商户消费", "商户消费"),
+            ("Group Payment", "Group Payment"),
+        ];
+
+        for (wx_type, expected) in cases {
+            assert_eq!(canonical_wx_type(wx_type), expected);
+        }
+        assert_eq!(
+            classify_txn_type("/", &canonical_wx_type("Red Packet Sent"), 10.0),
+            "expense"
+        );
+    }
+
+    fn status_sheet() -> Range<Data> {
+        let row = |time: &str, status: &str, id: &str| {
+            [
+                time,
+                "商户消费",
+                "Luckin",
+                "咖啡",
+                "支出",
+                "¥18.00",
+                "零钱",
+                status,
+                id,
+                "/",
+                "/",
+            ]
+            .map(str::to_string)
+        };
+        let rows = [
+            row("2025-03-01 12:30:00", "支付成功", "T001"),
+            row("2025-03-02 12:30:00", "处理中", "T002"),
+            row("2025-03-03 12:30:00", "已关闭", "T003"),
+        ];
+        let mut all: Vec<[&str; 11]> = vec![ZH_HEADER];
+        all.extend(rows.iter().map(|r| r.each_ref().map(String::as_str)));
+        sheet(&all)
+    }
+
+    #[test]
+    fn status_filter_controls_pending_rows() {
+        use utils::StatusFilter;
+
+        let range = status_sheet();
+        let parse = |filter| {
+            let parser = WeChatXlsxParser::new("WECHAT_CNY").with_status_filter(filter);
+            parse_range(&parser, &range, "status.xlsx").unwrap()
+        };
+
+        let completed = parse(StatusFilter::CompletedOnly);
+        assert_eq!(completed.len(), 1);
+        assert!(completed[0].get("pending").is_none());
+
+        let with_pending = parse(StatusFilter::IncludePending);
+        assert_eq!(with_pending.len(), 2);
+        assert_eq!(with_pending[1]["pending"], true);
+
+        assert_eq!(parse(StatusFilter::All).len(), 3);
+    }
+}
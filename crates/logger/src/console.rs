@@ -0,0 +1,135 @@
+//! Backend for the `log` facade.
+//!
+//! Library crates report progress and problems through `log::info!`,
+//! `log::warn!` and friends and never print. Binaries call
+//! [`init_console_logging`] once; records then go to stdout (info and below)
+//! or stderr (warnings and errors). `MATAPAN_LOG_LEVEL` (`error`, `warn`,
+//! `info`, `debug`, `trace` or `off`) sets the threshold, default `info`.
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static LOGGER: MatapanLogger = MatapanLogger;
+static CONSOLE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<(Level, String)>>> = const { RefCell::new(None) };
+}
+
+struct MatapanLogger;
+
+impl Log for MatapanLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let captured = CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+            Some(records) => {
+                records.push((record.level(), record.args().to_string()));
+                true
+            }
+            None => false,
+        });
+        if captured || !CONSOLE_ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+
+        match record.level() {
+            Level::Error => eprintln!("❌ {}", record.args()),
+            Level::Warn => eprintln!("  ⚠️  {}", record.args()),
+            _ => println!("{}", record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the console logger. Call once at the start of `main`.
+pub fn init_console_logging() {
+    let _ = log::set_logger(&LOGGER);
+    CONSOLE_ENABLED.store(true, Ordering::Relaxed);
+    log::set_max_level(level_from_env());
+}
+
+/// Runs `f` and returns the records it logged on the current thread instead
+/// of printing them. Intended for tests.
+pub fn capture_logs<F: FnOnce()>(f: F) -> Vec<(Level, String)> {
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(LevelFilter::Trace);
+
+    buffer_logs(f).1
+}
+
+/// Runs `f` and holds back the records it logs on the current thread,
+/// returning them with its result. The level threshold is left as it is, so
+/// passing the records to [`replay_logs`] later prints what `f` would have.
+pub fn buffer_logs<T, F: FnOnce() -> T>(f: F) -> (T, Vec<(Level, String)>) {
+    let previous = CAPTURED.with(|captured| captured.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let records = CAPTURED
+        .with(|captured| std::mem::replace(&mut *captured.borrow_mut(), previous))
+        .unwrap_or_default();
+    (result, records)
+}
+
+/// Logs records held back by [`buffer_logs`], in order.
+pub fn replay_logs(records: Vec<(Level, String)>) {
+    for (level, message) in records {
+        log::log!(level, "{}", message);
+    }
+}
+
+fn level_from_env() -> LevelFilter {
+    std::env::var("MATAPAN_LOG_LEVEL")
+        .ok()
+        .and_then(|raw| raw.trim().parse().ok())
+        .unwrap_or(LevelFilter::Info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_logs_collects_records_from_current_thread() {
+        let records = capture_logs(|| {
+            log::info!("parsed {} rows", 3);
+            log::warn!("skipped row {}", 4);
+        });
+
+        assert_eq!(
+            records,
+            vec![
+                (Level::Info, "parsed 3 rows".to_string()),
+                (Level::Warn, "skipped row 4".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn buffer_logs_holds_back_records_until_replayed() {
+        let records = capture_logs(|| {
+            let (value, held) = buffer_logs(|| {
+                log::warn!("from worker");
+                7
+            });
+            assert_eq!(value, 7);
+            log::info!("header");
+            replay_logs(held);
+        });
+
+        assert_eq!(
+            records,
+            vec![
+                (Level::Info, "header".to_string()),
+                (Level::Warn, "from worker".to_string()),
+            ]
+        );
+    }
+}
@@ -5,6 +5,10 @@ use std::fs::{create_dir_all, OpenOptions};
 use std::io::Write;
 use std::path::PathBuf;
 
+mod console;
+
+pub use console::{buffer_logs, capture_logs, init_console_logging, replay_logs};
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "snake_case")]
 enum EventType {
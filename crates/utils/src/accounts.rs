@@ -1,9 +1,10 @@
 //! Builds and merges normalized account entities in database.json.
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
 use logger::log_account_added;
 use serde_json::{json, Value};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub struct AccountInput {
@@ -22,10 +23,83 @@ pub fn build_account(input: &AccountInput) -> Value {
         "country": input.country,
         "iban": input.iban,
         "bic": input.bic,
-        "is_active": input.is_active
+        "is_active": input.is_active,
+        "opening_balance": null,
+        "opening_balance_date": null
     })
 }
 
+/// Balance of `account_id` at the end of `as_of`.
+///
+/// Starts from the account's `opening_balance` (0 when unset) and adds every
+/// transaction dated from `opening_balance_date` through `as_of`: inflows to
+/// the account count positive, outflows negative. Without an opening date all
+/// transactions up to `as_of` are summed.
+pub fn account_balance(database: &Value, account_id: &str, as_of: NaiveDate) -> Result<f64> {
+    let account = database
+        .get("accounts")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("database.json missing 'accounts' array"))?
+        .iter()
+        .find(|acc| acc.get("account_id").and_then(|v| v.as_str()) == Some(account_id))
+        .ok_or_else(|| anyhow!("Unknown account_id: {}", account_id))?;
+
+    let opening_balance = account
+        .get("opening_balance")
+        .and_then(number_value)
+        .unwrap_or(0.0);
+    let opening_date = match account.get("opening_balance_date").and_then(|v| v.as_str()) {
+        Some(raw) => Some(
+            parse_date(raw)
+                .with_context(|| format!("Invalid opening_balance_date for {}", account_id))?,
+        ),
+        None => None,
+    };
+
+    let txns = database
+        .get("transactions")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("database.json missing 'transactions' array"))?;
+
+    let mut balance = opening_balance;
+    for txn in txns {
+        let from = txn.get("from_account_id").and_then(|v| v.as_str());
+        let to = txn.get("to_account_id").and_then(|v| v.as_str());
+        let sign = match (from == Some(account_id), to == Some(account_id)) {
+            (false, true) => 1.0,
+            (true, false) => -1.0,
+            _ => continue,
+        };
+
+        let raw_date = txn.get("date").and_then(|v| v.as_str()).unwrap_or("");
+        let date = parse_date(raw_date).with_context(|| {
+            format!(
+                "Invalid date in transaction {}",
+                txn.get("txn_id").and_then(|v| v.as_str()).unwrap_or("?")
+            )
+        })?;
+        if date > as_of || opening_date.is_some_and(|open| date < open) {
+            continue;
+        }
+
+        balance += sign * txn.get("amount").and_then(number_value).unwrap_or(0.0);
+    }
+
+    Ok(balance)
+}
+
+fn number_value(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_date(raw: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").with_context(|| format!("'{}'", raw))
+}
+
 /// Merges new accounts into an existing database template with duplicate detection.
 /// Accounts are considered duplicates if they have the same `account_id`.
 ///
@@ -137,36 +211,273 @@ pub fn find_duplicate_account_ids(database: &Value, account_ids: &[String]) -> R
     Ok(duplicates)
 }
 
+/// Flags pairs of distinct account IDs that probably refer to the same real
+/// account, e.g. `REVOLUT_CURRENT` and `REVOLUT_ACCOUNT_2` after two imports
+/// with different parser settings.
+///
+/// Two accounts are flagged when they have the same `institution`
+/// (case-insensitive) and at least half of the smaller account's transaction
+/// fingerprints (date, direction, amount, currency), and no fewer than two,
+/// also appear on the other account. System accounts are ignored. Nothing is
+/// modified; the pairs (sorted, smaller ID first) are meant for review, e.g.
+/// before calling [`rename_account`].
+pub fn find_likely_duplicate_accounts(database: &Value) -> Vec<(String, String)> {
+    let Some(accounts) = database.get("accounts").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    let txns = database
+        .get("transactions")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut candidates: Vec<(String, String)> = accounts
+        .iter()
+        .filter(|acc| acc.get("structural_type").and_then(|v| v.as_str()) != Some("system"))
+        .filter_map(|acc| {
+            let id = acc.get("account_id")?.as_str()?;
+            let institution = acc.get("institution")?.as_str()?.trim().to_lowercase();
+            (!SYSTEM_ACCOUNT_IDS.contains(&id) && !institution.is_empty())
+                .then(|| (id.to_string(), institution))
+        })
+        .collect();
+    candidates.sort();
+    candidates.dedup_by(|a, b| a.0 == b.0);
+
+    let fingerprints: HashMap<&str, HashSet<String>> = candidates
+        .iter()
+        .map(|(id, _)| (id.as_str(), transaction_fingerprints(txns, id)))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (i, (id_a, institution_a)) in candidates.iter().enumerate() {
+        for (id_b, institution_b) in &candidates[i + 1..] {
+            if institution_a != institution_b {
+                continue;
+            }
+            let (a, b) = (&fingerprints[id_a.as_str()], &fingerprints[id_b.as_str()]);
+            let shared = a.intersection(b).count();
+            if shared >= 2 && shared * 2 >= a.len().min(b.len()) {
+                pairs.push((id_a.clone(), id_b.clone()));
+            }
+        }
+    }
+    pairs
+}
+
+fn transaction_fingerprints(txns: &[Value], account_id: &str) -> HashSet<String> {
+    txns.iter()
+        .filter_map(|txn| {
+            let from = txn.get("from_account_id").and_then(|v| v.as_str());
+            let to = txn.get("to_account_id").and_then(|v| v.as_str());
+            let direction = if from == Some(account_id) {
+                "out"
+            } else if to == Some(account_id) {
+                "in"
+            } else {
+                return None;
+            };
+            Some(format!(
+                "{}|{}|{:.2}|{}",
+                txn.get("date")?.as_str()?,
+                direction,
+                txn.get("amount").and_then(number_value)?,
+                txn.get("currency").and_then(|v| v.as_str()).unwrap_or(""),
+            ))
+        })
+        .collect()
+}
+
+/// What [`rename_account`] rewrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenameStats {
+    pub transactions: usize,
+    pub positions: usize,
+    pub balance_references: usize,
+    /// Transactions whose `txn_id` had the old account ID as a `-`-separated part.
+    pub txn_ids: usize,
+    /// Whether both account records existed and were merged into one.
+    pub merged_account: bool,
+}
+
+/// Renames `from_id` to `to_id` everywhere in the database: account records,
+/// `from_account_id`/`to_account_id`/`account_id` of transactions, and the
+/// `account_id` of positions and balance references.
+///
+/// If both accounts already exist, the `to_id` record is kept and any field
+/// it lacks (missing or `null`) is filled from the `from_id` record. A
+/// `txn_id` with `from_id` as one of its `-`-separated parts is rewritten to
+/// use `to_id` (along with `linked_txn_id` references to it). Hashed txn_ids
+/// cannot be recomputed here, so the transaction is marked with
+/// `renamed_from`; the next merge hands its txn_id over to the incoming
+/// transaction with the same content, which the parser now derives from
+/// `to_id`.
+pub fn rename_account(database: &mut Value, from_id: &str, to_id: &str) -> Result<RenameStats> {
+    if from_id.trim().is_empty() || to_id.trim().is_empty() {
+        return Err(anyhow!("Account IDs must not be empty"));
+    }
+    if from_id == to_id {
+        return Err(anyhow!("Cannot rename account {} to itself", from_id));
+    }
+
+    let mut stats = RenameStats::default();
+
+    let accounts = database
+        .get_mut("accounts")
+        .and_then(|v| v.as_array_mut())
+        .ok_or_else(|| anyhow!("database.json missing 'accounts' array"))?;
+    let position_of = |accounts: &[Value], id: &str| {
+        accounts
+            .iter()
+            .position(|acc| acc.get("account_id").and_then(|v| v.as_str()) == Some(id))
+    };
+    match (position_of(accounts, from_id), position_of(accounts, to_id)) {
+        (Some(from_idx), Some(to_idx)) => {
+            let from_account = accounts.remove(from_idx);
+            let to_idx = if from_idx < to_idx {
+                to_idx - 1
+            } else {
+                to_idx
+            };
+            if let (Some(from_obj), Some(to_obj)) =
+                (from_account.as_object(), accounts[to_idx].as_object_mut())
+            {
+                for (key, value) in from_obj {
+                    let target = to_obj.entry(key.clone()).or_insert(Value::Null);
+                    if target.is_null() {
+                        *target = value.clone();
+                    }
+                }
+            }
+            stats.merged_account = true;
+        }
+        (Some(from_idx), None) => {
+            accounts[from_idx]["account_id"] = json!(to_id);
+        }
+        _ => {}
+    }
+
+    let mut renamed_txn_ids = HashMap::new();
+    if let Some(txns) = database
+        .get_mut("transactions")
+        .and_then(|v| v.as_array_mut())
+    {
+        for txn in txns.iter_mut() {
+            let mut touched = false;
+            for key in ["from_account_id", "to_account_id", "account_id"] {
+                touched |= rename_field(txn, key, from_id, to_id);
+            }
+            if touched {
+                stats.transactions += 1;
+            }
+
+            if let Some(old) = txn.get("txn_id").and_then(|v| v.as_str()) {
+                if let Some(new) = rename_txn_id(old, from_id, to_id) {
+                    renamed_txn_ids.insert(old.to_string(), new.clone());
+                    txn["txn_id"] = json!(new);
+                    stats.txn_ids += 1;
+                } else if touched {
+                    txn["renamed_from"] = json!(from_id);
+                }
+            }
+        }
+
+        for txn in txns.iter_mut() {
+            let linked = txn.get("linked_txn_id").and_then(|v| v.as_str());
+            if let Some(new) = linked.and_then(|id| renamed_txn_ids.get(id)) {
+                txn["linked_txn_id"] = json!(new);
+            }
+        }
+    }
+
+    for (section, count) in [
+        ("positions", &mut stats.positions),
+        ("balance_references", &mut stats.balance_references),
+    ] {
+        if let Some(items) = database.get_mut(section).and_then(|v| v.as_array_mut()) {
+            for item in items.iter_mut() {
+                if rename_field(item, "account_id", from_id, to_id) {
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Replaces the `-`-separated parts of `txn_id` equal to `from_id`. Returns
+/// `None` when no part matches.
+fn rename_txn_id(txn_id: &str, from_id: &str, to_id: &str) -> Option<String> {
+    let parts: Vec<&str> = txn_id.split('-').collect();
+    if !parts.contains(&from_id) {
+        return None;
+    }
+    let renamed: Vec<&str> = parts
+        .into_iter()
+        .map(|part| if part == from_id { to_id } else { part })
+        .collect();
+    Some(renamed.join("-"))
+}
+
+fn rename_field(entity: &mut Value, key: &str, from_id: &str, to_id: &str) -> bool {
+    match entity.get_mut(key) {
+        Some(value) if value.as_str() == Some(from_id) => {
+            *value = json!(to_id);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Placeholder accounts that parsers route flows to when the real
+/// counterparty is unknown or outside the user's accounts.
+pub const SYSTEM_ACCOUNT_IDS: [&str; 6] = [
+    "EXTERNAL_PAYER",
+    "EXTERNAL_PAYEE",
+    "INTERNAL_SOURCE",
+    "INTERNAL_DESTINATION",
+    "INTERNAL_UNKNOWN",
+    "FX_EXCHANGE",
+];
+
 /// Creates standard system accounts that are used across all parsers.
 /// These accounts represent external parties and are used when money flows
-/// in/out of the user's accounts from/to unknown external sources.
+/// in/out of the user's accounts from/to unknown external sources, plus the
+/// internal and FX placeholders. Each is tagged `"structural_type": "system"`.
 ///
 /// Returns a vector of system account entries.
 pub fn create_system_accounts() -> Vec<Value> {
-    vec![
-        build_account(&AccountInput {
-            account_id: "EXTERNAL_PAYER".to_string(),
-            institution: "External".to_string(),
-            country: None,
-            iban: None,
-            bic: None,
-            is_active: true,
-        }),
-        build_account(&AccountInput {
-            account_id: "EXTERNAL_PAYEE".to_string(),
-            institution: "External".to_string(),
-            country: None,
-            iban: None,
-            bic: None,
-            is_active: true,
-        }),
-    ]
+    SYSTEM_ACCOUNT_IDS
+        .iter()
+        .map(|id| {
+            let institution = if id.starts_with("EXTERNAL_") {
+                "External"
+            } else {
+                "Internal"
+            };
+            let mut account = build_account(&AccountInput {
+                account_id: id.to_string(),
+                institution: institution.to_string(),
+                country: None,
+                iban: None,
+                bic: None,
+                is_active: true,
+            });
+            if let Some(obj) = account.as_object_mut() {
+                obj.insert("structural_type".to_string(), json!("system"));
+            }
+            account
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use regex::Regex;
     use serde_json::json;
+    use std::path::Path;
 
     #[test]
     fn test_merge_with_no_duplicates() {
@@ -238,4 +549,277 @@ mod tests {
         assert!(duplicates.contains(&"ACC002".to_string()));
         assert!(!duplicates.contains(&"ACC003".to_string()));
     }
+
+    fn balance_fixture(opening_balance: Value, opening_date: Value) -> Value {
+        json!({
+            "accounts": [{
+                "account_id": "SEB_CURRENT",
+                "institution": "SEB",
+                "opening_balance": opening_balance,
+                "opening_balance_date": opening_date
+            }],
+            "transactions": [
+                {"date": "2023-12-31", "from_account_id": "EXTERNAL_PAYER",
+                 "to_account_id": "SEB_CURRENT", "amount": 50.0},
+                {"date": "2024-01-10", "from_account_id": "EXTERNAL_PAYER",
+                 "to_account_id": "SEB_CURRENT", "amount": 1000.0},
+                {"date": "2024-01-15", "from_account_id": "SEB_CURRENT",
+                 "to_account_id": "EXTERNAL_PAYEE", "amount": 200.0},
+                {"date": "2024-02-01", "from_account_id": "SEB_CURRENT",
+                 "to_account_id": "EXTERNAL_PAYEE", "amount": 300.0}
+            ]
+        })
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_account_balance_without_opening_balance() {
+        let db = balance_fixture(Value::Null, Value::Null);
+
+        let balance = account_balance(&db, "SEB_CURRENT", date("2024-01-31")).unwrap();
+
+        assert_eq!(balance, 850.0);
+    }
+
+    #[test]
+    fn test_account_balance_with_opening_balance() {
+        let db = balance_fixture(json!(5000.0), json!("2024-01-01"));
+
+        // The 2023 inflow predates the opening balance and is already in it.
+        assert_eq!(
+            account_balance(&db, "SEB_CURRENT", date("2024-01-31")).unwrap(),
+            5800.0
+        );
+        assert_eq!(
+            account_balance(&db, "SEB_CURRENT", date("2024-02-01")).unwrap(),
+            5500.0
+        );
+        assert!(account_balance(&db, "UNKNOWN", date("2024-02-01")).is_err());
+    }
+
+    #[test]
+    fn test_build_account_defaults_opening_balance_to_null() {
+        let account = build_account(&AccountInput {
+            account_id: "ACC001".to_string(),
+            institution: "Bank A".to_string(),
+            country: None,
+            iban: None,
+            bic: None,
+            is_active: true,
+        });
+
+        assert!(account["opening_balance"].is_null());
+        assert!(account["opening_balance_date"].is_null());
+    }
+
+    #[test]
+    fn test_system_accounts_cover_parser_placeholders() {
+        let parsers_dir =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../data/bank_statement_parsers");
+        let placeholder = Regex::new(r#""((?:EXTERNAL|INTERNAL|FX)_[A-Z_]+)""#).unwrap();
+
+        let mut used = HashSet::new();
+        let mut dirs = vec![parsers_dir];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    let source = std::fs::read_to_string(&path).unwrap();
+                    for caps in placeholder.captures_iter(&source) {
+                        used.insert(caps[1].to_string());
+                    }
+                }
+            }
+        }
+        assert!(
+            used.contains("EXTERNAL_PAYEE"),
+            "scan found no parser sources"
+        );
+
+        let system: HashSet<String> = create_system_accounts()
+            .iter()
+            .map(|acc| acc["account_id"].as_str().unwrap().to_string())
+            .collect();
+        let missing: Vec<_> = used.difference(&system).collect();
+        assert!(missing.is_empty(), "missing system accounts: {:?}", missing);
+
+        for account in create_system_accounts() {
+            assert_eq!(account["structural_type"], "system");
+        }
+    }
+
+    fn rename_db() -> Value {
+        json!({
+            "accounts": [
+                {"account_id": "SEB_ACCOUNT_1", "institution": "SEB", "iban": "SE123"},
+                {"account_id": "SEB_CHECKING", "institution": "SEB", "iban": null}
+            ],
+            "transactions": [
+                {"txn_id": "SEB-abc", "from_account_id": "SEB_ACCOUNT_1",
+                 "to_account_id": "EXTERNAL_PAYEE", "amount": 10.0},
+                {"txn_id": "SEB_ACCOUNT_1-0001", "from_account_id": "EXTERNAL_PAYER",
+                 "to_account_id": "SEB_ACCOUNT_1", "amount": 20.0},
+                {"txn_id": "REV-1", "from_account_id": "REVOLUT_CURRENT",
+                 "to_account_id": "EXTERNAL_PAYEE", "amount": 5.0,
+                 "linked_txn_id": "SEB_ACCOUNT_1-0001"}
+            ],
+            "positions": [
+                {"position_id": "P1", "account_id": "SEB_ACCOUNT_1"},
+                {"position_id": "P2", "account_id": "IBKR"}
+            ]
+        })
+    }
+
+    #[test]
+    fn rename_account_rewrites_transaction_references() {
+        let mut db = rename_db();
+
+        let stats = rename_account(&mut db, "SEB_ACCOUNT_1", "SEB_CHECKING").unwrap();
+
+        assert_eq!(stats.transactions, 2);
+        assert_eq!(stats.txn_ids, 1);
+        let txns = db["transactions"].as_array().unwrap();
+        assert_eq!(txns[0]["from_account_id"], "SEB_CHECKING");
+        assert_eq!(txns[0]["txn_id"], "SEB-abc");
+        assert_eq!(txns[0]["renamed_from"], "SEB_ACCOUNT_1");
+        assert_eq!(txns[1]["to_account_id"], "SEB_CHECKING");
+        assert_eq!(txns[1]["txn_id"], "SEB_CHECKING-0001");
+        assert_eq!(txns[2]["linked_txn_id"], "SEB_CHECKING-0001");
+        assert_eq!(txns[2]["from_account_id"], "REVOLUT_CURRENT");
+    }
+
+    #[test]
+    fn rename_account_matches_whole_txn_id_parts() {
+        let mut db = json!({
+            "accounts": [{"account_id": "SEB", "institution": "SEB"}],
+            "transactions": [
+                {"txn_id": "SEB-0001", "from_account_id": "SEB",
+                 "to_account_id": "EXTERNAL_PAYEE", "amount": 1.0},
+                {"txn_id": "SEB_SAVINGS-0002", "from_account_id": "SEB_SAVINGS",
+                 "to_account_id": "EXTERNAL_PAYEE", "amount": 2.0}
+            ]
+        });
+
+        let stats = rename_account(&mut db, "SEB", "SEB_CHECKING").unwrap();
+
+        assert_eq!(stats.txn_ids, 1);
+        assert_eq!(db["transactions"][0]["txn_id"], "SEB_CHECKING-0001");
+        assert_eq!(db["transactions"][1]["txn_id"], "SEB_SAVINGS-0002");
+    }
+
+    #[test]
+    fn rename_account_then_reimport_does_not_duplicate() {
+        let txn = |txn_id: &str, account: &str| {
+            json!({"txn_id": txn_id, "date": "2024-03-01", "from_account_id": account,
+                   "to_account_id": "EXTERNAL_PAYEE", "type": "expense", "amount": 10.0,
+                   "currency": "SEK", "description": "ICA", "category": "groceries"})
+        };
+        let mut db = json!({
+            "accounts": [{"account_id": "SEB_ACCOUNT_1", "institution": "SEB"}],
+            "transactions": [txn("SEB-hash-old", "SEB_ACCOUNT_1")]
+        });
+        rename_account(&mut db, "SEB_ACCOUNT_1", "SEB_CHECKING").unwrap();
+
+        // The parser now hashes the new account ID into a different txn_id.
+        let mut reimported = txn("SEB-hash-new", "SEB_CHECKING");
+        reimported["category"] = json!("uncategorized");
+        let (merged, stats) =
+            crate::merge_transactions_with_deduplication(db, vec![reimported]).unwrap();
+
+        assert_eq!((stats.added, stats.skipped), (0, 1));
+        let txns = merged["transactions"].as_array().unwrap();
+        assert_eq!(txns.len(), 1);
+        assert_eq!(txns[0]["txn_id"], "SEB-hash-new");
+        assert_eq!(txns[0]["category"], "groceries");
+        assert!(txns[0].get("renamed_from").is_none());
+    }
+
+    #[test]
+    fn rename_account_rewrites_positions_and_merges_accounts() {
+        let mut db = rename_db();
+
+        let stats = rename_account(&mut db, "SEB_ACCOUNT_1", "SEB_CHECKING").unwrap();
+
+        assert_eq!(stats.positions, 1);
+        assert_eq!(db["positions"][0]["account_id"], "SEB_CHECKING");
+        assert_eq!(db["positions"][1]["account_id"], "IBKR");
+
+        assert!(stats.merged_account);
+        let accounts = db["accounts"].as_array().unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0]["account_id"], "SEB_CHECKING");
+        assert_eq!(accounts[0]["iban"], "SE123");
+    }
+
+    #[test]
+    fn rename_account_renames_lone_record() {
+        let mut db = json!({
+            "accounts": [{"account_id": "OLD", "institution": "Bank"}],
+            "transactions": []
+        });
+
+        let stats = rename_account(&mut db, "OLD", "NEW").unwrap();
+
+        assert!(!stats.merged_account);
+        assert_eq!(db["accounts"][0]["account_id"], "NEW");
+        assert!(rename_account(&mut db, "NEW", "NEW").is_err());
+    }
+
+    fn duplicate_db() -> Value {
+        let txn = |id: &str, account: &str, date: &str, amount: f64| {
+            json!({"txn_id": id, "date": date, "from_account_id": account,
+                   "to_account_id": "EXTERNAL_PAYEE", "amount": amount, "currency": "EUR"})
+        };
+        json!({
+            "accounts": [
+                {"account_id": "REVOLUT_CURRENT", "institution": "Revolut"},
+                {"account_id": "REVOLUT_ACCOUNT_2", "institution": "revolut"},
+                {"account_id": "SEB_CHECKING", "institution": "SEB"},
+                {"account_id": "EXTERNAL_PAYEE", "institution": "External",
+                 "structural_type": "system"}
+            ],
+            "transactions": [
+                txn("A1", "REVOLUT_CURRENT", "2024-01-03", 12.5),
+                txn("A2", "REVOLUT_CURRENT", "2024-01-04", 40.0),
+                txn("A3", "REVOLUT_CURRENT", "2024-01-09", 7.0),
+                txn("B1", "REVOLUT_ACCOUNT_2", "2024-01-03", 12.5),
+                txn("B2", "REVOLUT_ACCOUNT_2", "2024-01-04", 40.0),
+                txn("C1", "SEB_CHECKING", "2024-01-03", 12.5),
+                txn("C2", "SEB_CHECKING", "2024-01-04", 40.0)
+            ]
+        })
+    }
+
+    #[test]
+    fn find_likely_duplicate_accounts_flags_same_institution_overlap() {
+        let db = duplicate_db();
+        let before = db.clone();
+
+        let pairs = find_likely_duplicate_accounts(&db);
+
+        // SEB shares the fingerprints too, but is a different institution.
+        assert_eq!(
+            pairs,
+            vec![(
+                "REVOLUT_ACCOUNT_2".to_string(),
+                "REVOLUT_CURRENT".to_string()
+            )]
+        );
+        assert_eq!(db, before);
+    }
+
+    #[test]
+    fn find_likely_duplicate_accounts_ignores_unrelated_accounts() {
+        let mut db = duplicate_db();
+        db["transactions"][3]["date"] = json!("2024-02-03");
+        db["transactions"][4]["amount"] = json!(41.0);
+
+        assert!(find_likely_duplicate_accounts(&db).is_empty());
+        assert!(find_likely_duplicate_accounts(&json!({})).is_empty());
+    }
 }
@@ -111,6 +111,7 @@ pub fn merge_balance_references_with_deduplication(
     let mut stats = MergeStats {
         added: 0,
         skipped: 0,
+        updated: 0,
         total: new_refs.len(),
     };
 
@@ -0,0 +1,250 @@
+//! Suggests categories for uncategorized transactions with the local Ollama model.
+
+use ai_client::OllamaClient;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::database::{read_database, write_database};
+
+/// A model-suggested category for one transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategorySuggestion {
+    pub txn_id: String,
+    pub category: String,
+}
+
+/// Asks the model to pick one of `categories` for every transaction whose
+/// category is missing, empty or `uncategorized`.
+///
+/// Replies that are not exactly one allowed category (case-insensitive) are
+/// treated as ambiguous and skipped. Identical descriptions are asked once.
+pub fn suggest_categories(
+    database: &Value,
+    client: &OllamaClient,
+    categories: &[String],
+) -> Result<Vec<CategorySuggestion>> {
+    if categories.is_empty() {
+        return Err(anyhow!("category list is empty"));
+    }
+
+    let txns = database
+        .get("transactions")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("database.json missing 'transactions' array"))?;
+
+    let system_prompt = format!(
+        "You classify personal finance transactions. Reply with exactly one category \
+         from this list and nothing else: {}. If none clearly fits, reply: unknown.",
+        categories.join(", ")
+    );
+
+    let mut cache: HashMap<String, Option<String>> = HashMap::new();
+    let mut out = Vec::new();
+
+    for txn in txns {
+        if !is_uncategorized(txn) {
+            continue;
+        }
+        let Some(txn_id) = txn.get("txn_id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+
+        let prompt = transaction_prompt(txn);
+        let category = match cache.get(&prompt) {
+            Some(cached) => cached.clone(),
+            None => {
+                let reply = client.chat(&system_prompt, &prompt)?;
+                let matched = match_category(&reply, categories);
+                cache.insert(prompt, matched.clone());
+                matched
+            }
+        };
+
+        if let Some(category) = category {
+            out.push(CategorySuggestion {
+                txn_id: txn_id.to_string(),
+                category,
+            });
+        }
+    }
+
+    Ok(out)
+}
+
+/// Writes each suggestion into its transaction's `category`. Returns the
+/// number of transactions changed.
+pub fn apply_category_suggestions(
+    database: &mut Value,
+    suggestions: &[CategorySuggestion],
+) -> Result<usize> {
+    let txns = database
+        .get_mut("transactions")
+        .and_then(|v| v.as_array_mut())
+        .ok_or_else(|| anyhow!("database.json missing 'transactions' array"))?;
+
+    let by_id: HashMap<&str, &str> = suggestions
+        .iter()
+        .map(|s| (s.txn_id.as_str(), s.category.as_str()))
+        .collect();
+
+    let mut updated = 0usize;
+    for txn in txns.iter_mut() {
+        let Some(obj) = txn.as_object_mut() else {
+            continue;
+        };
+        let Some(category) = obj
+            .get("txn_id")
+            .and_then(|v| v.as_str())
+            .and_then(|id| by_id.get(id))
+        else {
+            continue;
+        };
+        obj.insert("category".to_string(), Value::String(category.to_string()));
+        updated += 1;
+    }
+
+    Ok(updated)
+}
+
+/// Reads the database, suggests categories and, unless `dry_run` is set,
+/// applies them and writes the database back. Returns the suggestions.
+pub fn suggest_categories_for_database_path<P: AsRef<Path>>(
+    database_path: P,
+    client: &OllamaClient,
+    categories: &[String],
+    dry_run: bool,
+) -> Result<Vec<CategorySuggestion>> {
+    let mut database = read_database(&database_path)?;
+    let suggestions = suggest_categories(&database, client, categories)?;
+
+    if !dry_run && !suggestions.is_empty() {
+        apply_category_suggestions(&mut database, &suggestions)?;
+        write_database(&database_path, &database)?;
+    }
+
+    Ok(suggestions)
+}
+
+fn is_uncategorized(txn: &Value) -> bool {
+    match txn.get("category").and_then(|v| v.as_str()) {
+        None => true,
+        Some(c) => c.trim().is_empty() || c.trim().eq_ignore_ascii_case("uncategorized"),
+    }
+}
+
+fn transaction_prompt(txn: &Value) -> String {
+    let text = |field: &str| txn.get(field).and_then(|v| v.as_str()).unwrap_or("").trim();
+    let description = match text("description-en") {
+        "" => text("description"),
+        en => en,
+    };
+    format!(
+        "Type: {}\nDescription: {}\nAmount: {} {}",
+        text("type"),
+        description,
+        txn.get("amount").map(|v| v.to_string()).unwrap_or_default(),
+        text("currency")
+    )
+}
+
+fn match_category(reply: &str, categories: &[String]) -> Option<String> {
+    let cleaned = reply
+        .trim()
+        .trim_matches(|c: char| c == '"' || c == '\'' || c == '.' || c == '`')
+        .trim();
+    categories
+        .iter()
+        .find(|c| c.eq_ignore_ascii_case(cleaned))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_client::OllamaClientConfig;
+    use serde_json::json;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serves one canned Ollama chat reply per incoming connection, in order.
+    fn mock_ollama(replies: Vec<&'static str>) -> OllamaClient {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        std::thread::spawn(move || {
+            for reply in replies {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 8192];
+                let _ = stream.read(&mut buf);
+                let body = json!({
+                    "message": {"role": "assistant", "content": reply},
+                    "done": true
+                })
+                .to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        OllamaClient::new(OllamaClientConfig {
+            base_url: format!("http://{addr}"),
+            model: "test-model".to_string(),
+            max_retries: 0,
+            retry_base_delay_ms: 0,
+        })
+        .unwrap()
+    }
+
+    fn categories() -> Vec<String> {
+        ["Groceries", "Transport", "Salary"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn suggests_allowed_categories_and_skips_ambiguous_replies() {
+        let client = mock_ollama(vec!["groceries", "Transport.", "Food or Transport"]);
+        let mut db = json!({"transactions": [
+            {"txn_id": "T1", "type": "expense", "category": "uncategorized",
+             "description": "ICA Maxi", "amount": 12.5, "currency": "SEK"},
+            {"txn_id": "T2", "type": "expense", "category": "Rent",
+             "description": "Landlord", "amount": 900.0, "currency": "SEK"},
+            {"txn_id": "T3", "type": "expense", "category": "",
+             "description": "SL ticket", "amount": 39.0, "currency": "SEK"},
+            {"txn_id": "T4", "type": "expense",
+             "description": "Food truck", "amount": 8.0, "currency": "SEK"}
+        ]});
+
+        let suggestions = suggest_categories(&db, &client, &categories()).unwrap();
+
+        assert_eq!(
+            suggestions,
+            vec![
+                CategorySuggestion {
+                    txn_id: "T1".to_string(),
+                    category: "Groceries".to_string()
+                },
+                CategorySuggestion {
+                    txn_id: "T3".to_string(),
+                    category: "Transport".to_string()
+                },
+            ]
+        );
+
+        assert_eq!(
+            apply_category_suggestions(&mut db, &suggestions).unwrap(),
+            2
+        );
+        assert_eq!(db["transactions"][0]["category"], "Groceries");
+        assert_eq!(db["transactions"][1]["category"], "Rent");
+        assert_eq!(db["transactions"][2]["category"], "Transport");
+        assert!(db["transactions"][3].get("category").is_none());
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::{InputFormat, ParsedEntities, PipelineProfile};
 use anyhow::Result;
+use chrono::NaiveDate;
 
 /// Minimal contract for statement parser implementations.
 ///
@@ -14,6 +15,17 @@ pub trait ParserContract {
 
     fn parse_file(&mut self, input_file_path: &str) -> Result<ParsedEntities>;
 
+    /// Asks the parser to skip rows dated before `since`. The default does
+    /// nothing; `run_parser_contract_cli` drops older transactions after
+    /// parsing either way.
+    fn set_since(&mut self, _since: NaiveDate) {}
+
+    /// Returns `Some` when files can be parsed through shared access, in
+    /// which case `run_parser_contract_cli` parses them on several threads.
+    fn as_concurrent(&self) -> Option<&dyn ConcurrentParserContract> {
+        None
+    }
+
     fn finalize_entities(&mut self, entities: ParsedEntities) -> Result<ParsedEntities> {
         Ok(entities)
     }
@@ -22,3 +34,9 @@ pub trait ParserContract {
         PipelineProfile::MinimalImport
     }
 }
+
+/// Parsers that keep no state between files. `parse_file_shared` must return
+/// the same entities as `ParserContract::parse_file` for the same file.
+pub trait ConcurrentParserContract: Sync {
+    fn parse_file_shared(&self, input_file_path: &str) -> Result<ParsedEntities>;
+}
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use std::{
     fs::File,
-    io::{Read, Write},
+    io::Write,
     path::{Path, PathBuf},
 };
 
@@ -23,28 +23,14 @@ use std::{
 /// println!("Database ready at: {:?}", db_path);
 /// ```
 pub fn ensure_database_exists<P: AsRef<Path>>(database_path: P) -> Result<PathBuf> {
-    let path = database_path.as_ref();
-
-    // Resolve to database.json if a directory was provided
-    let db_path = if path.is_dir() || (!path.exists() && !path.to_string_lossy().ends_with(".json"))
-    {
-        path.join("database.json")
-    } else {
-        path.to_path_buf()
-    };
+    let db_path = resolve_database_path(database_path.as_ref());
 
     // Initialize only when the file does not exist.
     if !db_path.exists() {
         initialize_from_template(&db_path)?;
     } else {
         // If the file exists, validate JSON and fail loudly instead of silently resetting.
-        let mut file = File::open(&db_path)
-            .with_context(|| format!("Cannot open existing database at {:?}", db_path))?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)
-            .with_context(|| format!("Cannot read existing database at {:?}", db_path))?;
-
-        serde_json::from_str::<serde_json::Value>(&contents).with_context(|| {
+        read_json_file(&db_path).with_context(|| {
             format!(
                 "Existing database at {:?} is invalid JSON; refusing to auto-reset it",
                 db_path
@@ -55,8 +41,42 @@ pub fn ensure_database_exists<P: AsRef<Path>>(database_path: P) -> Result<PathBu
     Ok(db_path)
 }
 
+/// Resolves to database.json if a directory was provided.
+fn resolve_database_path(path: &Path) -> PathBuf {
+    if path.is_dir() || (!path.exists() && !path.to_string_lossy().ends_with(".json")) {
+        path.join("database.json")
+    } else {
+        path.to_path_buf()
+    }
+}
+
 /// Initializes database.json from .database.example.json.
 fn initialize_from_template(db_path: &Path) -> Result<()> {
+    let minimal_db = empty_database_from_template(db_path)?;
+
+    // Create parent directory if it doesn't exist
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    // Write to database.json
+    let mut db_file = File::create(db_path)
+        .with_context(|| format!("Cannot create database file at {:?}", db_path))?;
+
+    let formatted = serde_json::to_string_pretty(&minimal_db)?;
+    db_file.write_all(formatted.as_bytes())?;
+
+    log::info!(
+        "✓ Initialized database.json with empty structure at {:?}",
+        db_path
+    );
+
+    Ok(())
+}
+
+/// Builds the empty database structure from .database.example.json next to
+/// `db_path`, without touching the filesystem.
+fn empty_database_from_template(db_path: &Path) -> Result<serde_json::Value> {
     let parent = db_path
         .parent()
         .ok_or_else(|| anyhow::anyhow!("Cannot determine parent directory of {:?}", db_path))?;
@@ -64,18 +84,11 @@ fn initialize_from_template(db_path: &Path) -> Result<()> {
     let template_path = parent.join(".database.example.json");
 
     // Read template to get user_profile and engine_version
-    let mut template_file = File::open(&template_path)
-        .with_context(|| format!("Cannot open template file at {:?}", template_path))?;
-
-    let mut template_contents = String::new();
-    template_file.read_to_string(&mut template_contents)?;
-
-    // Validate it's valid JSON
-    let template_value: serde_json::Value = serde_json::from_str(&template_contents)
-        .with_context(|| format!("template file at {:?} is not valid JSON", template_path))?;
+    let template_value = read_json_file(&template_path)
+        .with_context(|| format!("Cannot load template file at {:?}", template_path))?;
 
     // Create minimal database structure with empty arrays
-    let minimal_db = serde_json::json!({
+    Ok(serde_json::json!({
         "engine_version": template_value.get("engine_version").unwrap_or(&serde_json::json!("0.1")),
         "user_profile": template_value.get("user_profile").unwrap_or(&serde_json::json!({})),
         "accounts": [],
@@ -83,48 +96,152 @@ fn initialize_from_template(db_path: &Path) -> Result<()> {
         "positions": [],
         "transactions": [],
         "balance_references": [],
+        "recurring_templates": [],
         "month_end_snapshots": []
-    });
+    }))
+}
 
-    // Create parent directory if it doesn't exist
-    if let Some(parent) = db_path.parent() {
-        std::fs::create_dir_all(parent)?;
+/// Reads the database.json file and returns it as a serde_json::Value.
+/// Ensures the database exists before reading.
+pub fn read_database<P: AsRef<Path>>(database_path: P) -> Result<serde_json::Value> {
+    read_database_with_validation(database_path, false)
+}
+
+/// Reads the database.json file without creating it. A missing database is
+/// returned as the empty structure [`ensure_database_exists`] would write, so
+/// dry runs never touch the filesystem.
+pub fn read_database_no_init<P: AsRef<Path>>(database_path: P) -> Result<serde_json::Value> {
+    let db_path = resolve_database_path(database_path.as_ref());
+    if !db_path.exists() {
+        return empty_database_from_template(&db_path);
     }
 
-    // Write to database.json
-    let mut db_file = File::create(db_path)
-        .with_context(|| format!("Cannot create database file at {:?}", db_path))?;
+    read_json_file(&db_path).with_context(|| format!("Cannot load database at {:?}", db_path))
+}
 
-    let formatted = serde_json::to_string_pretty(&minimal_db)?;
-    db_file.write_all(formatted.as_bytes())?;
+/// Reads the database.json file, optionally running [`validate_database`] on
+/// the parsed value before returning it.
+pub fn read_database_with_validation<P: AsRef<Path>>(
+    database_path: P,
+    validate: bool,
+) -> Result<serde_json::Value> {
+    let db_path = ensure_database_exists(database_path)?;
 
-    println!(
-        "✓ Initialized database.json with empty structure at {:?}",
-        db_path
-    );
+    let database = read_json_file(&db_path)
+        .with_context(|| format!("Cannot load database at {:?}", db_path))?;
 
-    Ok(())
+    if validate {
+        validate_database(&database)
+            .with_context(|| format!("Database at {:?} failed validation", db_path))?;
+    }
+
+    Ok(database)
 }
 
-/// Reads the database.json file and returns it as a serde_json::Value.
-/// Ensures the database exists before reading.
-pub fn read_database<P: AsRef<Path>>(database_path: P) -> Result<serde_json::Value> {
-    let db_path = ensure_database_exists(database_path)?;
+/// Reads a JSON file, skipping a leading UTF-8 byte order mark (as written by
+/// Notepad and some spreadsheet tools). Errors say when the file is not UTF-8.
+pub(crate) fn read_json_file(path: &Path) -> Result<serde_json::Value> {
+    let bytes = std::fs::read(path).with_context(|| format!("Cannot read {:?}", path))?;
+    let text = std::str::from_utf8(&bytes).map_err(|e| {
+        anyhow::anyhow!(
+            "{:?} is not UTF-8 encoded ({}); re-save it as UTF-8",
+            path,
+            e
+        )
+    })?;
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+
+    serde_json::from_str(text).map_err(|e| {
+        anyhow::anyhow!(
+            "{:?} is not valid JSON: {} (expected UTF-8 text; a leading BOM is fine)",
+            path,
+            e
+        )
+    })
+}
 
-    let mut file =
-        File::open(&db_path).with_context(|| format!("Cannot open database at {:?}", db_path))?;
+/// Top-level keys every database.json must have as arrays.
+const REQUIRED_ARRAYS: [&str; 4] = ["accounts", "transactions", "instruments", "positions"];
 
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
+/// Fields every entry of `transactions` must carry.
+const REQUIRED_TRANSACTION_FIELDS: [&str; 4] = ["txn_id", "date", "amount", "currency"];
 
-    serde_json::from_str(&contents)
-        .with_context(|| format!("Database at {:?} is not valid JSON", db_path))
+/// Checks the structure of a database.json value.
+///
+/// Verifies that `accounts`, `transactions`, `instruments` and `positions`
+/// exist and are arrays, and that every transaction has `txn_id`, `date`,
+/// `amount` and `currency`. All problems are collected and reported
+/// together, one per line, instead of stopping at the first one.
+pub fn validate_database(database: &serde_json::Value) -> Result<()> {
+    let mut problems: Vec<String> = Vec::new();
+
+    if !database.is_object() {
+        anyhow::bail!("database.json is not a JSON object");
+    }
+
+    for key in REQUIRED_ARRAYS {
+        match database.get(key) {
+            None => problems.push(format!("missing '{}' array", key)),
+            Some(v) if !v.is_array() => problems.push(format!("'{}' is not an array", key)),
+            Some(_) => {}
+        }
+    }
+
+    if let Some(txns) = database.get("transactions").and_then(|v| v.as_array()) {
+        for (idx, txn) in txns.iter().enumerate() {
+            let label = txn
+                .get("txn_id")
+                .and_then(|v| v.as_str())
+                .map(|id| format!("transactions[{}] ({})", idx, id))
+                .unwrap_or_else(|| format!("transactions[{}]", idx));
+
+            if !txn.is_object() {
+                problems.push(format!("{} is not an object", label));
+                continue;
+            }
+
+            for field in REQUIRED_TRANSACTION_FIELDS {
+                if txn.get(field).is_none_or(|v| v.is_null()) {
+                    problems.push(format!("{} missing '{}'", label, field));
+                }
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "database.json has {} structural problem(s):\n{}",
+            problems.len(),
+            problems.join("\n")
+        ))
+    }
 }
 
 /// Writes a serde_json::Value to the database.json file.
+///
+/// The previous contents are kept as `database.json.bak`; see
+/// [`write_database_with_backup`].
 pub fn write_database<P: AsRef<Path>>(
     database_path: P,
     value: &serde_json::Value,
+) -> Result<PathBuf> {
+    write_database_with_backup(database_path, value, true)
+}
+
+/// Writes a serde_json::Value to the database.json file atomically.
+///
+/// The JSON is written to a temporary file next to the target, flushed to
+/// disk and then renamed over it, so a crash mid-write never leaves a
+/// truncated database behind. When `keep_backup` is set and the target
+/// already exists, its previous contents are copied to `<name>.bak` first.
+///
+/// Returns the final database path (never the temporary one).
+pub fn write_database_with_backup<P: AsRef<Path>>(
+    database_path: P,
+    value: &serde_json::Value,
+    keep_backup: bool,
 ) -> Result<PathBuf> {
     let path = database_path.as_ref();
 
@@ -141,11 +258,174 @@ pub fn write_database<P: AsRef<Path>>(
         std::fs::create_dir_all(parent)?;
     }
 
-    let mut file = File::create(&db_path)
-        .with_context(|| format!("Cannot create database file at {:?}", db_path))?;
-
+    let tmp_path = sibling_path(&db_path, "tmp");
     let formatted = serde_json::to_string_pretty(value)?;
-    file.write_all(formatted.as_bytes())?;
+    {
+        let mut file = File::create(&tmp_path)
+            .with_context(|| format!("Cannot create temporary database file at {:?}", tmp_path))?;
+        file.write_all(formatted.as_bytes())?;
+        file.sync_all()
+            .with_context(|| format!("Cannot flush temporary database file at {:?}", tmp_path))?;
+    }
+
+    if keep_backup && db_path.exists() {
+        let bak_path = sibling_path(&db_path, "bak");
+        std::fs::copy(&db_path, &bak_path)
+            .with_context(|| format!("Cannot back up database to {:?}", bak_path))?;
+    }
+
+    std::fs::rename(&tmp_path, &db_path)
+        .with_context(|| format!("Cannot move {:?} into place at {:?}", tmp_path, db_path))?;
 
     Ok(db_path)
 }
+
+/// `database.json` -> `database.json.<suffix>`, in the same directory.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("matapan-database-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn read_json(path: &Path) -> serde_json::Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn well_formed() -> serde_json::Value {
+        json!({
+            "accounts": [],
+            "instruments": [],
+            "positions": [],
+            "transactions": [
+                {"txn_id": "A", "date": "2026-01-02", "amount": 10.0, "currency": "EUR"}
+            ]
+        })
+    }
+
+    #[test]
+    fn validate_database_accepts_well_formed_db() {
+        assert!(validate_database(&well_formed()).is_ok());
+    }
+
+    #[test]
+    fn validate_database_reports_missing_and_non_array_sections() {
+        let mut db = well_formed();
+        db.as_object_mut().unwrap().remove("accounts");
+        db["positions"] = json!({});
+
+        let message = validate_database(&db).unwrap_err().to_string();
+        assert!(message.contains("2 structural problem(s)"));
+        assert!(message.contains("missing 'accounts' array"));
+        assert!(message.contains("'positions' is not an array"));
+    }
+
+    #[test]
+    fn validate_database_reports_incomplete_transactions() {
+        let mut db = well_formed();
+        db["transactions"] = json!([
+            {"txn_id": "A", "date": "2026-01-02", "amount": null, "currency": "EUR"},
+            {"date": "2026-01-03", "amount": 5.0},
+            "not an object"
+        ]);
+
+        let message = validate_database(&db).unwrap_err().to_string();
+        assert!(message.contains("transactions[0] (A) missing 'amount'"));
+        assert!(message.contains("transactions[1] missing 'txn_id'"));
+        assert!(message.contains("transactions[1] missing 'currency'"));
+        assert!(message.contains("transactions[2] is not an object"));
+    }
+
+    #[test]
+    fn validate_database_rejects_non_object_root() {
+        assert!(validate_database(&json!([])).is_err());
+    }
+
+    #[test]
+    fn read_database_with_validation_flags_broken_file() {
+        let dir = scratch_dir("validate");
+        let db_file = dir.join("database.json");
+        std::fs::write(&db_file, r#"{"transactions": []}"#).unwrap();
+
+        assert!(read_database(&db_file).is_ok());
+        assert!(read_database_with_validation(&db_file, true).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn readers_accept_utf8_bom() {
+        let dir = scratch_dir("bom");
+        let db_file = dir.join("database.json");
+        let mut bytes = b"\xEF\xBB\xBF".to_vec();
+        bytes.extend(serde_json::to_vec(&well_formed()).unwrap());
+        std::fs::write(&db_file, bytes).unwrap();
+
+        assert_eq!(
+            read_database_with_validation(&db_file, true).unwrap(),
+            well_formed()
+        );
+        assert_eq!(read_database_no_init(&db_file).unwrap(), well_formed());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn non_utf8_database_error_mentions_encoding() {
+        let dir = scratch_dir("latin1");
+        let db_file = dir.join("database.json");
+        // "Café" in Latin-1.
+        std::fs::write(&db_file, b"{\"note\": \"Caf\xE9\"}").unwrap();
+
+        let message = format!("{:#}", read_database_no_init(&db_file).unwrap_err());
+        assert!(message.contains("not UTF-8 encoded"), "{}", message);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_database_replaces_target_and_keeps_backup() {
+        let dir = scratch_dir("atomic");
+        let first = json!({"transactions": [{"txn_id": "A"}]});
+        let second = json!({"transactions": [{"txn_id": "A"}, {"txn_id": "B"}]});
+
+        let db_path = write_database(&dir, &first).unwrap();
+        assert_eq!(db_path, dir.join("database.json"));
+        assert!(!dir.join("database.json.bak").exists());
+
+        let db_path = write_database(&dir, &second).unwrap();
+        assert_eq!(db_path, dir.join("database.json"));
+        assert_eq!(read_json(&db_path), second);
+        assert_eq!(read_json(&dir.join("database.json.bak")), first);
+        assert!(!dir.join("database.json.tmp").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_database_with_backup_can_skip_backup() {
+        let dir = scratch_dir("no-backup");
+        let db_file = dir.join("database.json");
+
+        write_database_with_backup(&db_file, &json!({"v": 1}), false).unwrap();
+        write_database_with_backup(&db_file, &json!({"v": 2}), false).unwrap();
+
+        assert_eq!(read_json(&db_file), json!({"v": 2}));
+        assert!(!dir.join("database.json.bak").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
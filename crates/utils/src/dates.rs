@@ -0,0 +1,73 @@
+//! Date helpers shared by the spreadsheet parsers.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Converts an Excel serial (1900 date system) to a date-time, keeping the
+/// time-of-day fraction. The fraction is rounded to whole seconds, since
+/// Excel stores times as binary fractions of a day.
+pub fn excel_serial_to_naive_datetime(v: f64) -> Option<NaiveDateTime> {
+    if !v.is_finite() || v < 0.0 {
+        return None;
+    }
+
+    // 1899-12-30 rather than 12-31 absorbs Excel's phantom 1900-02-29.
+    let base = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_time(NaiveTime::MIN);
+    let seconds = (v * SECONDS_PER_DAY).round();
+    if seconds > i64::MAX as f64 {
+        return None;
+    }
+    base.checked_add_signed(Duration::seconds(seconds as i64))
+}
+
+/// The date-time text that goes into a txn_id seed. Midnight renders as the
+/// bare date, so rows without a time component keep the ids they had before
+/// times were read.
+pub fn txn_id_datetime_key(datetime: NaiveDateTime) -> String {
+    if datetime.time() == NaiveTime::MIN {
+        datetime.format("%Y-%m-%d").to_string()
+    } else {
+        datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn converts_midnight_noon_and_seconds() {
+        assert_eq!(
+            excel_serial_to_naive_datetime(45658.0),
+            Some(datetime("2025-01-01 00:00:00"))
+        );
+        assert_eq!(
+            excel_serial_to_naive_datetime(45658.5),
+            Some(datetime("2025-01-01 12:00:00"))
+        );
+        // 14:35:27 is 52527 s into the day.
+        assert_eq!(
+            excel_serial_to_naive_datetime(45658.0 + 52527.0 / 86400.0),
+            Some(datetime("2025-01-01 14:35:27"))
+        );
+        assert_eq!(excel_serial_to_naive_datetime(f64::NAN), None);
+        assert_eq!(excel_serial_to_naive_datetime(-1.0), None);
+    }
+
+    #[test]
+    fn txn_id_key_omits_midnight_time() {
+        assert_eq!(
+            txn_id_datetime_key(datetime("2025-01-01 00:00:00")),
+            "2025-01-01"
+        );
+        assert_eq!(
+            txn_id_datetime_key(datetime("2025-01-01 14:35:27")),
+            "2025-01-01 14:35:27"
+        );
+    }
+}
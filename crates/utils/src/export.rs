@@ -0,0 +1,237 @@
+//! Exports database.json transactions to CSV and ledger/hledger journals.
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::io::Write;
+
+const CSV_HEADER: [&str; 9] = [
+    "date",
+    "txn_id",
+    "type",
+    "from_account",
+    "to_account",
+    "amount",
+    "currency",
+    "category",
+    "description",
+];
+
+/// Writes one CSV row per transaction, with `from`/`to` account ids resolved
+/// to the same account names used by [`export_ledger`].
+pub fn export_transactions_csv(db: &Value, writer: impl Write) -> Result<()> {
+    let accounts = AccountNames::from_database(db);
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(CSV_HEADER)?;
+
+    for txn in transactions(db)? {
+        csv.write_record([
+            str_field(txn, "date"),
+            str_field(txn, "txn_id"),
+            str_field(txn, "type"),
+            &accounts.resolve(str_field(txn, "from_account_id"), txn),
+            &accounts.resolve(str_field(txn, "to_account_id"), txn),
+            &amount_field(txn),
+            str_field(txn, "currency"),
+            str_field(txn, "category"),
+            str_field(txn, "description"),
+        ])?;
+    }
+
+    csv.flush()?;
+    Ok(())
+}
+
+/// Writes a ledger/hledger journal. Each transaction becomes one entry with
+/// two postings: `+amount` on the `to` account and `-amount` on the `from`
+/// account, so every entry balances on its own.
+pub fn export_ledger(db: &Value, mut writer: impl Write) -> Result<()> {
+    let accounts = AccountNames::from_database(db);
+
+    for (idx, txn) in transactions(db)?.enumerate() {
+        if idx > 0 {
+            writeln!(writer)?;
+        }
+
+        let amount = amount_field(txn);
+        let currency = str_field(txn, "currency");
+        writeln!(
+            writer,
+            "{} {}",
+            str_field(txn, "date"),
+            single_line(str_field(txn, "description"))
+        )?;
+        let txn_id = str_field(txn, "txn_id");
+        if !txn_id.is_empty() {
+            writeln!(writer, "    ; txn_id: {}", txn_id)?;
+        }
+        writeln!(
+            writer,
+            "    {}  {} {}",
+            accounts.resolve(str_field(txn, "to_account_id"), txn),
+            amount,
+            currency
+        )?;
+        writeln!(
+            writer,
+            "    {}  -{} {}",
+            accounts.resolve(str_field(txn, "from_account_id"), txn),
+            amount,
+            currency
+        )?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+struct AccountNames {
+    institutions: HashMap<String, String>,
+}
+
+impl AccountNames {
+    fn from_database(db: &Value) -> Self {
+        let institutions = db
+            .get("accounts")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|acc| {
+                let id = acc.get("account_id")?.as_str()?;
+                let institution = acc.get("institution")?.as_str()?;
+                Some((id.to_string(), institution.to_string()))
+            })
+            .collect();
+        Self { institutions }
+    }
+
+    /// System accounts map to `Expenses:`/`Income:` by category; everything
+    /// else is an asset account under its institution.
+    fn resolve(&self, account_id: &str, txn: &Map<String, Value>) -> String {
+        let category = match str_field(txn, "category") {
+            "" => "uncategorized",
+            c => c,
+        };
+        match account_id {
+            "EXTERNAL_PAYEE" => format!("Expenses:{}", account_segment(category)),
+            "EXTERNAL_PAYER" => format!("Income:{}", account_segment(category)),
+            id => match self.institutions.get(id) {
+                Some(inst) if !inst.trim().is_empty() => {
+                    format!("Assets:{}:{}", account_segment(inst), account_segment(id))
+                }
+                _ => format!("Assets:{}", account_segment(id)),
+            },
+        }
+    }
+}
+
+fn transactions(db: &Value) -> Result<impl Iterator<Item = &Map<String, Value>>> {
+    let arr = db
+        .get("transactions")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("database.json missing 'transactions' array"))?;
+    Ok(arr.iter().filter_map(|t| t.as_object()))
+}
+
+fn str_field<'a>(txn: &'a Map<String, Value>, field: &str) -> &'a str {
+    txn.get(field).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn amount_field(txn: &Map<String, Value>) -> String {
+    match txn.get("amount") {
+        Some(Value::Number(n)) => n.to_string(),
+        Some(Value::String(s)) => s.trim().to_string(),
+        _ => "0".to_string(),
+    }
+}
+
+/// Ledger treats two spaces as the account/amount separator and `:` as the
+/// hierarchy separator, so both are squeezed out of a single name segment.
+fn account_segment(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(':', "-")
+}
+
+fn single_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fixture() -> Value {
+        json!({
+            "accounts": [
+                {"account_id": "EXTERNAL_PAYEE", "institution": "External"},
+                {"account_id": "SEB_CURRENT", "institution": "SEB"},
+                {"account_id": "REVOLUT_EUR", "institution": "Revolut"}
+            ],
+            "transactions": [
+                {
+                    "date": "2024-01-05",
+                    "from_account_id": "SEB_CURRENT",
+                    "to_account_id": "EXTERNAL_PAYEE",
+                    "type": "expense",
+                    "category": "groceries",
+                    "amount": 12.5,
+                    "currency": "SEK",
+                    "description": "ICA Maxi, Lund",
+                    "txn_id": "SEB-1"
+                },
+                {
+                    "date": "2024-01-06",
+                    "from_account_id": "EXTERNAL_PAYER",
+                    "to_account_id": "REVOLUT_EUR",
+                    "type": "income",
+                    "category": "salary",
+                    "amount": 2000,
+                    "currency": "EUR",
+                    "description": "Payroll",
+                    "txn_id": "REV-1"
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn csv_export_resolves_account_names() {
+        let mut out = Vec::new();
+        export_transactions_csv(&fixture(), &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "date,txn_id,type,from_account,to_account,amount,currency,category,description\n\
+             2024-01-05,SEB-1,expense,Assets:SEB:SEB_CURRENT,Expenses:groceries,12.5,SEK,groceries,\"ICA Maxi, Lund\"\n\
+             2024-01-06,REV-1,income,Income:salary,Assets:Revolut:REVOLUT_EUR,2000,EUR,salary,Payroll\n"
+        );
+    }
+
+    #[test]
+    fn ledger_export_emits_balanced_postings() {
+        let mut out = Vec::new();
+        export_ledger(&fixture(), &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "2024-01-05 ICA Maxi, Lund\n\
+             \x20   ; txn_id: SEB-1\n\
+             \x20   Expenses:groceries  12.5 SEK\n\
+             \x20   Assets:SEB:SEB_CURRENT  -12.5 SEK\n\
+             \n\
+             2024-01-06 Payroll\n\
+             \x20   ; txn_id: REV-1\n\
+             \x20   Assets:Revolut:REVOLUT_EUR  2000 EUR\n\
+             \x20   Income:salary  -2000 EUR\n"
+        );
+    }
+
+    #[test]
+    fn export_requires_transactions_array() {
+        let mut out = Vec::new();
+        assert!(export_ledger(&json!({"accounts": []}), &mut out).is_err());
+    }
+}
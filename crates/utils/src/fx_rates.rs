@@ -112,6 +112,70 @@ pub fn lookup_rate(
         .map(|e| e.rate)
 }
 
+/// Source of monthly rates for currencies missing from the local cache.
+///
+/// Returned maps use the same convention as [`FxRateEntry::rate`]: units of
+/// each currency per one unit of `base_currency`. Implementations may return
+/// only a subset of the requested currencies.
+pub trait FxRateProvider {
+    fn monthly_rates(
+        &self,
+        base_currency: &str,
+        month: &str,
+        currencies: &[String],
+    ) -> Result<HashMap<String, f64>>;
+}
+
+/// Builds the `currency -> rate` map for one month, reading `cached` first.
+///
+/// Only currencies absent from the cache are requested from `provider`, so
+/// without a provider no network access happens. The base currency always
+/// maps to `1.0`. Errors when any requested currency is still missing.
+pub fn monthly_fx_rates(
+    cached: &[FxRateEntry],
+    base_currency: &str,
+    month: &str,
+    currencies: &[String],
+    provider: Option<&dyn FxRateProvider>,
+) -> Result<HashMap<String, f64>> {
+    let mut rates: HashMap<String, f64> = HashMap::new();
+    let mut missing: Vec<String> = Vec::new();
+
+    for currency in currencies {
+        if rates.contains_key(currency) || missing.contains(currency) {
+            continue;
+        }
+        match lookup_rate(cached, month, currency, base_currency) {
+            Some(rate) => {
+                rates.insert(currency.clone(), rate);
+            }
+            None => missing.push(currency.clone()),
+        }
+    }
+
+    if let (Some(provider), false) = (provider, missing.is_empty()) {
+        let fetched = provider.monthly_rates(base_currency, month, &missing)?;
+        missing.retain(|currency| match fetched.get(currency) {
+            Some(&rate) => {
+                rates.insert(currency.clone(), rate);
+                false
+            }
+            None => true,
+        });
+    }
+
+    if !missing.is_empty() {
+        return Err(anyhow!(
+            "No {} FX rate for {} (base {})",
+            month,
+            missing.join(", "),
+            base_currency
+        ));
+    }
+
+    Ok(rates)
+}
+
 // ---------------------------------------------------------------------------
 // API fetching (direct reqwest â€” no third-party wrapper)
 // ---------------------------------------------------------------------------
@@ -199,10 +263,10 @@ async fn fetch_rates_for_date(
 /// # Arguments
 /// * `api_key`           â€“ freecurrencyapi.com API key (set `FREECURRENCYAPI_KEY`).
 /// * `database_path`     â€“ path to the database folder (or `database.json`, the
-///                         parent directory is used as the cache location).
+///   parent directory is used as the cache location).
 /// * `base_currency`     â€“ the target/base currency code (e.g., `"EUR"`).
 /// * `currencies`        â€“ source currencies to ensure are cached
-///                         (e.g., `&["SEK", "USD", "CNY"]`).
+///   (e.g., `&["SEK", "USD", "CNY"]`).
 /// * `months`            â€“ months in `"YYYY-MM"` format that must be available.
 ///
 /// Returns the full (including previously cached) list of [`FxRateEntry`] items.
@@ -317,15 +381,15 @@ pub async fn sync_fx_rates_for_pairs(
                 if is_rate_limited(&e) {
                     // 28th was rate-limited; use only the 1st result but stop after
                     // this month to avoid wasting more quota.
-                    eprintln!(
-                        "⚠  FX warning: 28th-of-month fetch rate-limited for {}. Using 1st only.",
+                    log::warn!(
+                        "FX warning: 28th-of-month fetch rate-limited for {}. Using 1st only.",
                         month
                     );
                     rate_limited_month = Some(month.to_string());
                     r1 // still cache what we got, then break below
                 } else {
-                    eprintln!(
-                        "⚠  FX warning: 28th-of-month fetch failed for {} ({}). Falling back to 1st only.",
+                    log::warn!(
+                        "FX warning: 28th-of-month fetch failed for {} ({}). Falling back to 1st only.",
                         month, e
                     );
                     r1
@@ -333,15 +397,15 @@ pub async fn sync_fx_rates_for_pairs(
             }
             (Err(e), Ok(r28)) => {
                 if is_rate_limited(&e) {
-                    eprintln!(
-                        "⚠  FX warning: 1st-of-month fetch rate-limited for {}. Using 28th only.",
+                    log::warn!(
+                        "FX warning: 1st-of-month fetch rate-limited for {}. Using 28th only.",
                         month
                     );
                     rate_limited_month = Some(month.to_string());
                     r28
                 } else {
-                    eprintln!(
-                        "⚠  FX warning: 1st-of-month fetch failed for {} ({}). Falling back to 28th only.",
+                    log::warn!(
+                        "FX warning: 1st-of-month fetch failed for {} ({}). Falling back to 28th only.",
                         month, e
                     );
                     r28
@@ -505,6 +569,95 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    struct StubProvider(HashMap<String, f64>);
+
+    impl FxRateProvider for StubProvider {
+        fn monthly_rates(
+            &self,
+            _base_currency: &str,
+            _month: &str,
+            currencies: &[String],
+        ) -> Result<HashMap<String, f64>> {
+            Ok(currencies
+                .iter()
+                .filter_map(|c| self.0.get(c).map(|&r| (c.clone(), r)))
+                .collect())
+        }
+    }
+
+    fn fixture_cache(name: &str) -> Vec<FxRateEntry> {
+        let dir =
+            std::env::temp_dir().join(format!("matapan-fx-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("fx_rates.json"),
+            r#"[
+                {"month": "2024-03", "from_currency": "SEK", "to_currency": "EUR", "rate": 11.3},
+                {"month": "2024-03", "from_currency": "USD", "to_currency": "EUR", "rate": 1.08},
+                {"month": "2024-04", "from_currency": "SEK", "to_currency": "EUR", "rate": 11.6}
+            ]"#,
+        )
+        .unwrap();
+        let rates = load_fx_rates(&dir).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        rates
+    }
+
+    fn currencies(codes: &[&str]) -> Vec<String> {
+        codes.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn monthly_fx_rates_reads_the_cache() {
+        let rates = monthly_fx_rates(
+            &fixture_cache("reads"),
+            "EUR",
+            "2024-03",
+            &currencies(&["SEK", "USD", "EUR"]),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(rates.len(), 3);
+        assert_eq!(rates["SEK"], 11.3);
+        assert_eq!(rates["USD"], 1.08);
+        assert_eq!(rates["EUR"], 1.0);
+    }
+
+    #[test]
+    fn monthly_fx_rates_errors_on_missing_without_provider() {
+        let err = monthly_fx_rates(
+            &fixture_cache("errors"),
+            "EUR",
+            "2024-04",
+            &currencies(&["SEK", "USD"]),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("USD"));
+    }
+
+    #[test]
+    fn monthly_fx_rates_asks_provider_only_for_missing() {
+        let provider = StubProvider(HashMap::from([
+            ("USD".to_string(), 1.07),
+            ("SEK".to_string(), 99.0),
+        ]));
+
+        let rates = monthly_fx_rates(
+            &fixture_cache("provider"),
+            "EUR",
+            "2024-04",
+            &currencies(&["SEK", "USD"]),
+            Some(&provider),
+        )
+        .unwrap();
+
+        assert_eq!(rates["SEK"], 11.6);
+        assert_eq!(rates["USD"], 1.07);
+    }
+
     #[test]
     fn collect_months_and_fx_pairs_keeps_exact_required_pairs() {
         let db = json!({
@@ -92,6 +92,22 @@ pub fn lookup_hicp(entries: &[HicpEntry], month: &str, country: &str) -> f64 {
         .unwrap_or(1.0)
 }
 
+/// Restates `amount`, expressed in the money of the month whose index is
+/// `from_hicp`, in the money of the month whose index is `to_hicp`.
+///
+/// `amount * (to_hicp / from_hicp)`: moving to a later (higher) index inflates
+/// the amount, moving to an earlier one deflates it. For example 1000 EUR at
+/// HICP 100 (2015) is `deflate_amount(1000.0, 100.0, 125.0) == 1250.0` in
+/// money of a month at HICP 125.
+///
+/// Returns `amount` unchanged when either index is not positive.
+pub fn deflate_amount(amount: f64, from_hicp: f64, to_hicp: f64) -> f64 {
+    if from_hicp <= 0.0 || to_hicp <= 0.0 {
+        return amount;
+    }
+    amount * (to_hicp / from_hicp)
+}
+
 // ---------------------------------------------------------------------------
 // API fetching (Eurostat dissemination API)
 // ---------------------------------------------------------------------------
@@ -182,9 +198,9 @@ async fn fetch_hicp_country(
 ///
 /// # Arguments
 /// * `database_path` – path to the database folder (or `database.json`; the
-///                     parent directory is used as the cache location).
+///   parent directory is used as the cache location).
 /// * `countries`     – ISO 3166-1 alpha-2 country codes to ensure are cached
-///                     (e.g., `&["IT", "SE"]`).
+///   (e.g., `&["IT", "SE"]`).
 /// * `months`        – months in `"YYYY-MM"` format that must be available.
 ///
 /// Returns the full (including previously cached) list of [`HicpEntry`] items.
@@ -244,3 +260,21 @@ pub async fn sync_hicp(
 
     Ok(cached)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deflate_amount_moves_between_index_levels() {
+        assert_eq!(deflate_amount(1000.0, 100.0, 125.0), 1250.0);
+        assert_eq!(deflate_amount(1250.0, 125.0, 100.0), 1000.0);
+    }
+
+    #[test]
+    fn deflate_amount_is_identity_for_equal_or_invalid_indices() {
+        assert_eq!(deflate_amount(42.0, 118.3, 118.3), 42.0);
+        assert_eq!(deflate_amount(42.0, 0.0, 118.3), 42.0);
+        assert_eq!(deflate_amount(42.0, 118.3, -1.0), 42.0);
+    }
+}
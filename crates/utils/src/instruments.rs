@@ -26,6 +26,51 @@ pub fn build_instrument(input: &InstrumentInput) -> Value {
     })
 }
 
+/// Returns true when `s` is a well-formed ISIN: two-letter country prefix,
+/// nine alphanumeric characters and a check digit that passes the mod-10
+/// (Luhn) checksum over the letter-expanded code.
+pub fn is_valid_isin(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    if bytes.len() != 12
+        || !bytes[..2].iter().all(|b| b.is_ascii_uppercase())
+        || !bytes[2..11]
+            .iter()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        || !bytes[11].is_ascii_digit()
+    {
+        return false;
+    }
+
+    // Letters expand to two digits (A=10 .. Z=35) before the Luhn pass.
+    let mut digits: Vec<u32> = Vec::with_capacity(24);
+    for b in bytes {
+        let value = if b.is_ascii_digit() {
+            (b - b'0') as u32
+        } else {
+            (b - b'A') as u32 + 10
+        };
+        if value >= 10 {
+            digits.push(value / 10);
+        }
+        digits.push(value % 10);
+    }
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                doubled / 10 + doubled % 10
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
 /// Merges new instruments into an existing database template with duplicate detection.
 /// Instruments are considered duplicates if they have the same `instrument_id`.
 ///
@@ -60,6 +105,7 @@ pub fn merge_instruments_with_deduplication(
     let mut stats = crate::MergeStats {
         added: 0,
         skipped: 0,
+        updated: 0,
         total: new_instruments.len(),
     };
 
@@ -146,6 +192,26 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn is_valid_isin_accepts_real_isins() {
+        for isin in [
+            "US0378331005",
+            "IE00B4L5Y983",
+            "IT0005090300",
+            "DE000BAY0017",
+        ] {
+            assert!(is_valid_isin(isin), "{isin}");
+        }
+    }
+
+    #[test]
+    fn is_valid_isin_rejects_bad_checksum_and_non_isins() {
+        assert!(!is_valid_isin("US0378331006"));
+        assert!(!is_valid_isin("123456789012"));
+        assert!(!is_valid_isin("us0378331005"));
+        assert!(!is_valid_isin("037833100"));
+    }
+
     #[test]
     fn test_merge_with_no_duplicates() {
         let database = json!({
@@ -1,8 +1,11 @@
 pub mod accounts;
 pub mod balance_references;
+pub mod category_suggestion;
 pub mod contract;
 pub mod database;
+pub mod dates;
 pub mod description_enrichment;
+pub mod export;
 pub mod fx_rates;
 pub mod hicp;
 pub mod instruments;
@@ -10,7 +13,9 @@ pub mod normalized_database;
 pub mod round_digits;
 pub mod pipeline;
 pub mod positions;
+pub mod recurring;
 pub mod rules;
+pub mod status;
 pub mod transactions;
 
 /// Loads the `.env` file from the current working directory (or any parent).
@@ -34,47 +39,67 @@ pub mod rule_sets {
 
 // Re-export commonly used items
 pub use crate::accounts::{
-    build_account, create_system_accounts, find_duplicate_account_ids,
-    merge_accounts_with_deduplication, AccountInput,
+    account_balance, build_account, create_system_accounts, find_duplicate_account_ids,
+    find_likely_duplicate_accounts, merge_accounts_with_deduplication, rename_account,
+    AccountInput, RenameStats, SYSTEM_ACCOUNT_IDS,
 };
 pub use crate::balance_references::{
     build_balance_reference, compute_monthly_balances,
     merge_balance_references_with_deduplication, BalanceReferenceInput,
 };
-pub use crate::contract::ParserContract;
-pub use crate::database::{ensure_database_exists, read_database, write_database};
+pub use crate::category_suggestion::{
+    apply_category_suggestions, suggest_categories, suggest_categories_for_database_path,
+    CategorySuggestion,
+};
+pub use crate::contract::{ConcurrentParserContract, ParserContract};
+pub use logger::init_console_logging;
+pub use crate::database::{
+    ensure_database_exists, read_database, read_database_no_init, read_database_with_validation,
+    validate_database,
+    write_database, write_database_with_backup,
+};
+pub use crate::dates::{excel_serial_to_naive_datetime, txn_id_datetime_key};
 pub use crate::description_enrichment::{
     contains_non_latin_script, enrich_descriptions_to_english,
 };
+pub use crate::export::{export_ledger, export_transactions_csv};
 pub use crate::instruments::{
-    build_instrument, find_duplicate_instrument_ids, merge_instruments_with_deduplication,
-    InstrumentInput,
+    build_instrument, find_duplicate_instrument_ids, is_valid_isin,
+    merge_instruments_with_deduplication, InstrumentInput,
 };
 pub use crate::round_digits::{round_money, round_money_option};
 pub use crate::pipeline::{
-    discover_input_files, discover_input_files_in_current_dir, for_each_input_file,
-    print_pipeline_summary, run_parser_contract_cli, run_parser_pipeline,
-    run_parser_pipeline_with_policy, DedupStrategy, InputDiscovery, InputFormat, ParsedEntities,
-    PipelineOptions, PipelinePolicy, PipelineProfile, PipelineSummary, PolicyEffects,
+    discover_input_files, discover_input_files_in_current_dir, dry_run_parser_pipeline,
+    for_each_input_file, parse_input_files_concurrently, print_dry_run_summary,
+    print_pipeline_summary, run_parser_contract_cli,
+    run_parser_pipeline, run_parser_pipeline_with_policy, DedupStrategy, DryRunSummary,
+    InputDiscovery, InputFormat, ParsedEntities, PipelineOptions, PipelinePolicy, PipelineProfile,
+    PipelineSummary, PolicyEffects,
 };
 pub use crate::positions::{
     build_position, merge_positions_with_deduplication, normalize_position_pnl_fields,
-    normalize_positions_pnl_fields, split_unrealized_pnl, PositionInput,
+    normalize_positions_pnl_fields, portfolio_value_series, reconcile_positions,
+    split_unrealized_pnl, value_series, Discrepancy, PositionInput,
 };
+pub use crate::recurring::{merge_projected_transactions, project_recurring_transactions};
 pub use crate::rules::{
-    apply_rules, apply_rules_from_database_path, load_rules_from_database_path, Condition, Rule,
-    RuleSet,
+    apply_rules, apply_rules_from_database_path, diff_rules, load_rules_from_database_path,
+    Condition, Rule, RuleFieldChange, RuleSet,
 };
 pub use crate::fx_rates::{
     collect_months_and_currencies, collect_months_and_fx_pairs, load_fx_rates, lookup_rate,
-    save_fx_rates, sync_fx_rates, sync_fx_rates_for_pairs, FxRateEntry,
+    monthly_fx_rates, save_fx_rates, sync_fx_rates, sync_fx_rates_for_pairs, FxRateEntry,
+    FxRateProvider,
 };
-pub use crate::hicp::{load_hicp, lookup_hicp, save_hicp, HicpEntry};
+pub use crate::hicp::{deflate_amount, load_hicp, lookup_hicp, save_hicp, HicpEntry};
 pub use crate::normalized_database::{
     build_normalized_database, sync_normalized_database, sync_normalized_database_blocking,
 };
+pub use crate::status::{mark_pending, RowStatus, StatusFilter};
 pub use crate::transactions::{
-    build_transaction, dedup_transactions_by_date_amount_reference, find_duplicate_txn_ids,
-    merge_transactions_with_deduplication, sort_transactions_by_date, MergeStats,
-    TransactionInput,
+    build_transaction, compute_txn_id, dedup_transactions_by_date_amount_reference,
+    find_duplicate_txn_ids,
+    find_near_duplicates, link_cross_account_transfers, merge_transactions_with_deduplication,
+    merge_transactions_with_mode, sort_transactions_by_date, split_transaction, MergeMode,
+    MergeStats, TransactionInput, TransferLink,
 };
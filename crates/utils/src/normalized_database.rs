@@ -24,6 +24,7 @@ use std::{
 };
 use crate::{
     balance_references::compute_monthly_balances,
+    database::read_json_file,
     fx_rates::{collect_months_and_fx_pairs, lookup_rate, sync_fx_rates_for_pairs, FxRateEntry},
     hicp::{load_hicp, lookup_hicp, sync_hicp, HicpEntry},
     round_digits::round_money,
@@ -462,10 +463,8 @@ pub async fn sync_normalized_database(database_path: &Path, api_key: &str) -> Re
     let db_dir = db_file.parent().unwrap_or(database_path);
 
     // Load master database.
-    let contents = fs::read_to_string(&db_file)
-        .with_context(|| format!("Cannot read source database at {:?}", db_file))?;
-    let source_db: Value = serde_json::from_str(&contents)
-        .with_context(|| format!("Cannot parse database JSON at {:?}", db_file))?;
+    let source_db = read_json_file(&db_file)
+        .with_context(|| format!("Cannot load source database at {:?}", db_file))?;
 
     let base_currency = source_db
         .get("user_profile")
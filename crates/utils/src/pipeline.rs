@@ -1,14 +1,26 @@
 use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
 use serde_json::Value;
+
+use crate::rules::RuleFieldChange;
+use crate::transactions::MergeMode;
 use std::{
+    collections::HashSet,
     env, fs,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputFormat {
     Csv,
     Excel,
+    Ofx,
+    Xml,
 }
 
 impl InputFormat {
@@ -16,6 +28,8 @@ impl InputFormat {
         match self {
             InputFormat::Csv => &["csv"],
             InputFormat::Excel => &["xlsx", "xls"],
+            InputFormat::Ofx => &["ofx", "qfx"],
+            InputFormat::Xml => &["xml"],
         }
     }
 }
@@ -42,6 +56,19 @@ impl ParsedEntities {
             && self.transactions.is_empty()
     }
 
+    /// Drops transactions dated before `since` and returns how many were
+    /// removed. Transactions without a parseable `date` are kept.
+    pub fn retain_transactions_since(&mut self, since: NaiveDate) -> usize {
+        let before = self.transactions.len();
+        self.transactions.retain(|txn| {
+            txn.get("date")
+                .and_then(|v| v.as_str())
+                .and_then(|s| NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok())
+                .is_none_or(|date| date >= since)
+        });
+        before - self.transactions.len()
+    }
+
     pub fn append(&mut self, other: ParsedEntities) {
         self.accounts.extend(other.accounts);
         self.instruments.extend(other.instruments);
@@ -54,6 +81,8 @@ impl ParsedEntities {
 pub struct PipelineOptions {
     pub include_system_accounts: bool,
     pub sort_transactions_by_date: bool,
+    /// How incoming transactions with an existing `txn_id` are merged.
+    pub merge_mode: MergeMode,
 }
 
 impl Default for PipelineOptions {
@@ -61,6 +90,7 @@ impl Default for PipelineOptions {
         Self {
             include_system_accounts: true,
             sort_transactions_by_date: false,
+            merge_mode: MergeMode::SkipExisting,
         }
     }
 }
@@ -77,6 +107,7 @@ pub struct PipelineSummary {
     pub positions_added: usize,
     pub positions_skipped: usize,
     pub transactions_added: usize,
+    pub transactions_updated: usize,
     pub transactions_skipped: usize,
     pub total_accounts: usize,
     pub total_transactions: usize,
@@ -99,6 +130,7 @@ pub struct MergeStatsSummary {
     pub positions_added: usize,
     pub positions_skipped: usize,
     pub transactions_added: usize,
+    pub transactions_updated: usize,
     pub transactions_skipped: usize,
 }
 
@@ -106,6 +138,11 @@ impl MergeStatsSummary {
     pub fn has_added_transactions(&self) -> bool {
         self.transactions_added > 0
     }
+
+    /// Whether the merge added or overwrote any transaction.
+    pub fn has_changed_transactions(&self) -> bool {
+        self.transactions_added > 0 || self.transactions_updated > 0
+    }
 }
 
 pub fn discover_input_files(args: &[String], formats: &[InputFormat]) -> Result<InputDiscovery> {
@@ -164,39 +201,12 @@ where
     F: FnMut(&mut Value, &MergeStatsSummary) -> Result<()>,
 {
     let template = crate::read_database(database_path)?;
-
-    let (db_after_sys, sys_added, sys_skipped) = if options.include_system_accounts {
-        let system_accounts = crate::create_system_accounts();
-        let (db, stats) = crate::merge_accounts_with_deduplication(template, system_accounts)?;
-        (db, stats.added, stats.skipped)
-    } else {
-        (template, 0, 0)
-    };
-
-    let (db_after_accounts, acc_stats) =
-        crate::merge_accounts_with_deduplication(db_after_sys, entities.accounts)?;
-
-    let (db_after_instruments, inst_stats) =
-        crate::merge_instruments_with_deduplication(db_after_accounts, entities.instruments)?;
-
-    let (db_after_positions, pos_stats) =
-        crate::merge_positions_with_deduplication(db_after_instruments, entities.positions)?;
-
-    let (mut merged, txn_stats) =
-        crate::merge_transactions_with_deduplication(db_after_positions, entities.transactions)?;
-
-    let merge_stats = MergeStatsSummary {
+    let (mut merged, merge_stats) = merge_parsed_entities(template, entities, &options)?;
+    let MergeStatsSummary {
         system_accounts_added: sys_added,
         system_accounts_skipped: sys_skipped,
-        accounts_added: acc_stats.added,
-        accounts_skipped: acc_stats.skipped,
-        instruments_added: inst_stats.added,
-        instruments_skipped: inst_stats.skipped,
-        positions_added: pos_stats.added,
-        positions_skipped: pos_stats.skipped,
-        transactions_added: txn_stats.added,
-        transactions_skipped: txn_stats.skipped,
-    };
+        ..
+    } = merge_stats;
 
     if let Some(hook) = post_merge_hook.as_mut() {
         hook(&mut merged, &merge_stats)?;
@@ -223,6 +233,53 @@ where
 
     Ok(PipelineSummary {
         written_path,
+        system_accounts_added: sys_added,
+        system_accounts_skipped: sys_skipped,
+        accounts_added: merge_stats.accounts_added,
+        accounts_skipped: merge_stats.accounts_skipped,
+        instruments_added: merge_stats.instruments_added,
+        instruments_skipped: merge_stats.instruments_skipped,
+        positions_added: merge_stats.positions_added,
+        positions_skipped: merge_stats.positions_skipped,
+        transactions_added: merge_stats.transactions_added,
+        transactions_updated: merge_stats.transactions_updated,
+        transactions_skipped: merge_stats.transactions_skipped,
+        total_accounts,
+        total_transactions,
+    })
+}
+
+/// Merges parsed entities into `template` in pipeline order: system
+/// accounts, accounts, instruments, positions, transactions.
+fn merge_parsed_entities(
+    template: Value,
+    entities: ParsedEntities,
+    options: &PipelineOptions,
+) -> Result<(Value, MergeStatsSummary)> {
+    let (db_after_sys, sys_added, sys_skipped) = if options.include_system_accounts {
+        let system_accounts = crate::create_system_accounts();
+        let (db, stats) = crate::merge_accounts_with_deduplication(template, system_accounts)?;
+        (db, stats.added, stats.skipped)
+    } else {
+        (template, 0, 0)
+    };
+
+    let (db_after_accounts, acc_stats) =
+        crate::merge_accounts_with_deduplication(db_after_sys, entities.accounts)?;
+
+    let (db_after_instruments, inst_stats) =
+        crate::merge_instruments_with_deduplication(db_after_accounts, entities.instruments)?;
+
+    let (db_after_positions, pos_stats) =
+        crate::merge_positions_with_deduplication(db_after_instruments, entities.positions)?;
+
+    let (merged, txn_stats) = crate::merge_transactions_with_mode(
+        db_after_positions,
+        entities.transactions,
+        options.merge_mode,
+    )?;
+
+    let merge_stats = MergeStatsSummary {
         system_accounts_added: sys_added,
         system_accounts_skipped: sys_skipped,
         accounts_added: acc_stats.added,
@@ -232,45 +289,166 @@ where
         positions_added: pos_stats.added,
         positions_skipped: pos_stats.skipped,
         transactions_added: txn_stats.added,
+        transactions_updated: txn_stats.updated,
         transactions_skipped: txn_stats.skipped,
-        total_accounts,
-        total_transactions,
+    };
+
+    Ok((merged, merge_stats))
+}
+
+/// What a pipeline run would change, computed without writing anything.
+#[derive(Debug, Clone)]
+pub struct DryRunSummary {
+    pub merge_stats: MergeStatsSummary,
+    /// Post-merge effects; `description_en_updated` is always 0.
+    pub effects: PolicyEffects,
+    pub new_transactions: Vec<Value>,
+    /// Fields the rules would change after the merge, per transaction.
+    pub rule_changes: Vec<RuleFieldChange>,
+}
+
+/// Performs the same merge and post-merge steps as
+/// [`run_parser_pipeline_with_policy`] on an in-memory copy of the database,
+/// except description enrichment, and reports the would-be-added
+/// transactions and rule changes. The database is read with
+/// [`crate::read_database_no_init`] and never written.
+pub fn dry_run_parser_pipeline(
+    database_path: &str,
+    entities: ParsedEntities,
+    policy: &PipelinePolicy,
+) -> Result<DryRunSummary> {
+    let template = crate::read_database_no_init(database_path)?;
+    let existing_ids = transaction_ids(&template);
+
+    let (mut merged, merge_stats) = merge_parsed_entities(template, entities, &policy.options())?;
+
+    let mut rule_changes = Vec::new();
+    let effects = apply_policy_steps(
+        &mut merged,
+        database_path,
+        &PipelinePolicy {
+            enrich_description_en: false,
+            ..policy.clone()
+        },
+        &merge_stats,
+        Some(&mut rule_changes),
+    )?;
+
+    let new_transactions = merged
+        .get("transactions")
+        .and_then(|t| t.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|txn| {
+            txn.get("txn_id")
+                .and_then(|id| id.as_str())
+                .is_none_or(|id| !existing_ids.contains(id))
+        })
+        .cloned()
+        .collect();
+
+    Ok(DryRunSummary {
+        merge_stats,
+        effects,
+        new_transactions,
+        rule_changes,
     })
 }
 
+fn transaction_ids(db: &Value) -> HashSet<String> {
+    db.get("transactions")
+        .and_then(|t| t.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|txn| txn.get("txn_id").and_then(|id| id.as_str()))
+        .map(|id| id.to_string())
+        .collect()
+}
+
+/// With `verbose`, also prints every field the rules would change.
+pub fn print_dry_run_summary(summary: &DryRunSummary, verbose: bool) {
+    let stats = &summary.merge_stats;
+    log::info!("\n🔍 Dry run (database not written):");
+    log::info!("─────────────────────────────────────────");
+    log::info!(
+        "✓ System accounts: {} would be added, {} already exist",
+        stats.system_accounts_added,
+        stats.system_accounts_skipped
+    );
+    log::info!(
+        "✓ Accounts: {} would be added, {} already exist",
+        stats.accounts_added,
+        stats.accounts_skipped
+    );
+    log::info!(
+        "✓ Transactions: {} would be added, {} would be updated, {} duplicates",
+        stats.transactions_added,
+        stats.transactions_updated,
+        stats.transactions_skipped
+    );
+    for txn in &summary.new_transactions {
+        let field = |name: &str| txn.get(name).and_then(|v| v.as_str()).unwrap_or("");
+        log::info!(
+            "  + {} {:<9} {} {} {} ({})",
+            field("date"),
+            field("type"),
+            txn.get("amount").map(|v| v.to_string()).unwrap_or_default(),
+            field("currency"),
+            field("description"),
+            field("txn_id")
+        );
+    }
+    log::info!(
+        "✓ Rules: {} transaction(s) would change, {} field change(s)",
+        summary.effects.rules_changed,
+        summary.rule_changes.len()
+    );
+    log::info!(
+        "✓ Dedup: {} transaction(s) would be removed",
+        summary.effects.dedup_removed
+    );
+    if verbose {
+        for change in &summary.rule_changes {
+            log::info!("  ~ {}", change);
+        }
+    }
+    log::info!("─────────────────────────────────────────");
+}
+
 pub fn print_pipeline_summary(summary: &PipelineSummary, extra_lines: &[String]) {
-    println!("\n📊 Summary:");
-    println!("─────────────────────────────────────────");
-    println!(
+    log::info!("\n📊 Summary:");
+    log::info!("─────────────────────────────────────────");
+    log::info!(
         "✓ Processed {} system accounts: {} added, {} skipped (already exist)",
         summary.system_accounts_added + summary.system_accounts_skipped,
         summary.system_accounts_added,
         summary.system_accounts_skipped
     );
-    println!(
+    log::info!(
         "✓ Processed {} accounts: {} added, {} skipped (already exist)",
         summary.accounts_added + summary.accounts_skipped,
         summary.accounts_added,
         summary.accounts_skipped
     );
-    println!(
-        "✓ Processed {} transactions: {} added, {} skipped (duplicates)",
-        summary.transactions_added + summary.transactions_skipped,
+    log::info!(
+        "✓ Processed {} transactions: {} added, {} updated, {} skipped (duplicates)",
+        summary.transactions_added + summary.transactions_updated + summary.transactions_skipped,
         summary.transactions_added,
+        summary.transactions_updated,
         summary.transactions_skipped
     );
 
     for line in extra_lines {
-        println!("{line}");
+        log::info!("{line}");
     }
 
-    println!("✓ Total accounts in database: {}", summary.total_accounts);
-    println!(
+    log::info!("✓ Total accounts in database: {}", summary.total_accounts);
+    log::info!(
         "✓ Total transactions in database: {}",
         summary.total_transactions
     );
-    println!("─────────────────────────────────────────");
-    println!("✅ Database written to: {}", summary.written_path.display());
+    log::info!("─────────────────────────────────────────");
+    log::info!("✅ Database written to: {}", summary.written_path.display());
 }
 
 fn has_supported_extension(path_or_name: &str, formats: &[InputFormat]) -> bool {
@@ -329,6 +507,57 @@ where
     Ok(())
 }
 
+/// Runs `parse` on every input file using a small pool of threads and returns
+/// the results in the order of `input_files`, however the threads finish.
+pub fn parse_input_files_concurrently<T, F>(input_files: &[String], parse: F) -> Vec<Result<T>>
+where
+    T: Send,
+    F: Fn(&str) -> Result<T> + Sync,
+{
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(input_files.len());
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<T>>>> =
+        Mutex::new(input_files.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(input_file) = input_files.get(index) else {
+                    break;
+                };
+                let result = parse(input_file);
+                results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|result| result.unwrap_or_else(|| Err(anyhow!("Input file was not parsed"))))
+        .collect()
+}
+
+fn collect_file_entities(result: Result<ParsedEntities>, parsed_entities: &mut ParsedEntities) {
+    match result {
+        Ok(file_entities) => {
+            log::info!(
+                "  ✓ Found {} transactions",
+                file_entities.transactions.len()
+            );
+            parsed_entities.append(file_entities);
+        }
+        Err(e) => {
+            log::warn!("Could not parse file: {}. Continuing with next file...", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -363,6 +592,369 @@ mod tests {
         assert_eq!(policy.dedup_strategy, DedupStrategy::None);
     }
 
+    #[test]
+    fn take_since_flag_accepts_both_spellings() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        let take_since_flag = |args: &[&'static str]| take_date_flag(args, SINCE_FLAG);
+
+        let (since, rest) = take_since_flag(&["bin", "--since", "2024-03-01", "db"]).unwrap();
+        assert_eq!(since, Some(date));
+        assert_eq!(rest, vec!["bin", "db"]);
+
+        let (since, rest) = take_since_flag(&["bin", "--since=2024-03-01"]).unwrap();
+        assert_eq!(since, Some(date));
+        assert_eq!(rest, vec!["bin"]);
+
+        assert!(take_since_flag(&["bin", "--since"]).is_err());
+        assert!(take_since_flag(&["bin", "--since", "03/01/2024"]).is_err());
+
+        let (until, rest) = take_date_flag(
+            &["bin", "--project-recurring=2024-03-01"],
+            PROJECT_RECURRING_FLAG,
+        )
+        .unwrap();
+        assert_eq!(until, Some(date));
+        assert_eq!(rest, vec!["bin"]);
+    }
+
+    #[test]
+    fn retain_transactions_since_keeps_cutoff_day() {
+        let mut entities = ParsedEntities {
+            transactions: vec![
+                serde_json::json!({"txn_id": "OLD", "date": "2024-02-29"}),
+                serde_json::json!({"txn_id": "CUTOFF", "date": "2024-03-01"}),
+                serde_json::json!({"txn_id": "NEW", "date": "2024-03-15"}),
+            ],
+            ..Default::default()
+        };
+
+        let removed =
+            entities.retain_transactions_since(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+
+        assert_eq!(removed, 1);
+        let ids: Vec<&str> = entities
+            .transactions
+            .iter()
+            .map(|t| t["txn_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["CUTOFF", "NEW"]);
+    }
+
+    #[test]
+    fn dry_run_reports_new_transactions_without_writing() {
+        let dir = std::env::temp_dir().join(format!("matapan-dry-run-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("database.json");
+        let original = serde_json::json!({
+            "accounts": [],
+            "instruments": [],
+            "positions": [],
+            "transactions": [{"txn_id": "T1", "date": "2026-01-01"}]
+        })
+        .to_string();
+        fs::write(&db_path, &original).unwrap();
+
+        let entities = ParsedEntities {
+            transactions: vec![
+                serde_json::json!({"txn_id": "T1", "date": "2026-01-01"}),
+                serde_json::json!({"txn_id": "T2", "date": "2026-01-02"}),
+            ],
+            ..Default::default()
+        };
+        let summary = dry_run_parser_pipeline(
+            db_path.to_str().unwrap(),
+            entities,
+            &PipelineProfile::MinimalImport.policy(),
+        )
+        .unwrap();
+
+        assert_eq!(summary.merge_stats.transactions_added, 1);
+        assert_eq!(summary.merge_stats.transactions_skipped, 1);
+        assert_eq!(summary.new_transactions.len(), 1);
+        assert_eq!(summary.new_transactions[0]["txn_id"], "T2");
+
+        assert_eq!(fs::read_to_string(&db_path).unwrap(), original);
+        let files: Vec<_> = fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(
+            files.len(),
+            1,
+            "dry run must not create backups or temp files"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn update_existing_flag_updates_stored_transactions() {
+        let args: Vec<String> = ["ibkr_parser", UPDATE_EXISTING_FLAG, "db"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(merge_mode_from_args(&args), MergeMode::UpdateExisting);
+        assert_eq!(merge_mode_from_args(&args[..1]), MergeMode::SkipExisting);
+
+        let dir = std::env::temp_dir().join(format!("matapan-update-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("database.json");
+        let db = serde_json::json!({
+            "accounts": [],
+            "instruments": [],
+            "positions": [],
+            "transactions": [
+                {"txn_id": "T1", "date": "2026-01-01", "description": "Typo", "category": "groceries"}
+            ]
+        });
+        fs::write(&db_path, db.to_string()).unwrap();
+        fs::write(
+            dir.join("rules.json"),
+            r#"{"rules": [{"when": {"field": "description", "contains": "Corrected"}, "set": {"reviewed": true}}]}"#,
+        )
+        .unwrap();
+
+        let entities = || ParsedEntities {
+            transactions: vec![serde_json::json!({
+                "txn_id": "T1", "date": "2026-01-01", "description": "Corrected",
+                "description-en": "", "category": "uncategorized"
+            })],
+            ..Default::default()
+        };
+        let policy = |merge_mode| PipelinePolicy {
+            merge_mode,
+            ..PipelineProfile::MinimalImport.policy()
+        };
+        let db_path = db_path.to_str().unwrap();
+
+        let skipped =
+            dry_run_parser_pipeline(db_path, entities(), &policy(MergeMode::SkipExisting)).unwrap();
+        let updated =
+            dry_run_parser_pipeline(db_path, entities(), &policy(merge_mode_from_args(&args)))
+                .unwrap();
+
+        // An update-only run still runs the post-merge rules and keeps the
+        // stored category over the parser's "uncategorized".
+        let (summary, effects) = run_parser_pipeline_with_policy(
+            db_path,
+            None,
+            entities(),
+            &PipelinePolicy {
+                enrich_description_en: false,
+                merge_mode: MergeMode::UpdateExisting,
+                ..PipelineProfile::Default.policy()
+            },
+        )
+        .unwrap();
+        let stored: Value = serde_json::from_str(&fs::read_to_string(db_path).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(summary.transactions_added, 0);
+        assert_eq!(summary.transactions_updated, 1);
+        assert_eq!(effects.rules_changed, 1);
+        let txn = &stored["transactions"][0];
+        assert_eq!(txn["description"], "Corrected");
+        assert_eq!(txn["category"], "groceries");
+        assert_eq!(txn["reviewed"], true);
+
+        assert_eq!(skipped.merge_stats.transactions_skipped, 1);
+        assert_eq!(skipped.merge_stats.transactions_updated, 0);
+        assert_eq!(updated.merge_stats.transactions_skipped, 0);
+        assert_eq!(updated.merge_stats.transactions_updated, 1);
+        assert!(updated.new_transactions.is_empty());
+    }
+
+    #[test]
+    fn verbose_dry_run_prints_each_rule_change() {
+        let dir = std::env::temp_dir().join(format!("matapan-verbose-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("database.json");
+        fs::write(
+            &db_path,
+            r#"{"accounts": [], "instruments": [], "positions": [], "transactions": []}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("rules.json"),
+            r#"{"rules": [{"when": {"field": "description", "contains": "COOP"}, "set": {"category": "groceries"}}]}"#,
+        )
+        .unwrap();
+
+        let entities = ParsedEntities {
+            transactions: vec![serde_json::json!({
+                "txn_id": "T1", "date": "2026-01-01", "description": "COOP", "category": "uncategorized"
+            })],
+            ..Default::default()
+        };
+        let policy = PipelinePolicy {
+            enrich_description_en: false,
+            ..PipelineProfile::Default.policy()
+        };
+        let summary =
+            dry_run_parser_pipeline(db_path.to_str().unwrap(), entities, &policy).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let printed = |verbose| -> Vec<String> {
+            logger::capture_logs(|| print_dry_run_summary(&summary, verbose))
+                .into_iter()
+                .map(|(_, message)| message)
+                .collect()
+        };
+        let diff_line = r#"  ~ T1 category: "uncategorized" -> "groceries""#;
+
+        assert_eq!(summary.rule_changes.len(), 1);
+        assert!(printed(true).iter().any(|line| line == diff_line));
+        assert!(!printed(false).iter().any(|line| line == diff_line));
+        assert!(printed(false)
+            .iter()
+            .any(|line| line == "✓ Rules: 1 transaction(s) would change, 1 field change(s)"));
+    }
+
+    #[test]
+    fn dry_run_counts_match_a_real_run() {
+        let dir = std::env::temp_dir().join(format!("matapan-dry-vs-real-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("database.json");
+        fs::write(
+            &db_path,
+            serde_json::json!({
+                "accounts": [],
+                "instruments": [],
+                "positions": [],
+                "transactions": [
+                    {"txn_id": "T1", "date": "2026-01-01", "amount": 10.0, "reference": "R1"}
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("rules.json"),
+            r#"{"rules": [{"when": {"field": "description", "contains": "COOP"}, "set": {"category": "groceries"}}]}"#,
+        )
+        .unwrap();
+
+        let entities = || ParsedEntities {
+            transactions: vec![
+                // Same date, amount and reference as T1: removed by dedup.
+                serde_json::json!({"txn_id": "T2", "date": "2026-01-01", "amount": 10.0, "reference": "R1"}),
+                serde_json::json!({"txn_id": "T3", "date": "2026-01-02", "amount": 5.0, "description": "COOP"}),
+            ],
+            ..Default::default()
+        };
+        let policy = PipelinePolicy {
+            enrich_description_en: false,
+            ..PipelineProfile::StricterForDedup.policy()
+        };
+        let db_path = db_path.to_str().unwrap();
+
+        let dry = dry_run_parser_pipeline(db_path, entities(), &policy).unwrap();
+        let (real, effects) =
+            run_parser_pipeline_with_policy(db_path, None, entities(), &policy).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(dry.merge_stats.transactions_added, real.transactions_added);
+        assert_eq!(dry.effects.dedup_removed, 1);
+        assert_eq!(dry.effects.dedup_removed, effects.dedup_removed);
+        assert_eq!(dry.effects.rules_changed, 1);
+        assert_eq!(dry.effects.rules_changed, effects.rules_changed);
+        assert_eq!(dry.new_transactions.len() + 1, real.total_transactions);
+        assert_eq!(dry.new_transactions[0]["category"], "groceries");
+    }
+
+    #[test]
+    fn project_recurring_policy_projects_after_the_merge() {
+        let dir = std::env::temp_dir().join(format!("matapan-project-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("database.json");
+        fs::write(
+            &db_path,
+            serde_json::json!({
+                "accounts": [],
+                "instruments": [],
+                "positions": [],
+                "recurring_templates": [{
+                    "template_id": "RENT", "frequency": "monthly", "amount": 950.0,
+                    "currency": "EUR", "from_account_id": "REVOLUT_CURRENT",
+                    "to_account_id": "EXTERNAL_PAYEE", "type": "expense",
+                    "start_date": "2024-01-31"
+                }],
+                "transactions": []
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let rent = |txn_id: &str, date: &str| ParsedEntities {
+            transactions: vec![serde_json::json!({
+                "txn_id": txn_id, "date": date, "from_account_id": "REVOLUT_CURRENT",
+                "to_account_id": "EXTERNAL_PAYEE", "amount": 950.0
+            })],
+            ..Default::default()
+        };
+        let policy = PipelinePolicy {
+            project_recurring_until: NaiveDate::from_ymd_opt(2024, 4, 30),
+            ..PipelineProfile::MinimalImport.policy()
+        };
+        let db_path = db_path.to_str().unwrap();
+        let stored = || -> Vec<(String, bool)> {
+            let db: Value = serde_json::from_str(&fs::read_to_string(db_path).unwrap()).unwrap();
+            db["transactions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|t| {
+                    (
+                        t["date"].as_str().unwrap().to_string(),
+                        t["projected"] == true,
+                    )
+                })
+                .collect()
+        };
+
+        // January is imported; February through April are projected.
+        let (_, effects) =
+            run_parser_pipeline_with_policy(db_path, None, rent("REV-1", "2024-01-31"), &policy)
+                .unwrap();
+        assert_eq!(effects.projections_added, 3);
+        let after_first = stored();
+
+        // Importing March replaces its projection; nothing else is projected.
+        let (_, effects) =
+            run_parser_pipeline_with_policy(db_path, None, rent("REV-3", "2024-03-30"), &policy)
+                .unwrap();
+        let after_second = stored();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            after_first,
+            vec![
+                ("2024-01-31".to_string(), false),
+                ("2024-02-29".to_string(), true),
+                ("2024-03-31".to_string(), true),
+                ("2024-04-30".to_string(), true),
+            ]
+        );
+        assert_eq!(effects.projections_added, 0);
+        assert_eq!(
+            after_second,
+            vec![
+                ("2024-01-31".to_string(), false),
+                ("2024-02-29".to_string(), true),
+                ("2024-04-30".to_string(), true),
+                ("2024-03-30".to_string(), false),
+            ]
+        );
+
+        // Without the policy field nothing is projected.
+        assert!(PipelineProfile::Default
+            .policy()
+            .project_recurring_until
+            .is_none());
+    }
+
     #[test]
     fn parsed_entities_append_merges_all_collections() {
         let mut left = ParsedEntities {
@@ -403,6 +995,20 @@ pub struct PipelinePolicy {
     pub apply_rules: bool,
     pub enrich_description_en: bool,
     pub dedup_strategy: DedupStrategy,
+    pub merge_mode: MergeMode,
+    /// When set, recurring templates are projected through this date after
+    /// the merge (see [`crate::recurring`]).
+    pub project_recurring_until: Option<NaiveDate>,
+}
+
+impl PipelinePolicy {
+    fn options(&self) -> PipelineOptions {
+        PipelineOptions {
+            include_system_accounts: self.include_system_accounts,
+            sort_transactions_by_date: self.sort_transactions_by_date,
+            merge_mode: self.merge_mode,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -421,6 +1027,8 @@ impl PipelineProfile {
                 apply_rules: true,
                 enrich_description_en: true,
                 dedup_strategy: DedupStrategy::None,
+                merge_mode: MergeMode::SkipExisting,
+                project_recurring_until: None,
             },
             PipelineProfile::StricterForDedup => PipelinePolicy {
                 include_system_accounts: true,
@@ -428,6 +1036,8 @@ impl PipelineProfile {
                 apply_rules: true,
                 enrich_description_en: true,
                 dedup_strategy: DedupStrategy::DateAmountReference,
+                merge_mode: MergeMode::SkipExisting,
+                project_recurring_until: None,
             },
             PipelineProfile::MinimalImport => PipelinePolicy {
                 include_system_accounts: true,
@@ -435,6 +1045,8 @@ impl PipelineProfile {
                 apply_rules: false,
                 enrich_description_en: false,
                 dedup_strategy: DedupStrategy::None,
+                merge_mode: MergeMode::SkipExisting,
+                project_recurring_until: None,
             },
         }
     }
@@ -445,6 +1057,8 @@ pub struct PolicyEffects {
     pub description_en_updated: usize,
     pub rules_changed: usize,
     pub dedup_removed: usize,
+    pub projections_added: usize,
+    pub projections_removed: usize,
 }
 
 pub fn run_parser_pipeline_with_policy(
@@ -458,38 +1072,117 @@ pub fn run_parser_pipeline_with_policy(
         database_path,
         output_path,
         entities,
-        PipelineOptions {
-            include_system_accounts: policy.include_system_accounts,
-            sort_transactions_by_date: policy.sort_transactions_by_date,
-        },
+        policy.options(),
         Some(|db: &mut Value, merge_stats: &MergeStatsSummary| {
-            // Skip transaction-level post-processing when the merge did not add transactions.
-            if !merge_stats.has_added_transactions() {
-                return Ok(());
+            effects = apply_policy_steps(db, database_path, policy, merge_stats, None)?;
+            Ok(())
+        }),
+    )?;
+
+    Ok((summary, effects))
+}
+
+/// Runs the policy's post-merge steps on `db`: dedup, rules, description
+/// enrichment, then recurring projection. With `rule_changes`, rules report
+/// every changed field there instead of writing rule-applied events.
+fn apply_policy_steps(
+    db: &mut Value,
+    database_path: &str,
+    policy: &PipelinePolicy,
+    merge_stats: &MergeStatsSummary,
+    rule_changes: Option<&mut Vec<RuleFieldChange>>,
+) -> Result<PolicyEffects> {
+    let mut effects = PolicyEffects::default();
+
+    // Skip transaction-level post-processing when the merge did not add
+    // or update transactions.
+    if merge_stats.has_changed_transactions() {
+        effects.dedup_removed = match policy.dedup_strategy {
+            DedupStrategy::None => 0,
+            DedupStrategy::DateAndAmount => 0,
+            DedupStrategy::DateAmountReference => {
+                crate::dedup_transactions_by_date_amount_reference(db)?
             }
+            DedupStrategy::StrictSignature => 0,
+        };
 
-            effects.dedup_removed = match policy.dedup_strategy {
-                DedupStrategy::None => 0,
-                DedupStrategy::DateAndAmount => 0,
-                DedupStrategy::DateAmountReference => {
-                    crate::dedup_transactions_by_date_amount_reference(db)?
-                }
-                DedupStrategy::StrictSignature => 0,
+        if policy.apply_rules {
+            effects.rules_changed = match rule_changes {
+                None => crate::apply_rules_from_database_path(db, database_path)?,
+                Some(changes) => match crate::load_rules_from_database_path(database_path)? {
+                    Some(rules) => crate::rules::apply_rules_collecting(db, &rules, Some(changes))?,
+                    None => 0,
+                },
             };
+        }
 
-            if policy.apply_rules {
-                effects.rules_changed = crate::apply_rules_from_database_path(db, database_path)?;
-            }
+        if policy.enrich_description_en {
+            effects.description_en_updated = crate::enrich_descriptions_to_english(db)?;
+        }
+    }
 
-            if policy.enrich_description_en {
-                effects.description_en_updated = crate::enrich_descriptions_to_english(db)?;
-            }
+    if let Some(until) = policy.project_recurring_until {
+        (effects.projections_added, effects.projections_removed) =
+            crate::recurring::project_recurring_after_merge(db, until)?;
+    }
 
-            Ok(())
-        }),
-    )?;
+    Ok(effects)
+}
 
-    Ok((summary, effects))
+/// Parser binaries accept this flag anywhere on the command line to preview
+/// the merge without writing the database.
+pub const DRY_RUN_FLAG: &str = "--dry-run";
+
+/// Parser binaries accept this flag to overwrite stored transactions whose
+/// `txn_id` is imported again ([`MergeMode::UpdateExisting`]) instead of
+/// skipping them.
+pub const UPDATE_EXISTING_FLAG: &str = "--update-existing";
+
+/// Parser binaries accept `--since YYYY-MM-DD` (or `--since=YYYY-MM-DD`) to
+/// skip statement rows dated before the cutoff.
+pub const SINCE_FLAG: &str = "--since";
+
+/// Parser binaries accept `--project-recurring YYYY-MM-DD` (or
+/// `--project-recurring=YYYY-MM-DD`) to project recurring templates through
+/// that date after the merge.
+pub const PROJECT_RECURRING_FLAG: &str = "--project-recurring";
+
+/// With [`DRY_RUN_FLAG`], parser binaries accept this flag to print every
+/// field the rules would change (`txn_id field: old -> new`).
+pub const VERBOSE_FLAG: &str = "--verbose";
+
+fn merge_mode_from_args(args: &[String]) -> MergeMode {
+    if args.iter().any(|arg| arg == UPDATE_EXISTING_FLAG) {
+        MergeMode::UpdateExisting
+    } else {
+        MergeMode::SkipExisting
+    }
+}
+
+/// Removes a date flag such as `--since` and its value from `args`, returning
+/// the parsed date and the remaining arguments.
+fn take_date_flag<'a>(args: &[&'a str], flag: &str) -> Result<(Option<NaiveDate>, Vec<&'a str>)> {
+    let inline_prefix = format!("{}=", flag);
+    let mut date = None;
+    let mut rest = Vec::with_capacity(args.len());
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let raw = if *arg == flag {
+            *iter
+                .next()
+                .ok_or_else(|| anyhow!("{} expects a date (YYYY-MM-DD)", flag))?
+        } else if let Some(raw) = arg.strip_prefix(&inline_prefix) {
+            raw
+        } else {
+            rest.push(*arg);
+            continue;
+        };
+        date = Some(
+            NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .with_context(|| format!("Invalid {} date '{}'", flag, raw))?,
+        );
+    }
+    Ok((date, rest))
 }
 
 pub fn run_parser_contract_cli<P>(
@@ -505,57 +1198,90 @@ where
     let input_files = discover_input_files_in_current_dir(contract.supported_input_formats())?;
 
     if input_files.is_empty() {
-        eprintln!("❌ No input files found for supported parser formats!");
+        log::error!("No input files found for supported parser formats!");
         return Ok(());
     }
 
-    println!("📂 Input files:");
+    log::info!("📂 Input files:");
     for file in &input_files {
-        println!("  ✓ Found: {}", file);
+        log::info!("  ✓ Found: {}", file);
     }
 
-    let database_path = args
-        .get(1)
+    let dry_run = args.iter().any(|arg| arg == DRY_RUN_FLAG);
+    let verbose = args.iter().any(|arg| arg == VERBOSE_FLAG);
+    let merge_mode = merge_mode_from_args(args);
+    let positional: Vec<&str> = args
+        .iter()
         .map(|s| s.as_str())
-        .unwrap_or(default_database_path);
-    let output_path = args.get(2).map(|s| s.as_str());
+        .filter(|arg| ![DRY_RUN_FLAG, UPDATE_EXISTING_FLAG, VERBOSE_FLAG].contains(arg))
+        .collect();
+    let (since, positional) = take_date_flag(&positional, SINCE_FLAG)?;
+    let (project_recurring_until, positional) =
+        take_date_flag(&positional, PROJECT_RECURRING_FLAG)?;
+    if let Some(since) = since {
+        log::info!("⏩ Skipping rows dated before {}", since);
+        contract.set_since(since);
+    }
+
+    let database_path = positional.get(1).copied().unwrap_or(default_database_path);
+    let output_path = positional.get(2).copied();
 
     let mut parsed_entities = ParsedEntities::default();
 
-    for_each_input_file(&input_files, |input_file_path| {
-        println!(
-            "\n📖 Parsing {} ({})",
-            input_file_path,
+    if let Some(concurrent) = contract.as_concurrent() {
+        log::info!(
+            "\n📖 Parsing {} file(s) in parallel ({})",
+            input_files.len(),
             contract.parser_name()
         );
-
-        match contract.parse_file(input_file_path) {
-            Ok(file_entities) => {
-                println!(
-                    "  ✓ Found {} transactions",
-                    file_entities.transactions.len()
-                );
-                parsed_entities.append(file_entities);
-            }
-            Err(e) => {
-                eprintln!("  ⚠ Warning: Could not parse file: {}", e);
-                eprintln!("    Continuing with next file...");
-            }
+        // Each file's log output is held back on its worker thread and
+        // printed under its own header, in input order.
+        let results = parse_input_files_concurrently(&input_files, |input_file_path| {
+            Ok(logger::buffer_logs(|| {
+                concurrent.parse_file_shared(input_file_path)
+            }))
+        });
+        for (input_file_path, result) in input_files.iter().zip(results) {
+            log::info!("\n📖 {}", input_file_path);
+            let (result, records) = result?;
+            logger::replay_logs(records);
+            collect_file_entities(result, &mut parsed_entities);
         }
+    } else {
+        for_each_input_file(&input_files, |input_file_path| {
+            log::info!(
+                "\n📖 Parsing {} ({})",
+                input_file_path,
+                contract.parser_name()
+            );
+            collect_file_entities(contract.parse_file(input_file_path), &mut parsed_entities);
+            Ok(())
+        })?;
+    }
 
-        Ok(())
-    })?;
+    if let Some(since) = since {
+        parsed_entities.retain_transactions_since(since);
+    }
 
     let parsed_entities = contract.finalize_entities(parsed_entities)?;
 
     if parsed_entities.is_empty() {
-        eprintln!("❌ No parsable entities found in any input file!");
+        log::error!("No parsable entities found in any input file!");
         return Ok(());
     }
 
-    println!("\n📖 Reading database from: {}", database_path);
+    log::info!("\n📖 Reading database from: {}", database_path);
+
+    let mut policy = contract.pipeline_profile().policy();
+    policy.merge_mode = merge_mode;
+    policy.project_recurring_until = project_recurring_until;
+
+    if dry_run {
+        let summary = dry_run_parser_pipeline(database_path, parsed_entities, &policy)?;
+        print_dry_run_summary(&summary, verbose);
+        return Ok(());
+    }
 
-    let policy = contract.pipeline_profile().policy();
     let (summary, effects) =
         run_parser_pipeline_with_policy(database_path, output_path, parsed_entities, &policy)?;
 
@@ -566,7 +1292,7 @@ where
         DedupStrategy::StrictSignature => "Strict-signature dedup removed",
     };
 
-    let extra_lines = vec![
+    let mut extra_lines = vec![
         format!(
             "✓ description-en updated: {} transaction(s)",
             effects.description_en_updated
@@ -577,17 +1303,26 @@ where
             dedup_label, effects.dedup_removed
         ),
     ];
+    if let Some(until) = policy.project_recurring_until {
+        extra_lines.push(format!(
+            "✓ Recurring projected through {}: {} added, {} fulfilled removed",
+            until, effects.projections_added, effects.projections_removed
+        ));
+    }
 
     print_pipeline_summary(&summary, &extra_lines);
 
     // Rebuild the normalised database (amounts in base currency + exchange_rate / hicp fields)
     // whenever the API key is available. Silently skip when the key is not set.
     if let Ok(api_key) = std::env::var("FREECURRENCYAPI_KEY") {
-        println!("\n🔄 Syncing normalised database...");
+        log::info!("\n🔄 Syncing normalised database...");
         let db_dir = std::path::Path::new(database_path);
         match crate::normalized_database::sync_normalized_database_blocking(db_dir, &api_key) {
-            Ok(()) => println!("✅ database_normalized.json updated."),
-            Err(e) => eprintln!("⚠  FX sync failed (database_normalized.json not updated): {}", e),
+            Ok(()) => log::info!("✅ database_normalized.json updated."),
+            Err(e) => log::warn!(
+                "FX sync failed (database_normalized.json not updated): {}",
+                e
+            ),
         }
     }
 
@@ -1,9 +1,10 @@
 //! Builds positions and normalizes unrealized PnL fields across schemas.
 
 use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
 use logger::log_position_added;
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use crate::round_digits::{round_money, round_money_option};
 
@@ -155,7 +156,7 @@ pub fn merge_positions_with_deduplication(
         normalize_position_pnl_fields(pos);
     }
 
-    let existing: HashSet<String> = arr
+    let mut existing: HashSet<String> = arr
         .iter()
         .filter_map(|v| {
             v.get("position_id")
@@ -167,6 +168,7 @@ pub fn merge_positions_with_deduplication(
     let mut stats = crate::MergeStats {
         added: 0,
         skipped: 0,
+        updated: 0,
         total: new_positions.len(),
     };
 
@@ -176,7 +178,7 @@ pub fn merge_positions_with_deduplication(
             .and_then(|x| x.as_str())
             .ok_or_else(|| anyhow!("Position missing position_id"))?;
 
-        if existing.contains(id) {
+        if !existing.insert(id.to_string()) {
             stats.skipped += 1;
         } else {
             log_position_added(&pos);
@@ -188,11 +190,187 @@ pub fn merge_positions_with_deduplication(
     Ok((template, stats))
 }
 
+/// Largest accepted gap between reported and recomputed unrealized PnL.
+/// `cost_basis`, `market_value` and the PnL fields are each rounded to
+/// cents, so up to 1.5 cents of drift is rounding noise.
+const PNL_TOLERANCE: f64 = 0.02;
+
+/// A position whose reported unrealized PnL disagrees with
+/// `market_value - cost_basis`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discrepancy {
+    pub position_id: String,
+    pub account_id: String,
+    pub expected_pnl: f64,
+    pub reported_pnl: f64,
+}
+
+impl Discrepancy {
+    pub fn difference(&self) -> f64 {
+        self.reported_pnl - self.expected_pnl
+    }
+}
+
+/// Recomputes each position's unrealized PnL as `market_value - cost_basis`
+/// and returns those whose reported PnL (`unrealized_pnl`, or
+/// `unrealized_profit - unrealized_loss`) is off by more than a rounding
+/// tolerance. Positions missing any of the three values are skipped.
+pub fn reconcile_positions(db: &Value) -> Vec<Discrepancy> {
+    let Some(positions) = db.get("positions").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+
+    positions
+        .iter()
+        .filter_map(|pos| {
+            let market_value = pos.get("market_value").and_then(|v| v.as_f64())?;
+            let cost_basis = pos.get("cost_basis").and_then(|v| v.as_f64())?;
+            let reported_pnl = reported_unrealized_pnl(pos)?;
+            let expected_pnl = round_money(market_value - cost_basis);
+
+            if (reported_pnl - expected_pnl).abs() <= PNL_TOLERANCE {
+                return None;
+            }
+
+            let text = |field: &str| {
+                pos.get(field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            Some(Discrepancy {
+                position_id: text("position_id"),
+                account_id: text("account_id"),
+                expected_pnl,
+                reported_pnl,
+            })
+        })
+        .collect()
+}
+
+fn reported_unrealized_pnl(pos: &Value) -> Option<f64> {
+    if let Some(pnl) = pos.get("unrealized_pnl").and_then(|v| v.as_f64()) {
+        return Some(pnl);
+    }
+    let profit = pos.get("unrealized_profit").and_then(|v| v.as_f64());
+    let loss = pos.get("unrealized_loss").and_then(|v| v.as_f64());
+    if profit.is_none() && loss.is_none() {
+        return None;
+    }
+    Some(profit.unwrap_or(0.0) - loss.unwrap_or(0.0))
+}
+
+/// Market value of `instrument_id` per `as_of_date`, sorted chronologically.
+///
+/// Values held in several accounts on the same date are summed. When one
+/// account has more than one position for the instrument on a date, the one
+/// stored last wins. Positions without a parseable date or `market_value` are
+/// skipped.
+pub fn value_series(db: &Value, instrument_id: &str) -> Vec<(NaiveDate, f64)> {
+    dated_market_values(db, Some(instrument_id))
+}
+
+/// Total market value of all positions per `as_of_date`, sorted
+/// chronologically, with the same duplicate handling as [`value_series`].
+pub fn portfolio_value_series(db: &Value) -> Vec<(NaiveDate, f64)> {
+    dated_market_values(db, None)
+}
+
+fn dated_market_values(db: &Value, instrument_id: Option<&str>) -> Vec<(NaiveDate, f64)> {
+    let Some(positions) = db.get("positions").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+
+    let text = |p: &Value, key: &str| {
+        p.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    let mut latest: BTreeMap<(NaiveDate, String, String), f64> = BTreeMap::new();
+    for position in positions {
+        let instrument = text(position, "instrument_id");
+        if instrument_id.is_some_and(|wanted| wanted != instrument) {
+            continue;
+        }
+        let Some(date) = position
+            .get("as_of_date")
+            .and_then(|v| v.as_str())
+            .and_then(|s| NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok())
+        else {
+            continue;
+        };
+        let Some(market_value) = position.get("market_value").and_then(|v| v.as_f64()) else {
+            continue;
+        };
+        latest.insert(
+            (date, text(position, "account_id"), instrument),
+            market_value,
+        );
+    }
+
+    let mut series: Vec<(NaiveDate, f64)> = Vec::new();
+    for ((date, _, _), value) in latest {
+        match series.last_mut() {
+            Some((last_date, total)) if *last_date == date => *total += value,
+            _ => series.push((date, value)),
+        }
+    }
+    series
+        .into_iter()
+        .map(|(date, total)| (date, round_money(total)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn reconcile_positions_flags_only_inconsistent_pnl() {
+        let db = json!({"positions": [
+            {
+                "position_id": "IBKR-OK", "account_id": "IBKR",
+                "cost_basis": 1000.0, "market_value": 1125.5,
+                "unrealized_profit": 125.5, "unrealized_loss": 0.0
+            },
+            {
+                "position_id": "INTESA-BAD", "account_id": "INTESA",
+                "cost_basis": 500.0, "market_value": 480.0,
+                "unrealized_profit": 0.0, "unrealized_loss": 2.0
+            },
+            {
+                "position_id": "NO-COST", "account_id": "IBKR",
+                "market_value": 10.0, "unrealized_pnl": 3.0
+            }
+        ]});
+
+        let found = reconcile_positions(&db);
+
+        assert_eq!(
+            found,
+            vec![Discrepancy {
+                position_id: "INTESA-BAD".to_string(),
+                account_id: "INTESA".to_string(),
+                expected_pnl: -20.0,
+                reported_pnl: -2.0,
+            }]
+        );
+        assert_eq!(found[0].difference(), 18.0);
+    }
+
+    #[test]
+    fn reconcile_positions_reads_legacy_unrealized_pnl() {
+        let db = json!({"positions": [
+            {"position_id": "P1", "account_id": "A", "cost_basis": 100.0,
+             "market_value": 90.0, "unrealized_pnl": -10.01}
+        ]});
+
+        assert!(reconcile_positions(&db).is_empty());
+    }
+
     #[test]
     fn test_split_unrealized_pnl() {
         assert_eq!(split_unrealized_pnl(Some(10.0)), (Some(10.0), Some(0.0)));
@@ -358,4 +536,77 @@ mod tests {
             Some(2.0)
         );
     }
+
+    #[test]
+    fn test_merge_positions_with_deduplication_dedups_within_batch() {
+        let database = json!({ "positions": [] });
+        let new_positions = vec![
+            json!({"position_id": "P1", "quantity": 1.0}),
+            json!({"position_id": "P1", "quantity": 1.0}),
+        ];
+
+        let (merged, stats) = merge_positions_with_deduplication(database, new_positions).unwrap();
+        assert_eq!(stats.added, 1);
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(merged["positions"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_merge_positions_with_deduplication_requires_position_id() {
+        let database = json!({ "positions": [] });
+        let new_positions = vec![json!({"account_id": "ACC", "quantity": 1.0})];
+
+        let err = merge_positions_with_deduplication(database, new_positions).unwrap_err();
+        assert!(err.to_string().contains("position_id"));
+    }
+
+    #[test]
+    fn test_merge_positions_with_deduplication_requires_positions_array() {
+        let err = merge_positions_with_deduplication(json!({}), Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("positions"));
+    }
+
+    fn snapshot_db() -> Value {
+        let position = |date: &str, account: &str, instrument: &str, value: f64| {
+            json!({"position_id": format!("{}-{}-{}", account, instrument, date),
+                   "as_of_date": date, "account_id": account,
+                   "instrument_id": instrument, "market_value": value})
+        };
+        json!({
+            "positions": [
+                position("2024-03-31", "IBKR", "VWCE", 1200.0),
+                position("2024-01-31", "IBKR", "VWCE", 1000.0),
+                position("2024-02-29", "IBKR", "VWCE", 1100.0),
+                // Re-imported February snapshot: the later record wins.
+                position("2024-02-29", "IBKR", "VWCE", 1150.0),
+                position("2024-02-29", "IBKR", "AAPL", 300.0),
+                {"position_id": "NO-VALUE", "as_of_date": "2024-04-30",
+                 "account_id": "IBKR", "instrument_id": "VWCE", "market_value": null}
+            ]
+        })
+    }
+
+    #[test]
+    fn value_series_is_sorted_and_keeps_latest_duplicate() {
+        let series = value_series(&snapshot_db(), "VWCE");
+
+        let d = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(
+            series,
+            vec![
+                (d("2024-01-31"), 1000.0),
+                (d("2024-02-29"), 1150.0),
+                (d("2024-03-31"), 1200.0),
+            ]
+        );
+        assert!(value_series(&snapshot_db(), "MISSING").is_empty());
+    }
+
+    #[test]
+    fn portfolio_value_series_sums_instruments_per_date() {
+        let series = portfolio_value_series(&snapshot_db());
+
+        let totals: Vec<f64> = series.iter().map(|(_, total)| *total).collect();
+        assert_eq!(totals, vec![1000.0, 1450.0, 1200.0]);
+    }
 }
@@ -0,0 +1,335 @@
+//! Projected transactions from recurring templates.
+//!
+//! A **recurring template** describes a transaction that repeats on a fixed
+//! schedule (rent, salary, subscriptions) and is stored in the database's
+//! `recurring_templates` array:
+//!
+//! ```json
+//! {
+//!   "template_id": "RENT",
+//!   "description": "Monthly rent",
+//!   "frequency": "monthly",
+//!   "amount": 950.0,
+//!   "currency": "EUR",
+//!   "from_account_id": "REVOLUT_CURRENT",
+//!   "to_account_id": "EXTERNAL_PAYEE",
+//!   "type": "expense",
+//!   "category": "housing",
+//!   "start_date": "2024-01-01",
+//!   "end_date": null
+//! }
+//! ```
+//!
+//! `frequency` is one of `weekly`, `monthly` or `yearly`. Occurrence `n` falls
+//! on `start_date + n` periods; monthly dates past the end of a shorter month
+//! are clamped to its last day (a template starting on the 31st lands on
+//! Feb 28/29).
+//!
+//! Projection is opt-in: the parser pipeline runs it after the merge only
+//! when `PipelinePolicy::project_recurring_until` is set (parser binaries set
+//! it with `--project-recurring YYYY-MM-DD`). Projected transactions carry `"projected": true` and a `PROJ-` txn_id derived from
+//! the template id and date, so re-running a projection is idempotent. When
+//! the real transaction is later imported, the merge drops the projection it
+//! fulfils so the occurrence is not counted twice.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Days, Months, NaiveDate};
+use logger::log_transaction_removed;
+use serde_json::Value;
+
+use crate::transactions::{
+    build_transaction, compute_txn_id, merge_transactions_with_deduplication, TransactionInput,
+};
+
+/// Days either side of a projected date in which an imported transaction with
+/// the same accounts and amount counts as the real occurrence.
+const MATCH_WINDOW_DAYS: i64 = 3;
+
+/// Projects every occurrence of `templates` dated from `from` through `to`
+/// (inclusive) as a transaction flagged `"projected": true`.
+pub fn project_recurring_transactions(
+    templates: &[Value],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<Value>> {
+    let mut projected = Vec::new();
+
+    for template in templates {
+        let template_id = str_field(template, "template_id")?;
+        let frequency = str_field(template, "frequency")?;
+        let start = date_field(template, "start_date")?
+            .ok_or_else(|| anyhow!("recurring template {} missing 'start_date'", template_id))?;
+        let end = date_field(template, "end_date")?;
+        let amount = template
+            .get("amount")
+            .and_then(|v| v.as_f64())
+            .ok_or_else(|| anyhow!("recurring template {} missing 'amount'", template_id))?;
+
+        let last = end.map_or(to, |end| end.min(to));
+        for n in 0u32.. {
+            let date = occurrence(start, frequency, n)
+                .with_context(|| format!("recurring template {}", template_id))?;
+            if date > last {
+                break;
+            }
+            if date < from {
+                continue;
+            }
+
+            let date_str = date.format("%Y-%m-%d").to_string();
+            let mut txn = build_transaction(&TransactionInput {
+                date: date_str.clone(),
+                from_account_id: str_field(template, "from_account_id")?.to_string(),
+                to_account_id: str_field(template, "to_account_id")?.to_string(),
+                transaction_type: str_field(template, "type")?.to_string(),
+                category: optional_str(template, "category")
+                    .unwrap_or("uncategorized")
+                    .to_string(),
+                amount,
+                currency: str_field(template, "currency")?.to_string(),
+                description: optional_str(template, "description")
+                    .unwrap_or(template_id)
+                    .to_string(),
+                description_en: None,
+                txn_id: compute_txn_id("PROJ", &[template_id, &date_str]),
+            });
+            txn["projected"] = Value::Bool(true);
+            projected.push(txn);
+        }
+    }
+
+    Ok(projected)
+}
+
+/// Projects the database's `recurring_templates` over `from..=to` and merges
+/// the result into `transactions`.
+///
+/// Occurrences already covered by an imported (non-projected) transaction with
+/// the same accounts and amount within a few days are not added. Returns the
+/// number of projected transactions added.
+pub fn merge_projected_transactions(
+    database: &mut Value,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<usize> {
+    let templates = match database.get("recurring_templates") {
+        Some(Value::Array(arr)) => arr.clone(),
+        Some(Value::Null) | None => return Ok(0),
+        Some(_) => bail!("database.json 'recurring_templates' is not an array"),
+    };
+
+    let existing = database
+        .get("transactions")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("database.json missing 'transactions' array"))?;
+
+    let projected: Vec<Value> = project_recurring_transactions(&templates, from, to)?
+        .into_iter()
+        .filter(|txn| !existing.iter().any(|real| is_real_occurrence(real, txn)))
+        .collect();
+
+    let (merged, stats) = merge_transactions_with_deduplication(database.take(), projected)?;
+    *database = merged;
+    Ok(stats.added)
+}
+
+/// Pipeline step behind `PipelinePolicy::project_recurring_until`: projects
+/// the templates from the day after the latest imported transaction through
+/// `until`, then drops stored projections an imported transaction fulfils.
+/// Returns the number of projections added and removed.
+pub(crate) fn project_recurring_after_merge(
+    database: &mut Value,
+    until: NaiveDate,
+) -> Result<(usize, usize)> {
+    let imported: Vec<Value> = database
+        .get("transactions")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("database.json missing 'transactions' array"))?
+        .iter()
+        .filter(|txn| txn.get("projected").and_then(|v| v.as_bool()) != Some(true))
+        .cloned()
+        .collect();
+
+    let from = imported
+        .iter()
+        .filter_map(|txn| txn.get("date").and_then(|v| v.as_str()))
+        .filter_map(|s| NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok())
+        .max()
+        .and_then(|latest| latest.succ_opt())
+        .unwrap_or(NaiveDate::MIN);
+
+    let added = merge_projected_transactions(database, from, until)?;
+    let removed = match database.get_mut("transactions") {
+        Some(Value::Array(arr)) => drop_fulfilled_projections(arr, &imported),
+        _ => 0,
+    };
+    Ok((added, removed))
+}
+
+/// Removes the stored projected transactions that one of `incoming` is the
+/// real occurrence of. Returns the number removed.
+pub(crate) fn drop_fulfilled_projections(
+    transactions: &mut Vec<Value>,
+    incoming: &[Value],
+) -> usize {
+    let before = transactions.len();
+    transactions.retain(|stored| {
+        let fulfilled = stored.get("projected").and_then(|v| v.as_bool()) == Some(true)
+            && incoming.iter().any(|real| is_real_occurrence(real, stored));
+        if fulfilled {
+            log_transaction_removed("projection_fulfilled", stored);
+        }
+        !fulfilled
+    });
+    before - transactions.len()
+}
+
+fn occurrence(start: NaiveDate, frequency: &str, n: u32) -> Result<NaiveDate> {
+    let date = match frequency {
+        "weekly" => start.checked_add_days(Days::new(7 * u64::from(n))),
+        "monthly" => start.checked_add_months(Months::new(n)),
+        "yearly" => start.checked_add_months(Months::new(12 * n)),
+        other => bail!("unsupported frequency '{}'", other),
+    };
+    date.ok_or_else(|| anyhow!("occurrence {} is out of range", n))
+}
+
+fn is_real_occurrence(real: &Value, projected: &Value) -> bool {
+    if real.get("projected").and_then(|v| v.as_bool()) == Some(true) {
+        return false;
+    }
+    let same = |key: &str| real.get(key) == projected.get(key);
+    if !same("from_account_id") || !same("to_account_id") || !same("amount") {
+        return false;
+    }
+
+    let parse = |txn: &Value| {
+        txn.get("date")
+            .and_then(|v| v.as_str())
+            .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+    };
+    match (parse(real), parse(projected)) {
+        (Some(a), Some(b)) => (a - b).num_days().abs() <= MATCH_WINDOW_DAYS,
+        _ => false,
+    }
+}
+
+fn str_field<'a>(template: &'a Value, key: &str) -> Result<&'a str> {
+    optional_str(template, key).ok_or_else(|| anyhow!("recurring template missing '{}'", key))
+}
+
+fn optional_str<'a>(template: &'a Value, key: &str) -> Option<&'a str> {
+    template
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+}
+
+fn date_field(template: &Value, key: &str) -> Result<Option<NaiveDate>> {
+    match optional_str(template, key) {
+        Some(raw) => NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+            .map(Some)
+            .with_context(|| format!("invalid '{}' in recurring template: '{}'", key, raw)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn rent_template() -> Value {
+        json!({
+            "template_id": "RENT",
+            "description": "Monthly rent",
+            "frequency": "monthly",
+            "amount": 950.0,
+            "currency": "EUR",
+            "from_account_id": "REVOLUT_CURRENT",
+            "to_account_id": "EXTERNAL_PAYEE",
+            "type": "expense",
+            "category": "housing",
+            "start_date": "2024-01-31"
+        })
+    }
+
+    #[test]
+    fn projects_monthly_template_over_three_months() {
+        let txns = project_recurring_transactions(
+            &[rent_template()],
+            date("2024-02-01"),
+            date("2024-04-30"),
+        )
+        .unwrap();
+
+        let dates: Vec<&str> = txns.iter().map(|t| t["date"].as_str().unwrap()).collect();
+        assert_eq!(dates, vec!["2024-02-29", "2024-03-31", "2024-04-30"]);
+        for txn in &txns {
+            assert_eq!(txn["projected"], json!(true));
+            assert_eq!(txn["amount"], json!(950.0));
+            assert!(txn["txn_id"].as_str().unwrap().starts_with("PROJ-"));
+        }
+    }
+
+    #[test]
+    fn merge_skips_months_already_imported() {
+        let mut db = json!({
+            "recurring_templates": [rent_template()],
+            "transactions": [{
+                "date": "2024-03-01",
+                "from_account_id": "REVOLUT_CURRENT",
+                "to_account_id": "EXTERNAL_PAYEE",
+                "amount": 950.0,
+                "txn_id": "REV-1"
+            }]
+        });
+
+        let added =
+            merge_projected_transactions(&mut db, date("2024-02-01"), date("2024-04-30")).unwrap();
+        assert_eq!(added, 2);
+
+        // Projecting again adds nothing new.
+        let added =
+            merge_projected_transactions(&mut db, date("2024-02-01"), date("2024-04-30")).unwrap();
+        assert_eq!(added, 0);
+        assert_eq!(db["transactions"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn importing_the_real_transaction_replaces_its_projection() {
+        let mut db = json!({
+            "recurring_templates": [rent_template()],
+            "transactions": []
+        });
+        let added =
+            merge_projected_transactions(&mut db, date("2024-03-01"), date("2024-03-31")).unwrap();
+        assert_eq!(added, 1);
+
+        let real = json!({
+            "date": "2024-03-29",
+            "from_account_id": "REVOLUT_CURRENT",
+            "to_account_id": "EXTERNAL_PAYEE",
+            "amount": 950.0,
+            "txn_id": "REV-1"
+        });
+        let (merged, stats) = merge_transactions_with_deduplication(db, vec![real]).unwrap();
+
+        assert_eq!(stats.added, 1);
+        let txns = merged["transactions"].as_array().unwrap();
+        assert_eq!(txns.len(), 1);
+        assert_eq!(txns[0]["txn_id"], "REV-1");
+    }
+
+    #[test]
+    fn missing_templates_project_nothing() {
+        let mut db = json!({"transactions": []});
+        let added =
+            merge_projected_transactions(&mut db, date("2024-01-01"), date("2024-12-31")).unwrap();
+        assert_eq!(added, 0);
+    }
+}
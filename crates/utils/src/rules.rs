@@ -1,29 +1,65 @@
 use anyhow::{anyhow, Context, Result};
 use logger::log_rule_applied;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
-use std::fs::File;
-use std::io::Read;
+use chrono::NaiveDate;
+use std::cmp::Ordering;
 use std::path::Path;
 
+use crate::database::read_json_file;
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RuleSet {
     pub rules: Vec<Rule>,
 }
 
+impl RuleSet {
+    /// Compiles every `regex` pattern once, failing on the first invalid one.
+    ///
+    /// Called by [`load_rules_from_database_path`]; rule sets built by hand
+    /// still work without it, at the cost of compiling patterns per match.
+    pub fn compile(&mut self) -> Result<()> {
+        for (idx, rule) in self.rules.iter_mut().enumerate() {
+            rule.when
+                .compile()
+                .with_context(|| format!("Invalid condition in rule #{}", idx + 1))?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Rule {
     pub when: Condition,
+    /// Fields to overwrite. Dotted keys (`"meta.source"`) address nested
+    /// objects, creating them as needed.
+    #[serde(default)]
     pub set: Map<String, Value>,
+    /// Values to push onto array fields (a single value or an array of
+    /// values). Missing fields start as an empty array, a scalar field is
+    /// turned into a one-element array, and values already present are not
+    /// added again. Keys may be dotted like in `set`. Applied after `set`.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub append: Map<String, Value>,
+    /// When this rule matches, skip the remaining rules for the transaction.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stop: bool,
 }
 
+// Conditions are parsed once per rules.json; the predicate's size is irrelevant.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum Condition {
+    /// `{ "and": [...] }` or `{ "all": [...] }`: every condition must match.
     All {
+        #[serde(alias = "all")]
         and: Vec<Condition>,
     },
+    /// `{ "or": [...] }` or `{ "any": [...] }`: at least one must match.
     Any {
+        #[serde(alias = "any")]
         or: Vec<Condition>,
     },
     Predicate {
@@ -32,9 +68,50 @@ pub enum Condition {
         contains: Option<String>,
         #[serde(default)]
         equals: Option<Value>,
+        /// Matched against string fields; takes priority over `contains`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        regex: Option<String>,
+        #[serde(skip)]
+        compiled_regex: Option<Regex>,
+        /// Bounds for numeric fields (e.g. `amount`) or ISO dates (e.g.
+        /// `date`). All bounds present must hold; a value of a different
+        /// type never matches.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gt: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gte: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lt: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lte: Option<Value>,
     },
 }
 
+impl Condition {
+    fn compile(&mut self) -> Result<()> {
+        match self {
+            Condition::All { and: conds } | Condition::Any { or: conds } => {
+                for cond in conds {
+                    cond.compile()?;
+                }
+            }
+            Condition::Predicate {
+                field,
+                regex: Some(pattern),
+                compiled_regex,
+                ..
+            } => {
+                let re = Regex::new(pattern).with_context(|| {
+                    format!("Invalid regex '{}' for field '{}'", pattern, field)
+                })?;
+                *compiled_regex = Some(re);
+            }
+            Condition::Predicate { .. } => {}
+        }
+        Ok(())
+    }
+}
+
 pub fn apply_rules_from_database_path(database: &mut Value, database_path: &str) -> Result<usize> {
     let Some(rules) = load_rules_from_database_path(database_path)? else {
         return Ok(0);
@@ -44,6 +121,46 @@ pub fn apply_rules_from_database_path(database: &mut Value, database_path: &str)
 }
 
 pub fn apply_rules(database: &mut Value, rules: &RuleSet) -> Result<usize> {
+    apply_rules_collecting(database, rules, None)
+}
+
+/// One top-level field of one transaction changed by the rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleFieldChange {
+    pub txn_id: String,
+    pub field: String,
+    /// `None` when the rules added the field.
+    pub old: Option<Value>,
+    pub new: Value,
+}
+
+impl std::fmt::Display for RuleFieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let old = self
+            .old
+            .as_ref()
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "(unset)".to_string());
+        write!(f, "{} {}: {} -> {}", self.txn_id, self.field, old, self.new)
+    }
+}
+
+/// Dry run of [`apply_rules`]: returns every field the rules would change,
+/// per transaction, without touching `database`.
+pub fn diff_rules(database: &Value, rules: &RuleSet) -> Result<Vec<RuleFieldChange>> {
+    let mut scratch = database.clone();
+    let mut changes = Vec::new();
+    apply_rules_collecting(&mut scratch, rules, Some(&mut changes))?;
+    Ok(changes)
+}
+
+/// Applies `rules`. With `changes`, every changed field is collected there
+/// and no rule-applied events are written.
+pub(crate) fn apply_rules_collecting(
+    database: &mut Value,
+    rules: &RuleSet,
+    mut changes: Option<&mut Vec<RuleFieldChange>>,
+) -> Result<usize> {
     let txns = database
         .get_mut("transactions")
         .and_then(|v| v.as_array_mut())
@@ -62,17 +179,45 @@ pub fn apply_rules(database: &mut Value, rules: &RuleSet) -> Result<usize> {
             if matches_condition(obj, &rule.when) {
                 let before_rule = Value::Object(obj.clone());
                 for (k, v) in &rule.set {
-                    obj.insert(k.clone(), v.clone());
+                    *field_slot(obj, k) = v.clone();
+                }
+                for (k, v) in &rule.append {
+                    append_values(field_slot(obj, k), v);
+                }
+
+                // Dry runs report through `changes` and leave the event log alone.
+                if changes.is_none() {
+                    let rule_value = serde_json::to_value(rule).unwrap_or(Value::Null);
+                    let after_rule = Value::Object(obj.clone());
+                    log_rule_applied(&rule_value, &before_rule, &after_rule);
                 }
 
-                let rule_value = serde_json::to_value(rule).unwrap_or(Value::Null);
-                let after_rule = Value::Object(obj.clone());
-                log_rule_applied(&rule_value, &before_rule, &after_rule);
+                if rule.stop {
+                    break;
+                }
             }
         }
 
         if &before != obj {
             changed += 1;
+
+            if let Some(changes) = changes.as_deref_mut() {
+                let txn_id = obj
+                    .get("txn_id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("(no txn_id)");
+                for (field, new) in obj.iter() {
+                    let old = before.get(field);
+                    if old != Some(new) {
+                        changes.push(RuleFieldChange {
+                            txn_id: txn_id.to_string(),
+                            field: field.clone(),
+                            old: old.cloned(),
+                            new: new.clone(),
+                        });
+                    }
+                }
+            }
         }
     }
 
@@ -99,13 +244,13 @@ pub fn load_rules_from_database_path(database_path: &str) -> Result<Option<RuleS
         return Ok(None);
     }
 
-    let mut file = File::open(&rules_path)
-        .with_context(|| format!("Cannot open rules file at {}", rules_path.display()))?;
-    let mut buf = String::new();
-    file.read_to_string(&mut buf)?;
-
-    let parsed: RuleSet = serde_json::from_str(&buf)
-        .with_context(|| format!("Invalid JSON in {}", rules_path.display()))?;
+    // rules.json is hand-edited, so it goes through the BOM-tolerant reader.
+    let raw = read_json_file(&rules_path)?;
+    let mut parsed: RuleSet = serde_json::from_value(raw)
+        .with_context(|| format!("Invalid rules in {}", rules_path.display()))?;
+    parsed
+        .compile()
+        .with_context(|| format!("Invalid rule in {}", rules_path.display()))?;
 
     Ok(Some(parsed))
 }
@@ -130,17 +275,55 @@ fn matches_condition(obj: &Map<String, Value>, cond: &Condition) -> bool {
             field,
             contains,
             equals,
+            regex,
+            compiled_regex,
+            gt,
+            gte,
+            lt,
+            lte,
         } => {
             let Some(val) = obj.get(field) else {
                 return false;
             };
 
+            let bounds = [
+                (gt, &[Ordering::Greater][..]),
+                (gte, &[Ordering::Greater, Ordering::Equal][..]),
+                (lt, &[Ordering::Less][..]),
+                (lte, &[Ordering::Less, Ordering::Equal][..]),
+            ];
+            let mut has_bound = false;
+            for (bound, accepted) in bounds {
+                let Some(bound) = bound else {
+                    continue;
+                };
+                has_bound = true;
+                match compare_values(val, bound) {
+                    Some(ord) if accepted.contains(&ord) => {}
+                    _ => return false,
+                }
+            }
+
+            if equals.is_none() && regex.is_none() && contains.is_none() {
+                return has_bound;
+            }
+
             if let Some(eq) = equals {
                 if val == eq {
                     return true;
                 }
             }
 
+            if let Some(pattern) = regex.as_ref() {
+                let Some(s) = val.as_str() else {
+                    return false;
+                };
+                return match compiled_regex {
+                    Some(re) => re.is_match(s),
+                    None => Regex::new(pattern).is_ok_and(|re| re.is_match(s)),
+                };
+            }
+
             if let Some(sub) = contains.as_ref() {
                 if let Some(s) = val.as_str() {
                     return s
@@ -153,3 +336,389 @@ fn matches_condition(obj: &Map<String, Value>, cond: &Condition) -> bool {
         }
     }
 }
+
+/// Returns the value at a dotted `path`, creating intermediate objects (and
+/// replacing non-object intermediates) as needed. Missing leaves are `null`.
+fn field_slot<'a>(obj: &'a mut Map<String, Value>, path: &str) -> &'a mut Value {
+    let (parents, leaf) = match path.rsplit_once('.') {
+        Some((parents, leaf)) => (Some(parents), leaf),
+        None => (None, path),
+    };
+
+    let mut current = obj;
+    for segment in parents.into_iter().flat_map(|p| p.split('.')) {
+        let next = current
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !next.is_object() {
+            *next = Value::Object(Map::new());
+        }
+        current = next.as_object_mut().expect("just ensured an object");
+    }
+
+    current.entry(leaf.to_string()).or_insert(Value::Null)
+}
+
+fn append_values(slot: &mut Value, values: &Value) {
+    match slot {
+        Value::Array(_) => {}
+        Value::Null => *slot = Value::Array(Vec::new()),
+        _ => *slot = Value::Array(vec![slot.take()]),
+    }
+    let Value::Array(items) = slot else {
+        return;
+    };
+
+    let incoming = match values {
+        Value::Array(values) => values.clone(),
+        value => vec![value.clone()],
+    };
+    for value in incoming {
+        if !items.contains(&value) {
+            items.push(value);
+        }
+    }
+}
+
+/// Orders two JSON values of the same kind: numbers numerically, strings as
+/// ISO dates (`YYYY-MM-DD`, optionally followed by a time). Anything else,
+/// including mixed types, is not comparable.
+fn compare_values(value: &Value, bound: &Value) -> Option<Ordering> {
+    match (value, bound) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => {
+            let parse = |s: &str| NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok();
+            Some(parse(a)?.cmp(&parse(b)?))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(value: Value) -> RuleSet {
+        let mut rules: RuleSet = serde_json::from_value(value).unwrap();
+        rules.compile().unwrap();
+        rules
+    }
+
+    fn apply(rules: &RuleSet, txns: Value) -> Value {
+        let mut db = json!({ "transactions": txns });
+        apply_rules(&mut db, rules).unwrap();
+        db["transactions"].clone()
+    }
+
+    #[test]
+    fn all_group_requires_every_condition() {
+        let rules = rules(json!({"rules": [{
+            "when": {"all": [
+                {"field": "description", "contains": "uber"},
+                {"field": "amount", "lt": 50}
+            ]},
+            "set": {"category": "Transport:Taxi"}
+        }]}));
+
+        let txns = apply(
+            &rules,
+            json!([
+                {"description": "UBER *TRIP", "amount": 23.5},
+                {"description": "UBER *TRIP", "amount": 120.0},
+                {"description": "Bolt", "amount": 12.0}
+            ]),
+        );
+
+        assert_eq!(txns[0]["category"], "Transport:Taxi");
+        assert!(txns[1].get("category").is_none());
+        assert!(txns[2].get("category").is_none());
+    }
+
+    #[test]
+    fn any_group_requires_one_condition() {
+        let rules = rules(json!({"rules": [{
+            "when": {"any": [
+                {"field": "type", "equals": "expense"},
+                {"field": "type", "equals": "fee"}
+            ]},
+            "set": {"outflow": true}
+        }]}));
+
+        let txns = apply(
+            &rules,
+            json!([{"type": "expense"}, {"type": "fee"}, {"type": "income"}]),
+        );
+
+        assert_eq!(txns[0]["outflow"], true);
+        assert_eq!(txns[1]["outflow"], true);
+        assert!(txns[2].get("outflow").is_none());
+    }
+
+    #[test]
+    fn legacy_single_condition_and_and_or_keys_still_parse() {
+        let rules = rules(json!({"rules": [
+            {
+                "when": {"field": "description", "contains": "salary"},
+                "set": {"category": "Income:Salary"}
+            },
+            {
+                "when": {"and": [{"field": "currency", "equals": "USD"}]},
+                "set": {"usd": true}
+            },
+            {
+                "when": {"or": [{"field": "currency", "equals": "SEK"}]},
+                "set": {"sek": true}
+            }
+        ]}));
+
+        let txns = apply(
+            &rules,
+            json!([
+                {"description": "Monthly SALARY", "currency": "USD"},
+                {"description": "Rent", "currency": "SEK"}
+            ]),
+        );
+
+        assert_eq!(txns[0]["category"], "Income:Salary");
+        assert_eq!(txns[0]["usd"], true);
+        assert_eq!(txns[1]["sek"], true);
+        assert!(txns[1].get("category").is_none());
+    }
+
+    fn amazon_rules(stop: bool) -> RuleSet {
+        rules(json!({"rules": [
+            {
+                "when": {"and": [
+                    {"field": "description", "contains": "amazon"},
+                    {"field": "currency", "equals": "USD"}
+                ]},
+                "set": {"category": "Shopping:AmazonUSD"},
+                "stop": stop
+            },
+            {
+                "when": {"field": "description", "contains": "amazon"},
+                "set": {"category": "Shopping"}
+            }
+        ]}))
+    }
+
+    #[test]
+    fn stop_rule_prevents_later_rules_from_overwriting() {
+        let txns = apply(
+            &amazon_rules(true),
+            json!([
+                {"description": "AMAZON.COM", "currency": "USD"},
+                {"description": "Amazon.se", "currency": "SEK"}
+            ]),
+        );
+
+        assert_eq!(txns[0]["category"], "Shopping:AmazonUSD");
+        assert_eq!(txns[1]["category"], "Shopping");
+    }
+
+    #[test]
+    fn rules_cascade_by_default() {
+        let txns = apply(
+            &amazon_rules(false),
+            json!([{"description": "AMAZON.COM", "currency": "USD"}]),
+        );
+
+        assert_eq!(txns[0]["category"], "Shopping");
+    }
+
+    #[test]
+    fn append_accumulates_tags_without_duplicates() {
+        let rules = rules(json!({"rules": [
+            {
+                "when": {"field": "description", "contains": "spotify"},
+                "append": {"tags": ["subscription", "music"]}
+            },
+            {
+                "when": {"field": "currency", "equals": "SEK"},
+                "append": {"tags": "subscription"}
+            }
+        ]}));
+
+        let txns = apply(
+            &rules,
+            json!([
+                {"description": "Spotify AB", "currency": "SEK", "tags": ["music"]},
+                {"description": "Rent", "currency": "SEK", "tags": "home"}
+            ]),
+        );
+
+        assert_eq!(txns[0]["tags"], json!(["music", "subscription"]));
+        assert_eq!(txns[1]["tags"], json!(["home", "subscription"]));
+    }
+
+    #[test]
+    fn dotted_set_key_creates_nested_object() {
+        let rules = rules(json!({"rules": [{
+            "when": {"field": "currency", "equals": "SEK"},
+            "set": {"meta.review.status": "checked", "category": "Home"}
+        }]}));
+
+        let txns = apply(
+            &rules,
+            json!([{"currency": "SEK", "meta": {"source": "seb"}}]),
+        );
+
+        assert_eq!(
+            txns[0]["meta"],
+            json!({"source": "seb", "review": {"status": "checked"}})
+        );
+        assert_eq!(txns[0]["category"], "Home");
+    }
+
+    #[test]
+    fn diff_rules_reports_changed_fields_without_mutating() {
+        let rules = rules(json!({"rules": [{
+            "when": {"field": "description", "contains": "ica"},
+            "set": {"category": "Groceries"}
+        }]}));
+        let db = json!({"transactions": [
+            {"txn_id": "SEB-1", "description": "ICA Maxi", "category": "uncategorized"},
+            {"txn_id": "SEB-2", "description": "Rent", "category": "uncategorized"}
+        ]});
+
+        let changes = diff_rules(&db, &rules).unwrap();
+
+        assert_eq!(
+            changes,
+            vec![RuleFieldChange {
+                txn_id: "SEB-1".to_string(),
+                field: "category".to_string(),
+                old: Some(json!("uncategorized")),
+                new: json!("Groceries"),
+            }]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            r#"SEB-1 category: "uncategorized" -> "Groceries""#
+        );
+        assert_eq!(db["transactions"][0]["category"], "uncategorized");
+    }
+
+    #[test]
+    fn regex_condition_matches_string_fields() {
+        let rules = rules(json!({"rules": [{
+            "when": {"field": "description", "regex": "^ICA \\d+", "contains": "nomatch"},
+            "set": {"category": "Groceries"}
+        }]}));
+
+        let txns = apply(
+            &rules,
+            json!([
+                {"description": "ICA 1234 Solna"},
+                {"description": "ICA Maxi"},
+                {"description": 42}
+            ]),
+        );
+
+        assert_eq!(txns[0]["category"], "Groceries");
+        assert!(txns[1].get("category").is_none());
+        assert!(txns[2].get("category").is_none());
+    }
+
+    #[test]
+    fn amount_threshold_rule() {
+        let rules = rules(json!({"rules": [{
+            "when": {"field": "amount", "gt": 1000},
+            "set": {"category": "large"}
+        }]}));
+
+        let txns = apply(
+            &rules,
+            json!([{"amount": 1500.0}, {"amount": 1000.0}, {"amount": "2000"}]),
+        );
+
+        assert_eq!(txns[0]["category"], "large");
+        assert!(txns[1].get("category").is_none());
+        // A string amount is a type mismatch, not a match.
+        assert!(txns[2].get("category").is_none());
+    }
+
+    #[test]
+    fn date_range_rule() {
+        let rules = rules(json!({"rules": [{
+            "when": {"field": "date", "gte": "2024-01-01", "lt": "2025-01-01"},
+            "set": {"category": "fy2024"}
+        }]}));
+
+        let txns = apply(
+            &rules,
+            json!([
+                {"date": "2023-12-31"},
+                {"date": "2024-01-01"},
+                {"date": "2024-12-31"},
+                {"date": "2025-01-01"},
+                {"date": 20240601}
+            ]),
+        );
+
+        let categories: Vec<bool> = txns
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t.get("category").is_some())
+            .collect();
+        assert_eq!(categories, vec![false, true, true, false, false]);
+    }
+
+    #[test]
+    fn bounds_and_together_with_equals() {
+        let rules = rules(json!({"rules": [{
+            "when": {"field": "amount", "lte": 50, "equals": 20.0},
+            "set": {"category": "small"}
+        }]}));
+
+        let txns = apply(&rules, json!([{"amount": 20.0}, {"amount": 30.0}]));
+        assert_eq!(txns[0]["category"], "small");
+        assert!(txns[1].get("category").is_none());
+    }
+
+    #[test]
+    fn invalid_regex_fails_at_compile_time() {
+        let mut rules: RuleSet = serde_json::from_value(json!({"rules": [{
+            "when": {"and": [{"field": "description", "regex": "ICA (\\d+"}]},
+            "set": {"category": "Groceries"}
+        }]}))
+        .unwrap();
+
+        let err = rules.compile().unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid regex 'ICA (\\d+'"));
+    }
+
+    #[test]
+    fn invalid_regex_is_reported_when_loading_rules_json() {
+        let dir = std::env::temp_dir().join(format!("matapan-rules-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("rules.json"),
+            r#"{"rules": [{"when": {"field": "description", "regex": "["}, "set": {}}]}"#,
+        )
+        .unwrap();
+
+        let err = load_rules_from_database_path(dir.to_str().unwrap()).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid regex '['"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rules_json_with_utf8_bom_loads() {
+        let dir = std::env::temp_dir().join(format!("matapan-rules-bom-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut bytes = b"\xEF\xBB\xBF".to_vec();
+        bytes.extend_from_slice(
+            br#"{"rules": [{"when": {"field": "description", "contains": "ICA"}, "set": {"category": "Groceries"}}]}"#,
+        );
+        std::fs::write(dir.join("rules.json"), bytes).unwrap();
+
+        let rules = load_rules_from_database_path(dir.to_str().unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(rules.unwrap().rules.len(), 1);
+    }
+}
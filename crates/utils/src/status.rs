@@ -0,0 +1,77 @@
+//! Shared status filtering for statement rows (settled vs pending).
+
+use serde_json::Value;
+
+/// Which statement rows a parser turns into transactions, based on the
+/// row's status column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusFilter {
+    /// Only settled rows.
+    #[default]
+    CompletedOnly,
+    /// Settled rows plus pending authorizations, which are tagged
+    /// `"pending": true`.
+    IncludePending,
+    /// Every row, including declined, reverted or closed ones.
+    All,
+}
+
+/// A parser's classification of one row's status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowStatus {
+    Completed,
+    Pending,
+    /// Failed, declined, reverted, closed, or unrecognized.
+    Other,
+}
+
+impl StatusFilter {
+    pub fn keeps(self, status: RowStatus) -> bool {
+        match self {
+            StatusFilter::CompletedOnly => status == RowStatus::Completed,
+            StatusFilter::IncludePending => status != RowStatus::Other,
+            StatusFilter::All => true,
+        }
+    }
+
+    /// Maps the parsers' former `only_completed`/`only_successful` flags.
+    pub fn from_only_completed(only_completed: bool) -> Self {
+        if only_completed {
+            StatusFilter::CompletedOnly
+        } else {
+            StatusFilter::All
+        }
+    }
+}
+
+/// Tags a transaction built from a pending row with `"pending": true`.
+pub fn mark_pending(txn: &mut Value) {
+    if let Some(obj) = txn.as_object_mut() {
+        obj.insert("pending".to_string(), Value::Bool(true));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn filters_keep_expected_statuses() {
+        let statuses = [RowStatus::Completed, RowStatus::Pending, RowStatus::Other];
+        let kept = |filter: StatusFilter| -> Vec<bool> {
+            statuses.iter().map(|s| filter.keeps(*s)).collect()
+        };
+
+        assert_eq!(kept(StatusFilter::CompletedOnly), [true, false, false]);
+        assert_eq!(kept(StatusFilter::IncludePending), [true, true, false]);
+        assert_eq!(kept(StatusFilter::All), [true, true, true]);
+    }
+
+    #[test]
+    fn mark_pending_sets_flag() {
+        let mut txn = json!({"txn_id": "T1"});
+        mark_pending(&mut txn);
+        assert_eq!(txn["pending"], json!(true));
+    }
+}
@@ -1,10 +1,14 @@
 //! Builds, merges, sorts, and deduplicates normalized transaction entities.
 
 use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
 use logger::{log_transaction_added, log_transaction_removed};
 use serde_json::Value;
-use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 
+use crate::accounts::SYSTEM_ACCOUNT_IDS;
+use crate::recurring::drop_fulfilled_projections;
 use crate::round_digits::round_money;
 
 #[derive(Debug, Clone)]
@@ -59,6 +63,32 @@ pub fn build_transaction(input: &TransactionInput) -> Value {
     Value::Object(obj)
 }
 
+/// Hex characters of the SHA-256 digest kept in a [`compute_txn_id`] (128 bits).
+const TXN_ID_HASH_LEN: usize = 32;
+
+/// Builds a `{prefix}-{hash}` transaction ID shared by all parsers.
+///
+/// Each field is trimmed and has inner whitespace collapsed, so cosmetic
+/// spacing changes in a re-exported statement keep the same ID. Fields are
+/// joined with a separator that cannot occur in text cells, so `["a", "bc"]`
+/// and `["ab", "c"]` hash differently.
+pub fn compute_txn_id(prefix: &str, fields: &[&str]) -> String {
+    let canonical: Vec<String> = fields
+        .iter()
+        .map(|field| field.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update(prefix.trim().as_bytes());
+    for field in &canonical {
+        hasher.update([0x1f]);
+        hasher.update(field.as_bytes());
+    }
+    let hash = hex::encode(hasher.finalize());
+
+    format!("{}-{}", prefix.trim(), &hash[..TXN_ID_HASH_LEN])
+}
+
 /// Sort transactions in-place by `date` ascending.
 ///
 /// Sorting is stable. Transactions with missing/non-string `date` are placed at the end,
@@ -105,61 +135,235 @@ pub fn sort_transactions_by_date(database: &mut Value) -> Result<()> {
 /// println!("Added: {}, Skipped: {}", stats.added, stats.skipped);
 /// ```
 pub fn merge_transactions_with_deduplication(
+    template: Value,
+    new_txns: Vec<Value>,
+) -> Result<(Value, MergeStats)> {
+    merge_transactions_with_mode(template, new_txns, MergeMode::SkipExisting)
+}
+
+/// How [`merge_transactions_with_mode`] treats an incoming transaction whose
+/// `txn_id` already exists in the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeMode {
+    /// Keep the stored record and count the incoming one as skipped.
+    #[default]
+    SkipExisting,
+    /// Overwrite the stored record's fields with the incoming ones. Fields
+    /// only present on the stored record, and fields the parser left at their
+    /// defaults (empty `description-en`, `"uncategorized"` category), keep
+    /// their stored value.
+    UpdateExisting,
+    /// Replace the stored record with the incoming one as a whole.
+    ReplaceAll,
+}
+
+/// Like [`merge_transactions_with_deduplication`], but lets the caller choose
+/// what happens to transactions whose `txn_id` already exists.
+///
+/// Records that are overwritten in `UpdateExisting` or `ReplaceAll` mode are
+/// counted in `MergeStats::updated`. Only ids already stored before the merge
+/// are matched; new transactions are appended as usual. An incoming
+/// transaction that was split with [`split_transaction`] is always skipped so
+/// re-imports do not bring the original back; the same holds for the inflow
+/// side of a transfer linked by [`link_cross_account_transfers`]. Projected
+/// recurring transactions fulfilled by an incoming one are removed first. A
+/// stored transaction marked `renamed_from` by
+/// [`crate::accounts::rename_account`] takes over the txn_id of an incoming
+/// transaction with the same content and is then treated as existing. So does
+/// a stored transaction whose hashed id predates [`compute_txn_id`], when the
+/// incoming id has the same prefix.
+pub fn merge_transactions_with_mode(
     mut template: Value,
     new_txns: Vec<Value>,
+    mode: MergeMode,
 ) -> Result<(Value, MergeStats)> {
     let arr = template
         .get_mut("transactions")
         .and_then(|v| v.as_array_mut())
         .ok_or_else(|| anyhow!("database.json missing 'transactions' array"))?;
 
+    drop_fulfilled_projections(arr, &new_txns);
+
     // Ensure existing transactions have standard fields and correct ordering
     for txn in arr.iter_mut() {
         ensure_description_en_position(txn);
     }
 
-    // Build a set of existing transaction IDs
-    let existing_ids: HashSet<String> = arr
+    // Map existing transaction IDs to their position
+    let existing_ids: HashMap<String, usize> = arr
         .iter()
-        .filter_map(|txn| {
+        .enumerate()
+        .filter_map(|(idx, txn)| {
             txn.get("txn_id")
                 .and_then(|id| id.as_str())
-                .map(|s| s.to_string())
+                .map(|s| (s.to_string(), idx))
         })
         .collect();
+    let split_parents: HashSet<String> = arr
+        .iter()
+        .filter_map(|txn| txn.get("split_parent").and_then(|id| id.as_str()))
+        .map(str::to_string)
+        .collect();
+    let linked_inflows: HashSet<String> = arr
+        .iter()
+        .filter_map(|txn| txn.get("linked_txn_id").and_then(|id| id.as_str()))
+        .map(str::to_string)
+        .collect();
+    let mut renamed: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut legacy_ids: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    for (idx, txn) in arr.iter().enumerate() {
+        let Some(key) = content_key(txn) else {
+            continue;
+        };
+        if txn.get("renamed_from").is_some() {
+            renamed.entry(key).or_default().push(idx);
+        } else if let Some(prefix) = legacy_hash_prefix(&txn_id_of(txn)) {
+            legacy_ids
+                .entry((prefix.to_string(), key))
+                .or_default()
+                .push(idx);
+        }
+    }
 
     let mut stats = MergeStats {
         added: 0,
         skipped: 0,
+        updated: 0,
         total: new_txns.len(),
     };
 
-    // Only add transactions that don't already exist
     for mut txn in new_txns {
         let txn_id = txn
             .get("txn_id")
             .and_then(|id| id.as_str())
             .ok_or_else(|| anyhow!("Transaction missing 'txn_id' field"))?;
 
-        if existing_ids.contains(txn_id) {
+        if (split_parents.contains(txn_id) || linked_inflows.contains(txn_id))
+            && !existing_ids.contains_key(txn_id)
+        {
             stats.skipped += 1;
-        } else {
-            // Add standard fields and ordering before storing
-            ensure_description_en_position(&mut txn);
-            log_transaction_added(&txn);
-            arr.push(txn);
-            stats.added += 1;
+            continue;
+        }
+
+        let rekey_match = |key: String| {
+            if let Some(idx) = renamed.get_mut(&key).and_then(Vec::pop) {
+                return Some(idx);
+            }
+            let prefix = current_hash_prefix(txn_id)?.to_string();
+            legacy_ids.get_mut(&(prefix, key))?.pop()
+        };
+
+        let idx = match existing_ids.get(txn_id) {
+            Some(&idx) => idx,
+            None => match content_key(&txn).and_then(rekey_match) {
+                Some(idx) => {
+                    rekey_transaction(arr, idx, txn_id);
+                    idx
+                }
+                None => {
+                    // Add standard fields and ordering before storing
+                    ensure_description_en_position(&mut txn);
+                    log_transaction_added(&txn);
+                    arr.push(txn);
+                    stats.added += 1;
+                    continue;
+                }
+            },
+        };
+
+        match mode {
+            MergeMode::SkipExisting => stats.skipped += 1,
+            MergeMode::UpdateExisting => {
+                if let (Some(stored), Some(incoming)) = (arr[idx].as_object_mut(), txn.as_object())
+                {
+                    for (key, value) in incoming {
+                        if !is_parser_default(key, value) {
+                            stored.insert(key.clone(), value.clone());
+                        }
+                    }
+                }
+                ensure_description_en_position(&mut arr[idx]);
+                stats.updated += 1;
+            }
+            MergeMode::ReplaceAll => {
+                ensure_description_en_position(&mut txn);
+                arr[idx] = txn;
+                stats.updated += 1;
+            }
         }
     }
 
     Ok((template, stats))
 }
 
+/// Identifies a transaction by what the statement says about it, for matching
+/// records whose txn_id changed after an account rename or a hashing change.
+fn content_key(txn: &Value) -> Option<String> {
+    let text = |key: &str| txn.get(key).and_then(|v| v.as_str()).map(str::trim);
+    Some(format!(
+        "{}|{}|{}|{:.2}|{}|{}",
+        text("date")?,
+        text("from_account_id")?,
+        text("to_account_id")?,
+        txn.get("amount")?.as_f64()?,
+        text("currency").unwrap_or(""),
+        text("description").unwrap_or(""),
+    ))
+}
+
+/// Gives the stored transaction at `idx` the parser's new `txn_id` and
+/// repoints `linked_txn_id` references to it.
+fn rekey_transaction(arr: &mut [Value], idx: usize, txn_id: &str) {
+    let old_id = txn_id_of(&arr[idx]);
+    if let Some(obj) = arr[idx].as_object_mut() {
+        obj.remove("renamed_from");
+        obj.insert("txn_id".to_string(), Value::String(txn_id.to_string()));
+    }
+    for txn in arr.iter_mut() {
+        if txn.get("linked_txn_id").and_then(|v| v.as_str()) == Some(old_id.as_str()) {
+            txn["linked_txn_id"] = Value::String(txn_id.to_string());
+        }
+    }
+}
+
+/// Splits a [`compute_txn_id`] id into its prefix, e.g. `SEB` for
+/// `SEB-<32 hex>`.
+fn current_hash_prefix(txn_id: &str) -> Option<&str> {
+    let (prefix, hash) = txn_id.rsplit_once('-')?;
+    (hash.len() == TXN_ID_HASH_LEN && is_lower_hex(hash)).then_some(prefix)
+}
+
+/// The prefix of a hashed id built before [`compute_txn_id`], when parsers
+/// kept 24 hex characters of their own seed (`SEB-<24 hex>`).
+fn legacy_hash_prefix(txn_id: &str) -> Option<&str> {
+    let (prefix, hash) = txn_id.rsplit_once('-')?;
+    (hash.len() >= 16 && hash.len() != TXN_ID_HASH_LEN && is_lower_hex(hash)).then_some(prefix)
+}
+
+fn is_lower_hex(s: &str) -> bool {
+    s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Whether `value` is a placeholder parsers emit before enrichment, which must
+/// not overwrite a stored value in `UpdateExisting` mode.
+fn is_parser_default(key: &str, value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => {
+            let s = s.trim();
+            s.is_empty() || (key == "category" && s.eq_ignore_ascii_case("uncategorized"))
+        }
+        _ => false,
+    }
+}
+
 /// Statistics about a transaction merge operation
 #[derive(Debug, Clone)]
 pub struct MergeStats {
     pub added: usize,
     pub skipped: usize,
+    /// Existing records overwritten by the merge (always 0 in skip mode).
+    pub updated: usize,
     pub total: usize,
 }
 
@@ -247,6 +451,282 @@ fn extract_reference_for_dedup(obj: &serde_json::Map<String, Value>) -> Option<S
     None
 }
 
+/// Flags likely duplicates that share no `txn_id`, e.g. the same payment
+/// imported from two different sources.
+///
+/// A pair `(i, j)` (with `i < j`) is reported when both transactions have the
+/// same rounded `amount` and `currency`, their dates are at most
+/// `window_days` apart, and their descriptions share at least half of the
+/// tokens of the shorter one. Nothing is removed; the indices are meant for
+/// user review.
+pub fn find_near_duplicates(txns: &[Value], window_days: i64) -> Vec<(usize, usize)> {
+    let mut keyed: Vec<(usize, NaiveDate, String, String, HashSet<String>)> = txns
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, txn)| {
+            let obj = txn.as_object()?;
+            let date = NaiveDate::parse_from_str(obj.get("date")?.as_str()?.get(..10)?, "%Y-%m-%d")
+                .ok()?;
+            let amount = format!("{:.2}", round_money(obj.get("amount")?.as_f64()?.abs()));
+            let currency = obj.get("currency")?.as_str()?.trim().to_uppercase();
+            let tokens = description_tokens(
+                obj.get("description")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(""),
+            );
+            Some((idx, date, amount, currency, tokens))
+        })
+        .collect();
+
+    keyed.sort_by_key(|(idx, date, ..)| (*date, *idx));
+
+    let mut pairs = Vec::new();
+    for (pos, (i, date_i, amount_i, currency_i, tokens_i)) in keyed.iter().enumerate() {
+        for (j, date_j, amount_j, currency_j, tokens_j) in &keyed[pos + 1..] {
+            if (*date_j - *date_i).num_days() > window_days {
+                break;
+            }
+            if amount_i == amount_j
+                && currency_i == currency_j
+                && descriptions_overlap(tokens_i, tokens_j)
+            {
+                pairs.push(((*i).min(*j), (*i).max(*j)));
+            }
+        }
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+fn description_tokens(description: &str) -> HashSet<String> {
+    description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+fn descriptions_overlap(a: &HashSet<String>, b: &HashSet<String>) -> bool {
+    let shorter = a.len().min(b.len());
+    if shorter == 0 {
+        return a.is_empty() && b.is_empty();
+    }
+    a.intersection(b).count() * 2 >= shorter
+}
+
+/// Replaces transaction `txn_id` with one child per `(category, amount)` part,
+/// e.g. a supermarket charge split into groceries and household.
+///
+/// The parts must be positive and add up to the original amount (to the
+/// cent). Each child is a copy of the original (same date, accounts and
+/// currency) with its own category, amount, a derived `txn_id` and
+/// `split_parent` set to the original `txn_id`. The children take the
+/// original's place in the list.
+pub fn split_transaction(database: &mut Value, txn_id: &str, parts: &[(&str, f64)]) -> Result<()> {
+    let arr = database
+        .get_mut("transactions")
+        .and_then(|v| v.as_array_mut())
+        .ok_or_else(|| anyhow!("database.json missing 'transactions' array"))?;
+
+    let idx = arr
+        .iter()
+        .position(|txn| txn.get("txn_id").and_then(|v| v.as_str()) == Some(txn_id))
+        .ok_or_else(|| anyhow!("Unknown txn_id: {}", txn_id))?;
+
+    if parts.len() < 2 {
+        return Err(anyhow!("A split needs at least two parts"));
+    }
+    if let Some((category, amount)) = parts.iter().find(|(_, amount)| *amount <= 0.0) {
+        return Err(anyhow!(
+            "Split part '{}' has non-positive amount {}",
+            category,
+            amount
+        ));
+    }
+
+    let original = arr[idx].clone();
+    let original_amount = original
+        .get("amount")
+        .and_then(|v| v.as_f64())
+        .ok_or_else(|| anyhow!("Transaction {} has no numeric amount", txn_id))?;
+    let parts_total: f64 = parts.iter().map(|(_, amount)| amount).sum();
+    if (round_money(parts_total) - round_money(original_amount)).abs() >= 0.005 {
+        return Err(anyhow!(
+            "Split parts of {} add up to {:.2}, expected {:.2}",
+            txn_id,
+            parts_total,
+            original_amount
+        ));
+    }
+
+    let children: Vec<Value> = parts
+        .iter()
+        .enumerate()
+        .map(|(part_idx, (category, amount))| {
+            let mut child = original.clone();
+            child["txn_id"] =
+                Value::String(compute_txn_id("SPLIT", &[txn_id, &part_idx.to_string()]));
+            child["category"] = Value::String(category.to_string());
+            child["amount"] = serde_json::json!(round_money(*amount));
+            child["split_parent"] = Value::String(txn_id.to_string());
+            child
+        })
+        .collect();
+
+    log_transaction_removed("split_transaction", &original);
+    for child in &children {
+        log_transaction_added(child);
+    }
+    arr.splice(idx..=idx, children);
+
+    Ok(())
+}
+
+/// One pair of transactions merged by [`link_cross_account_transfers`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferLink {
+    /// The outgoing side, kept and rewritten into the transfer.
+    pub outflow_txn_id: String,
+    /// The incoming side, removed from the database.
+    pub inflow_txn_id: String,
+    pub from_account_id: String,
+    pub to_account_id: String,
+    pub amount: f64,
+}
+
+/// Links transfers between two of the user's accounts that were imported
+/// from separate statements, e.g. an SEB expense and a Revolut top-up.
+///
+/// An outflow (real account → system account) and an inflow (system account
+/// → a different real account) are linked when they share the `currency`,
+/// their amounts differ by at most `tolerance` and their dates are at most
+/// `window_days` apart. Each outflow is paired with the closest unmatched
+/// inflow. The outflow is rewritten to go from its account to the inflow's
+/// account with type `internal_transfer` and a `linked_txn_id` pointing to
+/// the inflow, which is removed so the transfer is only counted once. Later
+/// merges skip the inflow's `txn_id`, so re-importing its statement does not
+/// bring it back.
+pub fn link_cross_account_transfers(
+    database: &mut Value,
+    window_days: i64,
+    tolerance: f64,
+) -> Result<Vec<TransferLink>> {
+    let arr = database
+        .get_mut("transactions")
+        .and_then(|v| v.as_array_mut())
+        .ok_or_else(|| anyhow!("database.json missing 'transactions' array"))?;
+
+    let legs: Vec<Option<TransferLeg>> = arr.iter().map(TransferLeg::from_txn).collect();
+
+    let mut used = vec![false; arr.len()];
+    let mut links = Vec::new();
+    let mut removed = HashSet::new();
+    for (out_idx, out_leg) in legs.iter().enumerate() {
+        let Some(out_leg) = out_leg.as_ref().filter(|leg| !leg.inflow) else {
+            continue;
+        };
+
+        let best = legs
+            .iter()
+            .enumerate()
+            .filter(|(in_idx, _)| !used[*in_idx])
+            .filter_map(|(in_idx, leg)| Some((in_idx, leg.as_ref()?)))
+            .filter(|(_, in_leg)| {
+                in_leg.inflow
+                    && in_leg.account_id != out_leg.account_id
+                    && in_leg.currency == out_leg.currency
+                    && (in_leg.amount - out_leg.amount).abs() <= tolerance + 1e-9
+                    && (in_leg.date - out_leg.date).num_days().abs() <= window_days
+            })
+            .min_by_key(|(in_idx, in_leg)| {
+                ((in_leg.date - out_leg.date).num_days().abs(), *in_idx)
+            });
+        let Some((in_idx, in_leg)) = best else {
+            continue;
+        };
+
+        used[out_idx] = true;
+        used[in_idx] = true;
+        removed.insert(in_idx);
+
+        let inflow_txn_id = txn_id_of(&arr[in_idx]);
+        let outflow = &mut arr[out_idx];
+        outflow["to_account_id"] = Value::String(in_leg.account_id.clone());
+        outflow["type"] = Value::String("internal_transfer".to_string());
+        outflow["linked_txn_id"] = Value::String(inflow_txn_id.clone());
+
+        links.push(TransferLink {
+            outflow_txn_id: txn_id_of(outflow),
+            inflow_txn_id,
+            from_account_id: out_leg.account_id.clone(),
+            to_account_id: in_leg.account_id.clone(),
+            amount: out_leg.amount,
+        });
+    }
+
+    let mut idx = 0usize;
+    arr.retain(|txn| {
+        let keep = !removed.contains(&idx);
+        if !keep {
+            log_transaction_removed("link_cross_account_transfer", txn);
+        }
+        idx += 1;
+        keep
+    });
+
+    Ok(links)
+}
+
+/// The side of a possible cross-account transfer seen from one statement.
+struct TransferLeg {
+    /// The user's own account on this side of the transfer.
+    account_id: String,
+    inflow: bool,
+    date: NaiveDate,
+    amount: f64,
+    currency: String,
+}
+
+impl TransferLeg {
+    fn from_txn(txn: &Value) -> Option<Self> {
+        let obj = txn.as_object()?;
+        if obj.get("projected").and_then(|v| v.as_bool()) == Some(true) {
+            return None;
+        }
+
+        let from = obj.get("from_account_id")?.as_str()?;
+        let to = obj.get("to_account_id")?.as_str()?;
+        let (account_id, inflow) = match (is_counterparty(from), is_counterparty(to)) {
+            (false, true) => (from, false),
+            (true, false) => (to, true),
+            _ => return None,
+        };
+
+        Some(Self {
+            account_id: account_id.to_string(),
+            inflow,
+            date: NaiveDate::parse_from_str(obj.get("date")?.as_str()?.get(..10)?, "%Y-%m-%d")
+                .ok()?,
+            amount: obj.get("amount")?.as_f64()?.abs(),
+            currency: obj.get("currency")?.as_str()?.trim().to_uppercase(),
+        })
+    }
+}
+
+/// System accounts standing in for the other side of a one-sided import.
+/// `FX_EXCHANGE` legs belong to currency conversions, not transfers.
+fn is_counterparty(account_id: &str) -> bool {
+    account_id != "FX_EXCHANGE" && SYSTEM_ACCOUNT_IDS.contains(&account_id)
+}
+
+fn txn_id_of(txn: &Value) -> String {
+    txn.get("txn_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
 /// Ensure a transaction contains "description-en" and place it right after "description".
 fn ensure_description_en_position(txn: &mut Value) {
     let Some(obj) = txn.as_object_mut() else {
@@ -326,6 +806,77 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn compute_txn_id_is_stable_and_canonicalizes_whitespace() {
+        let a = compute_txn_id(
+            "SEB",
+            &["SEB_CURRENT", "2024-01-05", "-12.50", "ICA  Maxi "],
+        );
+        let b = compute_txn_id("SEB", &[" SEB_CURRENT", "2024-01-05", "-12.50", "ICA Maxi"]);
+
+        assert_eq!(a, b);
+        assert!(a.starts_with("SEB-"));
+        assert_eq!(a.len(), "SEB-".len() + 32);
+    }
+
+    #[test]
+    fn compute_txn_id_does_not_collide_across_prefixes_or_field_boundaries() {
+        let fields = ["ACC", "2024-01-05", "10.00", "Coffee"];
+        let ofx = compute_txn_id("OFX", &fields);
+        let camt = compute_txn_id("CAMT", &fields);
+
+        assert_ne!(ofx, camt);
+        assert_eq!(ofx[4..].len(), camt[5..].len());
+        assert_ne!(
+            compute_txn_id("OFX", &["a", "bc"]),
+            compute_txn_id("OFX", &["ab", "c"])
+        );
+    }
+
+    #[test]
+    fn reimport_rekeys_ids_from_before_compute_txn_id() {
+        let row = |txn_id: &str, description: &str| {
+            json!({
+                "txn_id": txn_id, "date": "2024-01-05", "from_account_id": "SEB_CURRENT",
+                "to_account_id": "EXTERNAL_PAYEE", "amount": 12.5, "currency": "SEK",
+                "description": description, "category": "uncategorized"
+            })
+        };
+        let mut stored = row("SEB-0123456789abcdef01234567", "ICA Maxi");
+        stored["category"] = json!("groceries");
+        let database = json!({
+            "transactions": [stored, row("IBKR-FEE-89abcdef0123456789abcdef", "ICA Maxi")]
+        });
+
+        let new_id = compute_txn_id("SEB", &["SEB_CURRENT", "2024-01-05", "ICA Maxi"]);
+        let other_id = compute_txn_id("SEB", &["SEB_CURRENT", "2024-01-05", "Pressbyrån"]);
+        let (merged, stats) = merge_transactions_with_mode(
+            database.clone(),
+            vec![row(&new_id, "ICA Maxi"), row(&other_id, "Pressbyrån")],
+            MergeMode::SkipExisting,
+        )
+        .unwrap();
+
+        assert_eq!(stats.added, 1);
+        assert_eq!(stats.skipped, 1);
+        let txns = merged["transactions"].as_array().unwrap();
+        assert_eq!(txns.len(), 3);
+        assert_eq!(txns[0]["txn_id"], new_id.as_str());
+        assert_eq!(txns[0]["category"], "groceries");
+        // A legacy id with another prefix is left alone.
+        assert_eq!(txns[1]["txn_id"], "IBKR-FEE-89abcdef0123456789abcdef");
+
+        // Importing the same rows again is a no-op.
+        let (again, stats) = merge_transactions_with_mode(
+            merged.clone(),
+            vec![row(&new_id, "ICA Maxi"), row(&other_id, "Pressbyrån")],
+            MergeMode::SkipExisting,
+        )
+        .unwrap();
+        assert_eq!(stats.added, 0);
+        assert_eq!(again, merged);
+    }
+
     #[test]
     fn test_merge_with_no_duplicates() {
         let database = json!({
@@ -424,6 +975,76 @@ mod tests {
         assert_eq!(txns.len(), 3); // Should still be 3, not 4
     }
 
+    fn corrected_statement() -> (Value, Vec<Value>) {
+        let database = json!({
+            "transactions": [
+                {"txn_id": "TXN001", "amount": 100.0, "description": "Typo", "description-en": "Kept", "category": "uncategorized"}
+            ]
+        });
+        let new_txns = vec![
+            json!({"txn_id": "TXN001", "amount": 100.0, "description": "Fixed", "description-en": "", "category": "groceries"}),
+            json!({"txn_id": "TXN002", "amount": 50.0, "description": "New"}),
+        ];
+        (database, new_txns)
+    }
+
+    #[test]
+    fn test_merge_with_mode_skip_existing() {
+        let (database, new_txns) = corrected_statement();
+        let (merged, stats) =
+            merge_transactions_with_mode(database, new_txns, MergeMode::SkipExisting).unwrap();
+
+        assert_eq!((stats.added, stats.skipped, stats.updated), (1, 1, 0));
+        assert_eq!(merged["transactions"][0]["description"], "Typo");
+    }
+
+    #[test]
+    fn test_merge_with_mode_update_existing() {
+        let (database, new_txns) = corrected_statement();
+        let (merged, stats) =
+            merge_transactions_with_mode(database, new_txns, MergeMode::UpdateExisting).unwrap();
+
+        assert_eq!((stats.added, stats.skipped, stats.updated), (1, 0, 1));
+        let txns = merged["transactions"].as_array().unwrap();
+        assert_eq!(txns.len(), 2);
+        assert_eq!(txns[0]["description"], "Fixed");
+        assert_eq!(txns[0]["category"], "groceries");
+        assert_eq!(txns[0]["description-en"], "Kept");
+    }
+
+    #[test]
+    fn test_merge_with_mode_update_existing_keeps_user_category() {
+        let database = json!({
+            "transactions": [
+                {"txn_id": "TXN001", "amount": 100.0, "description": "Typo", "category": "groceries"}
+            ]
+        });
+        let new_txns = vec![
+            json!({"txn_id": "TXN001", "amount": 100.0, "description": "Fixed", "description-en": "", "category": "uncategorized"}),
+        ];
+
+        let (merged, stats) =
+            merge_transactions_with_mode(database, new_txns, MergeMode::UpdateExisting).unwrap();
+
+        assert_eq!(stats.updated, 1);
+        let txn = &merged["transactions"][0];
+        assert_eq!(txn["description"], "Fixed");
+        assert_eq!(txn["category"], "groceries");
+        assert_eq!(txn["description-en"], "");
+    }
+
+    #[test]
+    fn test_merge_with_mode_replace_all() {
+        let (database, new_txns) = corrected_statement();
+        let (merged, stats) =
+            merge_transactions_with_mode(database, new_txns, MergeMode::ReplaceAll).unwrap();
+
+        assert_eq!((stats.added, stats.skipped, stats.updated), (1, 0, 1));
+        let txns = merged["transactions"].as_array().unwrap();
+        assert_eq!(txns[0]["description"], "Fixed");
+        assert_eq!(txns[0]["description-en"], "");
+    }
+
     #[test]
     fn test_find_duplicate_txn_ids() {
         let database = json!({
@@ -503,6 +1124,24 @@ mod tests {
         assert_eq!(arr[1].get("txn_id").unwrap().as_str().unwrap(), "A2");
     }
 
+    #[test]
+    fn test_find_near_duplicates() {
+        let txns = vec![
+            // Exact match across two sources.
+            json!({"txn_id": "REV-1", "date": "2026-02-03", "amount": 129.0, "currency": "SEK", "description": "Spotify AB"}),
+            json!({"txn_id": "SEB-1", "date": "2026-02-03", "amount": 129.0, "currency": "SEK", "description": "Spotify AB"}),
+            // Same payment booked two days later with a longer description.
+            json!({"txn_id": "REV-2", "date": "2026-02-10", "amount": 649.9, "currency": "SEK", "description": "Circle K Solna"}),
+            json!({"txn_id": "CARPAY-2", "date": "2026-02-12", "amount": 649.9, "currency": "sek", "description": "CIRCLE K SOLNA [Kostnadsuppföljning] card=1234"}),
+            // Same amount, unrelated merchant.
+            json!({"txn_id": "REV-3", "date": "2026-02-11", "amount": 649.9, "currency": "SEK", "description": "ICA Maxi"}),
+            // Same description, outside the window.
+            json!({"txn_id": "SEB-4", "date": "2026-03-03", "amount": 129.0, "currency": "SEK", "description": "Spotify AB"}),
+        ];
+
+        assert_eq!(find_near_duplicates(&txns, 3), vec![(0, 1), (2, 3)]);
+    }
+
     #[test]
     fn test_build_transaction_rounds_amount_half_down_to_2_decimals() {
         let txn = build_transaction(&TransactionInput {
@@ -543,4 +1182,152 @@ mod tests {
         let arr = merged.get("transactions").unwrap().as_array().unwrap();
         assert_eq!(arr[0].get("amount").and_then(|v| v.as_f64()), Some(12.34));
     }
+
+    fn transfer_db() -> Value {
+        json!({
+            "transactions": [
+                {
+                    "txn_id": "SEB-1",
+                    "date": "2024-03-01",
+                    "from_account_id": "SEB_CURRENT",
+                    "to_account_id": "EXTERNAL_PAYEE",
+                    "type": "expense",
+                    "amount": 500.0,
+                    "currency": "SEK"
+                },
+                {
+                    "txn_id": "REV-1",
+                    "date": "2024-03-02",
+                    "from_account_id": "EXTERNAL_PAYER",
+                    "to_account_id": "REVOLUT_CURRENT",
+                    "type": "income",
+                    "amount": 500.0,
+                    "currency": "SEK"
+                },
+                {
+                    "txn_id": "REV-2",
+                    "date": "2024-03-20",
+                    "from_account_id": "EXTERNAL_PAYER",
+                    "to_account_id": "REVOLUT_CURRENT",
+                    "type": "income",
+                    "amount": 500.0,
+                    "currency": "SEK"
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn link_cross_account_transfers_merges_matching_pair() {
+        let mut db = transfer_db();
+
+        let links = link_cross_account_transfers(&mut db, 3, 0.01).unwrap();
+
+        assert_eq!(
+            links,
+            vec![TransferLink {
+                outflow_txn_id: "SEB-1".to_string(),
+                inflow_txn_id: "REV-1".to_string(),
+                from_account_id: "SEB_CURRENT".to_string(),
+                to_account_id: "REVOLUT_CURRENT".to_string(),
+                amount: 500.0,
+            }]
+        );
+        let arr = db["transactions"].as_array().unwrap();
+        assert_eq!(arr.len(), 2);
+        assert_eq!(arr[0]["to_account_id"], json!("REVOLUT_CURRENT"));
+        assert_eq!(arr[0]["type"], json!("internal_transfer"));
+        assert_eq!(arr[0]["linked_txn_id"], json!("REV-1"));
+        assert_eq!(arr[1]["txn_id"], json!("REV-2"));
+    }
+
+    #[test]
+    fn link_cross_account_transfers_survives_reimport() {
+        let mut db = transfer_db();
+        let reimport: Vec<Value> = db["transactions"].as_array().unwrap()[1..].to_vec();
+        link_cross_account_transfers(&mut db, 3, 0.01).unwrap();
+
+        let (merged, stats) = merge_transactions_with_deduplication(db, reimport).unwrap();
+
+        assert_eq!((stats.added, stats.skipped), (0, 2));
+        let arr = merged["transactions"].as_array().unwrap();
+        assert_eq!(arr.len(), 2);
+        assert!(arr.iter().all(|txn| txn["txn_id"] != json!("REV-1")));
+    }
+
+    #[test]
+    fn link_cross_account_transfers_ignores_near_miss() {
+        let mut db = transfer_db();
+        // Outside the tolerance: 500.00 vs 499.50.
+        db["transactions"][1]["amount"] = json!(499.5);
+        let before = db.clone();
+
+        let links = link_cross_account_transfers(&mut db, 3, 0.01).unwrap();
+
+        // REV-2 has the right amount but is 19 days away.
+        assert!(links.is_empty());
+        assert_eq!(db, before);
+    }
+
+    fn split_db() -> Value {
+        json!({
+            "transactions": [
+                {"txn_id": "BEFORE", "date": "2024-03-01", "amount": 5.0},
+                {"txn_id": "ICA-1", "date": "2024-03-02", "from_account_id": "SEB_CHECKING",
+                 "to_account_id": "EXTERNAL_PAYEE", "type": "expense",
+                 "category": "uncategorized", "amount": 100.0, "currency": "SEK"},
+                {"txn_id": "AFTER", "date": "2024-03-03", "amount": 7.0}
+            ]
+        })
+    }
+
+    #[test]
+    fn split_transaction_replaces_original_with_children() {
+        let mut db = split_db();
+
+        split_transaction(
+            &mut db,
+            "ICA-1",
+            &[("groceries", 70.25), ("household", 29.75)],
+        )
+        .unwrap();
+
+        let arr = db["transactions"].as_array().unwrap();
+        assert_eq!(arr.len(), 4);
+        assert_eq!(arr[0]["txn_id"], "BEFORE");
+        assert_eq!(arr[3]["txn_id"], "AFTER");
+        for (child, (category, amount)) in arr[1..3]
+            .iter()
+            .zip([("groceries", 70.25), ("household", 29.75)])
+        {
+            assert_eq!(child["split_parent"], "ICA-1");
+            assert_eq!(child["category"], category);
+            assert_eq!(child["amount"], amount);
+            assert_eq!(child["date"], "2024-03-02");
+            assert_eq!(child["from_account_id"], "SEB_CHECKING");
+            assert!(child["txn_id"].as_str().unwrap().starts_with("SPLIT-"));
+        }
+        assert_ne!(arr[1]["txn_id"], arr[2]["txn_id"]);
+
+        // Re-importing the original does not undo the split.
+        let original = split_db()["transactions"][1].clone();
+        let (merged, stats) = merge_transactions_with_deduplication(db, vec![original]).unwrap();
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(merged["transactions"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn split_transaction_rejects_sum_mismatch() {
+        let mut db = split_db();
+
+        let err = split_transaction(
+            &mut db,
+            "ICA-1",
+            &[("groceries", 70.0), ("household", 20.0)],
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("add up to 90.00, expected 100.00"));
+        assert_eq!(db, split_db());
+    }
 }